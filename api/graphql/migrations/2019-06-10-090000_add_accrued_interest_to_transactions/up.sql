@@ -0,0 +1,2 @@
+ALTER TABLE transactions
+ADD COLUMN accrued_interest NUMERIC NOT NULL DEFAULT 0;
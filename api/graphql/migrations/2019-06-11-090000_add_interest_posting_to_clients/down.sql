@@ -0,0 +1,2 @@
+ALTER TABLE clients
+DROP COLUMN interest_posting;
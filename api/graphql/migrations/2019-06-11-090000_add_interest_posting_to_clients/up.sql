@@ -0,0 +1,2 @@
+ALTER TABLE clients
+ADD COLUMN interest_posting VARCHAR NOT NULL DEFAULT 'DAILY';
@@ -0,0 +1,2 @@
+ALTER TABLE accounts
+ADD COLUMN compounding VARCHAR NOT NULL DEFAULT 'DAILY';
@@ -0,0 +1,15 @@
+CREATE TABLE interest_rates (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  account_id INTEGER NOT NULL REFERENCES accounts (id),
+  effective_from TIMESTAMP NOT NULL,
+  changed_by_user_id INTEGER REFERENCES users (id),
+  old_yearly_interest NUMERIC,
+  new_yearly_interest NUMERIC NOT NULL
+);
+
+CREATE INDEX index_interest_rates_on_account_id ON interest_rates (account_id);
+
+-- The current rates have been in effect since the accounts were created
+INSERT INTO interest_rates (account_id, effective_from, new_yearly_interest)
+SELECT id, created_at, yearly_interest FROM accounts;
@@ -0,0 +1,9 @@
+CREATE TABLE interest_tiers (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  account_id INTEGER NOT NULL REFERENCES accounts (id),
+  from_balance MONEY NOT NULL,
+  yearly_interest NUMERIC NOT NULL
+);
+
+CREATE UNIQUE INDEX index_interest_tiers_on_account_id_and_from_balance ON interest_tiers (account_id, from_balance);
@@ -0,0 +1,2 @@
+ALTER TABLE transactions
+ADD COLUMN linked_transaction_id INTEGER REFERENCES transactions (id);
@@ -0,0 +1,63 @@
+-- Every money movement is a journal entry whose postings add up to zero.
+-- A posting without an account belongs to the client's family bank.
+CREATE TABLE journal_entries (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  client_id INTEGER NOT NULL REFERENCES clients (id),
+  kind VARCHAR NOT NULL
+);
+
+CREATE INDEX index_journal_entries_on_client_id ON journal_entries (client_id);
+
+CREATE TABLE postings (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  journal_entry_id INTEGER NOT NULL REFERENCES journal_entries (id),
+  account_id INTEGER REFERENCES accounts (id),
+  transaction_id INTEGER REFERENCES transactions (id),
+  amount MONEY NOT NULL
+);
+
+CREATE INDEX index_postings_on_journal_entry_id ON postings (journal_entry_id);
+CREATE INDEX index_postings_on_account_id ON postings (account_id);
+
+-- Move the existing transactions into the journal.
+-- Both legs of a transfer share one entry, everything else is against the family bank.
+DO $$
+DECLARE
+  t RECORD;
+  entry_id INTEGER;
+  change MONEY;
+BEGIN
+  FOR t IN
+    SELECT transactions.*, users.client_id
+    FROM transactions
+    JOIN accounts ON accounts.id = transactions.account_id
+    JOIN users ON users.id = accounts.user_id
+    ORDER BY transactions.id
+  LOOP
+    IF t.kind = 'WITHDRAWAL' THEN
+      change := t.amount * -1;
+    ELSE
+      change := t.amount;
+    END IF;
+
+    IF t.kind = 'TRANSFER' AND t.linked_transaction_id < t.id THEN
+      SELECT postings.journal_entry_id INTO entry_id
+      FROM postings
+      WHERE postings.transaction_id = t.linked_transaction_id;
+    ELSE
+      INSERT INTO journal_entries (created_at, client_id, kind)
+      VALUES (t.created_at, t.client_id, t.kind)
+      RETURNING id INTO entry_id;
+    END IF;
+
+    INSERT INTO postings (created_at, journal_entry_id, account_id, transaction_id, amount)
+    VALUES (t.created_at, entry_id, t.account_id, t.id, change);
+
+    IF t.kind <> 'TRANSFER' THEN
+      INSERT INTO postings (created_at, journal_entry_id, account_id, transaction_id, amount)
+      VALUES (t.created_at, entry_id, NULL, NULL, change * -1);
+    END IF;
+  END LOOP;
+END $$;
@@ -0,0 +1,2 @@
+DROP TABLE scheduled_transaction_runs;
+DROP TABLE scheduled_transactions;
@@ -0,0 +1,28 @@
+-- Deposits that run by themselves, e.g. a weekly allowance.
+-- next_run_at is NULL once a one-off rule has run.
+CREATE TABLE scheduled_transactions (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  account_id INTEGER NOT NULL REFERENCES accounts (id),
+  amount MONEY NOT NULL,
+  frequency VARCHAR NOT NULL,
+  day_of_month INTEGER,
+  starts_at TIMESTAMP NOT NULL,
+  next_run_at TIMESTAMP,
+  archived_at TIMESTAMP
+);
+
+CREATE INDEX index_scheduled_transactions_on_account_id ON scheduled_transactions (account_id);
+CREATE INDEX index_scheduled_transactions_on_next_run_at ON scheduled_transactions (next_run_at);
+
+-- One row for each execution, the unique index stops a rule running twice for the same date
+CREATE TABLE scheduled_transaction_runs (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  scheduled_transaction_id INTEGER NOT NULL REFERENCES scheduled_transactions (id),
+  run_for TIMESTAMP NOT NULL,
+  transaction_id INTEGER REFERENCES transactions (id),
+  error VARCHAR
+);
+
+CREATE UNIQUE INDEX index_scheduled_transaction_runs_on_rule_and_run_for ON scheduled_transaction_runs (scheduled_transaction_id, run_for);
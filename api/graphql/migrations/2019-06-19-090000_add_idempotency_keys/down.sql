@@ -0,0 +1,5 @@
+ALTER TABLE transaction_requests
+DROP COLUMN idempotency_key;
+
+ALTER TABLE transactions
+DROP COLUMN idempotency_key;
@@ -0,0 +1,9 @@
+ALTER TABLE transactions
+ADD COLUMN idempotency_key VARCHAR;
+
+ALTER TABLE transaction_requests
+ADD COLUMN idempotency_key VARCHAR;
+
+-- A key can be used once per account, rows without a key don't conflict
+CREATE UNIQUE INDEX index_transactions_on_account_id_and_idempotency_key ON transactions (account_id, idempotency_key);
+CREATE UNIQUE INDEX index_transaction_requests_on_account_id_and_idempotency_key ON transaction_requests (account_id, idempotency_key);
@@ -0,0 +1,9 @@
+ALTER TABLE transaction_requests
+DROP COLUMN memo,
+DROP COLUMN category_id;
+
+ALTER TABLE transactions
+DROP COLUMN memo,
+DROP COLUMN category_id;
+
+DROP TABLE categories;
@@ -0,0 +1,19 @@
+-- Labels a family picks for its money, e.g. "birthday money" or "toys"
+CREATE TABLE categories (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  client_id INTEGER NOT NULL REFERENCES clients (id),
+  name VARCHAR NOT NULL
+);
+
+CREATE UNIQUE INDEX index_categories_on_client_id_and_name ON categories (client_id, name);
+
+ALTER TABLE transactions
+ADD COLUMN memo VARCHAR,
+ADD COLUMN category_id INTEGER REFERENCES categories (id);
+
+ALTER TABLE transaction_requests
+ADD COLUMN memo VARCHAR,
+ADD COLUMN category_id INTEGER REFERENCES categories (id);
+
+CREATE INDEX index_transactions_on_category_id ON transactions (category_id);
@@ -0,0 +1,2 @@
+ALTER TABLE transaction_requests
+DROP COLUMN transaction_id;
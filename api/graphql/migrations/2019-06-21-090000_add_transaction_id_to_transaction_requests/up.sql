@@ -0,0 +1,3 @@
+-- The deposit or withdrawal made when the request was approved
+ALTER TABLE transaction_requests
+ADD COLUMN transaction_id INTEGER REFERENCES transactions (id);
@@ -0,0 +1,2 @@
+ALTER TABLE clients
+DROP COLUMN request_expiry_days;
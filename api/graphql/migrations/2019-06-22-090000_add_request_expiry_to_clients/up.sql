@@ -0,0 +1,3 @@
+-- Pending transaction requests older than this are expired by the jobs
+ALTER TABLE clients
+ADD COLUMN request_expiry_days INTEGER NOT NULL DEFAULT 14;
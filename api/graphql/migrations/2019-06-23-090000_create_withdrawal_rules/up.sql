@@ -0,0 +1,10 @@
+CREATE TABLE withdrawal_rules (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  account_id INTEGER NOT NULL REFERENCES accounts (id),
+  kind VARCHAR NOT NULL,
+  amount MONEY,
+  days INTEGER
+);
+
+CREATE UNIQUE INDEX index_withdrawal_rules_on_account_id_and_kind ON withdrawal_rules (account_id, kind);
@@ -0,0 +1 @@
+DROP TABLE matching_rules;
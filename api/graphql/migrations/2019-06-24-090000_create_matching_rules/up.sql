@@ -0,0 +1,11 @@
+CREATE TABLE matching_rules (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  account_id INTEGER NOT NULL REFERENCES accounts (id),
+  percentage NUMERIC NOT NULL,
+  cap MONEY,
+  period VARCHAR NOT NULL DEFAULT 'MONTHLY',
+  sources VARCHAR[] NOT NULL
+);
+
+CREATE UNIQUE INDEX index_matching_rules_on_account_id ON matching_rules (account_id);
@@ -0,0 +1,9 @@
+CREATE TABLE split_rules (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  user_id INTEGER NOT NULL REFERENCES users (id),
+  account_id INTEGER NOT NULL REFERENCES accounts (id),
+  percentage NUMERIC NOT NULL
+);
+
+CREATE UNIQUE INDEX index_split_rules_on_user_id_and_account_id ON split_rules (user_id, account_id);
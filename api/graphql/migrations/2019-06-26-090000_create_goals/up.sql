@@ -0,0 +1,12 @@
+CREATE TABLE goals (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL,
+  account_id INTEGER NOT NULL REFERENCES accounts (id),
+  name VARCHAR NOT NULL,
+  target MONEY NOT NULL,
+  target_date TIMESTAMP,
+  image_url VARCHAR,
+  reached_at TIMESTAMP
+);
+
+CREATE INDEX index_goals_on_account_id ON goals (account_id);
@@ -0,0 +1,11 @@
+ALTER TABLE accounts
+ADD COLUMN archived_at TIMESTAMP;
+
+-- Accounts archived before this are taken as archived with their last transaction,
+-- which is the final interest payment when there was any.
+UPDATE accounts
+SET archived_at = COALESCE(
+	(SELECT MAX(created_at) FROM transactions WHERE transactions.account_id = accounts.id),
+	accounts.created_at
+)
+WHERE state = 'ARCHIVED';
@@ -0,0 +1,452 @@
+use super::calculate_interest::{self, Rate, Tier};
+use crate::models::{
+	account::{Account, Compounding},
+	cents::Cents,
+	fixed_point::FixedPoint,
+	interest_rate::InterestRate,
+	interest_tier::InterestTier,
+	posting::Posting,
+	transaction::Transaction,
+};
+use chrono::{Duration, NaiveDateTime};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+#[derive(Debug, PartialEq)]
+pub struct Accrual {
+	// Whole cents credited by the compounding periods that have completed
+	pub payable: Cents,
+	// Interest of the unfinished period and the fraction of a cent, carried forward
+	pub carried: FixedPoint,
+}
+
+// Interest earned since the previous transaction,
+// including the interest carried by that transaction.
+pub fn call(
+	conn: &PgConnection,
+	account: &Account,
+	previous_transaction: &Transaction,
+	now: NaiveDateTime,
+) -> Result<Accrual, Error> {
+	let previously_accrued = FixedPoint::from_big_decimal(&previous_transaction.accrued_interest)?;
+
+	if !account.kind.earns_interest() {
+		return Ok(Accrual {
+			payable: Cents(0),
+			carried: previously_accrued,
+		});
+	}
+
+	// The previous transaction is the last, so everything posted was posted by then
+	let earning_balance = earning_balance(conn, account)?;
+
+	let from = day_start(account, previous_transaction.created_at);
+
+	let rates = rates_since(conn, account, from)?;
+
+	let tiers = tiers(conn, account)?;
+
+	accrue(
+		account,
+		earning_balance,
+		previously_accrued,
+		&rates,
+		&tiers,
+		previous_transaction.created_at,
+		now,
+	)
+}
+
+// Interest `earning_balance` earns from a transaction made at `since` until `now`,
+// on top of what that transaction carried. `rates` must start by the day `since` falls in.
+// Compounding periods and days are counted from the day the account was opened,
+// and the day the transaction was made in earns on the balance it left.
+// The interest of a period is only credited, and earns interest, once the period completes.
+// Simple interest doesn't compound, so it is payable as soon as it makes a cent.
+// Archived accounts stop earning when they were archived.
+pub fn accrue(
+	account: &Account,
+	earning_balance: Cents,
+	previously_accrued: FixedPoint,
+	rates: &[Rate],
+	tiers: &[Tier],
+	since: NaiveDateTime,
+	now: NaiveDateTime,
+) -> Result<Accrual, Error> {
+	if !account.kind.earns_interest() {
+		return Ok(Accrual {
+			payable: Cents(0),
+			carried: previously_accrued,
+		});
+	}
+
+	let now = account.archived_at.map_or(now, |at| at.min(now));
+
+	let Cents(earning_balance) = earning_balance;
+
+	let from = day_start(account, since);
+
+	let mut accrued = previously_accrued;
+	let mut credited = 0;
+	let mut start = from;
+
+	while let Some(end) = account.compounding.period_end(account.created_at, start) {
+		if end > now {
+			break;
+		}
+
+		let balance = Cents(earning_balance + credited);
+
+		accrued = accrued.checked_add(earned(account, balance, rates, tiers, start, end)?)?;
+
+		let Cents(cents) = accrued.to_cents();
+
+		accrued = accrued.checked_sub(FixedPoint::from_cents(Cents(cents)))?;
+		credited += cents;
+		start = end;
+	}
+
+	let balance = Cents(earning_balance + credited);
+
+	accrued = accrued.checked_add(earned(account, balance, rates, tiers, start, now)?)?;
+
+	if account.compounding == Compounding::Simple {
+		let Cents(cents) = accrued.to_cents();
+
+		accrued = accrued.checked_sub(FixedPoint::from_cents(Cents(cents)))?;
+		credited += cents;
+	}
+
+	Ok(Accrual {
+		payable: Cents(credited),
+		carried: accrued,
+	})
+}
+
+// Tiers are blended at the balance the period starts with
+fn earned(
+	account: &Account,
+	balance: Cents,
+	rates: &[Rate],
+	tiers: &[Tier],
+	from: NaiveDateTime,
+	to: NaiveDateTime,
+) -> Result<FixedPoint, Error> {
+	let rates = rates
+		.iter()
+		.map(|rate| {
+			let yearly_interest =
+				calculate_interest::blended_rate(balance, &rate.yearly_interest, tiers)?;
+
+			Ok(Rate {
+				yearly_interest,
+				..rate.clone()
+			})
+		})
+		.collect::<Result<Vec<Rate>, Error>>()?;
+
+	calculate_interest::precise_with_rates(balance, &rates, account.compounding, from, to)
+}
+
+// The start of the day `at` falls in, days being counted from when the account was opened
+pub fn day_start(account: &Account, at: NaiveDateTime) -> NaiveDateTime {
+	let days = at.signed_duration_since(account.created_at).num_days();
+
+	let start = account.created_at + Duration::days(days);
+
+	if start > at {
+		start - Duration::days(1)
+	} else {
+		start
+	}
+}
+
+// Interest `balance` earns between `from` and `to` at the account's rates, without tiers.
+// Used to correct interest paid on a balance that turned out to be wrong.
+pub fn earned_on(
+	conn: &PgConnection,
+	account: &Account,
+	balance: Cents,
+	from: NaiveDateTime,
+	to: NaiveDateTime,
+) -> Result<FixedPoint, Error> {
+	if !account.kind.earns_interest() {
+		return Ok(FixedPoint::zero());
+	}
+
+	let rates = rates_since(conn, account, from)?;
+
+	calculate_interest::precise_with_rates(balance, &rates, account.compounding, from, to)
+}
+
+// The rates in effect from `from` onwards, oldest first
+pub fn rates_since(
+	conn: &PgConnection,
+	account: &Account,
+	from: NaiveDateTime,
+) -> Result<Vec<Rate>, Error> {
+	let history = InterestRate::find_by_account_id(&conn, account.id)?;
+
+	// The last change before `from`, otherwise the rate before the first change.
+	// Accounts without history have always had their current rate.
+	let initial = history
+		.iter()
+		.rev()
+		.find(|rate| rate.effective_from <= from)
+		.map(|rate| rate.new_yearly_interest.clone())
+		.or_else(|| {
+			history
+				.first()
+				.and_then(|rate| rate.old_yearly_interest.clone())
+		})
+		.unwrap_or_else(|| account.yearly_interest.clone());
+
+	let mut rates = vec![Rate {
+		starts_at:       from,
+		yearly_interest: initial,
+	}];
+
+	let changes = history
+		.into_iter()
+		.filter(|rate| rate.effective_from > from)
+		.map(|rate| {
+			Rate {
+				starts_at:       rate.effective_from,
+				yearly_interest: rate.new_yearly_interest,
+			}
+		});
+
+	rates.extend(changes);
+
+	Ok(rates)
+}
+
+pub fn tiers(conn: &PgConnection, account: &Account) -> Result<Vec<Tier>, Error> {
+	let tiers = InterestTier::find_by_account_id(&conn, account.id)?;
+
+	Ok(tiers.iter().map(Tier::from).collect())
+}
+
+// The part of the balance that earns interest, from what has been posted to the account.
+// With simple interest, interest already paid doesn't earn more interest.
+fn earning_balance(conn: &PgConnection, account: &Account) -> Result<Cents, Error> {
+	let posted = Posting::balance_by_account_id(&conn, account.id)?;
+
+	match account.compounding {
+		Compounding::Simple => {
+			let interest_paid = Transaction::total_interest_by_account_id(&conn, account.id)?;
+
+			let principal = posted - interest_paid;
+
+			Ok(principal.max(Cents(0)))
+		},
+		// A reversal can leave the balance below zero, that doesn't owe interest
+		_ => Ok(posted.max(Cents(0))),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{self, transaction::TransactionKind},
+		utils::tests,
+	};
+	use chrono::Duration;
+
+	#[test]
+	fn it_adds_the_previous_fraction() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100)
+				.balance(100)
+				.accrued_interest("0.99")
+				.save(conn);
+
+			let now = transaction.created_at + Duration::days(1);
+
+			let accrual = call(conn, &account, &transaction, now).unwrap();
+
+			assert_eq!(accrual.payable, Cents(1));
+			assert!(accrual.carried > FixedPoint::zero());
+		})
+	}
+
+	#[test]
+	fn it_carries_less_than_a_cent() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100)
+				.balance(100)
+				.save(conn);
+
+			let now = transaction.created_at + Duration::days(2);
+
+			let accrual = call(conn, &account, &transaction, now).unwrap();
+
+			assert_eq!(accrual.payable, Cents(0));
+			assert!(accrual.carried > FixedPoint::zero());
+			assert!(accrual.carried < FixedPoint::one());
+		})
+	}
+
+	#[test]
+	fn simple_interest_is_not_paid_on_interest() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let account = account.compounding(conn, Compounding::Simple);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(10_000)
+				.balance(10_000)
+				.save(conn);
+
+			let interest = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Interest)
+				.amount(10_000)
+				.balance(20_000)
+				.save(conn);
+
+			let now = interest.created_at + Duration::days(30);
+
+			let simple = call(conn, &account, &interest, now).unwrap();
+
+			let daily = account.compounding(conn, Compounding::Daily);
+
+			let compound = call(conn, &daily, &interest, now).unwrap();
+
+			assert!(simple.payable > Cents(0));
+			assert!(compound.payable > simple.payable);
+		})
+	}
+
+	#[test]
+	fn it_stops_when_the_account_is_archived() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.save(conn);
+
+			let archived_at = transaction.created_at + Duration::days(10);
+
+			let until_archived = call(conn, &account, &transaction, archived_at).unwrap();
+
+			let archived = account.archived_at(conn, archived_at);
+
+			let now = archived_at + Duration::days(20);
+
+			let accrual = call(conn, &archived, &transaction, now).unwrap();
+
+			assert_eq!(accrual, until_archived);
+		})
+	}
+
+	#[test]
+	fn it_uses_the_rate_in_effect_for_each_period() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.save(conn);
+
+			let now = transaction.created_at + Duration::days(20);
+
+			let before = call(conn, &account, &transaction, now).unwrap();
+
+			// The rate doubles half way
+			let _rate = models::interest_rate::factories::interest_rate_attrs(&account)
+				.effective_from(transaction.created_at + Duration::days(10))
+				.change(10.5, 21.0)
+				.save(conn);
+
+			let after = call(conn, &account, &transaction, now).unwrap();
+
+			assert!(after.payable > before.payable);
+		})
+	}
+
+	#[test]
+	fn it_pays_the_tier_rate_above_the_threshold() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.save(conn);
+
+			let now = transaction.created_at + Duration::days(30);
+
+			let untiered = call(conn, &account, &transaction, now).unwrap();
+
+			let _tier = models::interest_tier::factories::interest_tier_attrs(&account)
+				.from_balance(5_000)
+				.yearly_interest(1.0)
+				.save(conn);
+
+			let tiered = call(conn, &account, &transaction, now).unwrap();
+
+			assert!(tiered.payable > Cents(0));
+			assert!(tiered.payable < untiered.payable);
+		})
+	}
+
+	#[test]
+	fn monthly_interest_is_credited_when_the_month_ends() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let account = account.compounding(conn, Compounding::Monthly);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.save(conn);
+
+			let now = account.created_at + Duration::days(20);
+
+			let mid_month = call(conn, &account, &transaction, now).unwrap();
+
+			assert_eq!(mid_month.payable, Cents(0));
+			assert!(mid_month.carried > FixedPoint::one());
+
+			let now = account.created_at + Duration::days(30);
+
+			let month_end = call(conn, &account, &transaction, now).unwrap();
+
+			assert!(month_end.payable > Cents(0));
+		})
+	}
+
+	#[test]
+	fn periods_are_counted_from_the_open_date() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let account = account.compounding(conn, Compounding::Monthly);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.created_at(account.created_at + Duration::days(20))
+				.save(conn);
+
+			// The first month ends 10 days after the transaction
+			let now = account.created_at + Duration::days(31);
+
+			let accrual = call(conn, &account, &transaction, now).unwrap();
+
+			assert!(accrual.payable > Cents(0));
+		})
+	}
+}
@@ -0,0 +1,143 @@
+use super::{get_balance, pay_interest, rules};
+use crate::{
+	actions::{
+		emails::acknowledge_transfer,
+		transactions::transfer::{self, Transfer, TransferInput},
+	},
+	models::{
+		account::{Account, State},
+		schema as db,
+	},
+	utils::db_transaction,
+};
+use chrono::prelude::*;
+use diesel::{self, pg::PgConnection, prelude::*};
+use failure::Error;
+
+// Closes the account, paying the interest earned so far.
+// The remaining balance moves to `sweep_to_account_id` when given.
+pub fn call(
+	conn: &PgConnection,
+	account_id: i32,
+	sweep_to_account_id: Option<i32>,
+) -> Result<Account, Error> {
+	let (account, swept) = db_transaction::serializable(&conn, || {
+		let account = Account::lock(&conn, account_id)?;
+
+		rules::check_active(&account)?;
+
+		// Final interest
+		pay_interest::call(&conn, account_id)?;
+
+		let swept = match sweep_to_account_id {
+			Some(target_id) => sweep(&conn, account_id, target_id)?,
+			None => None,
+		};
+
+		let now = Utc::now().naive_utc();
+
+		let account = diesel::update(db::accounts::table.find(account_id))
+			.set((
+				db::accounts::state.eq(State::Archived),
+				db::accounts::archived_at.eq(now),
+			))
+			.get_result(conn)?;
+
+		Ok((account, swept))
+	})?;
+
+	// Send the transfer emails once it can't be rolled back
+	if let Some(transfer) = swept {
+		acknowledge_transfer::call(&conn, &transfer.from, &transfer.to)?;
+	}
+
+	Ok(account)
+}
+
+// The transfer of the balance, None when there is nothing to move
+fn sweep(conn: &PgConnection, account_id: i32, target_id: i32) -> Result<Option<Transfer>, Error> {
+	if target_id == account_id {
+		return Err(format_err!("Cannot sweep into the same account"));
+	}
+
+	let balance = get_balance::call(&conn, account_id)?;
+
+	if balance <= 0 {
+		return Ok(None);
+	}
+
+	if balance > i32::max_value() as i64 {
+		return Err(format_err!("Balance is too large to sweep"));
+	}
+
+	let cents = balance as i32;
+
+	let input = TransferInput {
+		from_account_id: account_id,
+		to_account_id:   target_id,
+		cents:           cents,
+	};
+
+	transfer::record(&conn, &input).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{models, utils::tests};
+
+	#[test]
+	fn it_archives_the_account() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let archived = call(conn, account.id, None).unwrap();
+
+			assert_eq!(archived.state, State::Archived);
+			assert!(archived.archived_at.is_some());
+
+			assert!(call(conn, account.id, None).is_err());
+		})
+	}
+
+	#[test]
+	fn it_sweeps_the_balance() {
+		tests::with_db(|conn| {
+			let (account, user, _) = tests::account(&conn);
+
+			let target = models::account::factories::account_attrs(&user).save(conn);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			call(conn, account.id, Some(target.id)).unwrap();
+
+			assert_eq!(get_balance::call(conn, account.id).unwrap(), 0);
+			assert_eq!(get_balance::call(conn, target.id).unwrap(), 300);
+		})
+	}
+
+	#[test]
+	fn it_doesnt_sweep_into_an_archived_account() {
+		tests::with_db(|conn| {
+			let (account, user, _) = tests::account(&conn);
+
+			let target = models::account::factories::account_attrs(&user)
+				.save(conn)
+				.state(conn, State::Archived);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			assert!(call(conn, account.id, Some(target.id)).is_err());
+
+			let account = Account::find(conn, account.id).unwrap();
+
+			assert_eq!(account.state, State::Active);
+		})
+	}
+}
@@ -25,6 +25,13 @@ pub fn can_access(
 	can_admin(conn, account_id, current_user)
 }
 
+// Only the account holder, not the admins
+pub fn is_holder(conn: &PgConnection, account_id: i32, current_user: &User) -> Result<bool, Error> {
+	let account = Account::find(&conn, account_id)?;
+
+	Ok(account.user_id == current_user.id)
+}
+
 pub fn can_admin(conn: &PgConnection, account_id: i32, current_user: &User) -> Result<bool, Error> {
 	// Ok if admin for this client
 	let account = Account::find(&conn, account_id)?;
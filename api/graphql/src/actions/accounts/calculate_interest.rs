@@ -1,48 +1,236 @@
-use crate::models::cents::Cents;
-use bigdecimal::{BigDecimal, ToPrimitive};
+use crate::models::{
+	cents::Cents, compounding::Compounding, fixed_point::FixedPoint, interest_tier::InterestTier,
+};
+use bigdecimal::BigDecimal;
 use chrono::prelude::*;
 use failure::Error;
 
+pub const DAYS_IN_YEAR: u64 = 365;
+
+// Interest is kept to this many decimal places of a cent.
+// This removes the tiny error left by the root and power steps,
+// so e.g. a full year at 20% is exactly 20% and not 19.999...%
+pub const INTEREST_DECIMALS: u32 = 9;
+
+// Calculates the interest earned by `balance` between `from` and `to`
+//
+// `yearly_interest` is an effective annual rate in percent.
+// With compounding the rate for each period is (1 + rate) ^ (period / 365),
+// so a full year pays the yearly rate whatever the compounding.
+// Within an unfinished period interest accrues simply.
+// Simple interest is rate * days / 365 and never compounds.
+// Only whole days earn interest.
+//
+// The calculation uses fixed-point arithmetic (see models::fixed_point),
+// so the same inputs always produce the same cents.
+// The result is truncated to whole cents.
 pub fn call(
 	balance: Cents,
 	yearly_interest: &BigDecimal,
+	compounding: Compounding,
 	from: NaiveDateTime,
 	to: NaiveDateTime,
 ) -> Result<Cents, Error> {
-	let rate: f64 = BigDecimal::to_f64(&yearly_interest)
-		.ok_or(format_err!("Failed to unwrap yearly interest"))?;
+	let interest = precise(balance, yearly_interest, compounding, from, to)?;
+
+	Ok(interest.to_cents())
+}
+
+// Same as `call` but keeps the fraction of a cent
+pub fn precise(
+	balance: Cents,
+	yearly_interest: &BigDecimal,
+	compounding: Compounding,
+	from: NaiveDateTime,
+	to: NaiveDateTime,
+) -> Result<FixedPoint, Error> {
+	let rates = [Rate {
+		starts_at:       from,
+		yearly_interest: yearly_interest.clone(),
+	}];
+
+	precise_with_rates(balance, &rates, compounding, from, to)
+}
+
+// A yearly interest that applies from `starts_at` until the next rate starts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rate {
+	pub starts_at:       NaiveDateTime,
+	pub yearly_interest: BigDecimal,
+}
+
+// Same as `precise` but the rate can change between `from` and `to`.
+// `rates` must be ordered by `starts_at` and the first one must start at or before `from`.
+// Days are counted from `from`, so splitting the period doesn't lose partial days.
+pub fn precise_with_rates(
+	balance: Cents,
+	rates: &[Rate],
+	compounding: Compounding,
+	from: NaiveDateTime,
+	to: NaiveDateTime,
+) -> Result<FixedPoint, Error> {
+	let days = to.signed_duration_since(from).num_days();
+
+	if days <= 0 {
+		return Ok(FixedPoint::zero());
+	}
+
+	match rates.first() {
+		Some(first) if first.starts_at <= from => (),
+		_ => return Err(format_err!("No interest rate at {}", from)),
+	}
+
+	let day_of = |at: NaiveDateTime| at.signed_duration_since(from).num_days().max(0).min(days);
+
+	let mut growth = FixedPoint::one();
+
+	for (index, rate) in rates.iter().enumerate() {
+		let start_day = day_of(rate.starts_at);
+		let end_day = rates
+			.get(index + 1)
+			.map(|next| day_of(next.starts_at))
+			.unwrap_or(days);
+
+		if end_day <= start_day {
+			continue;
+		}
+
+		let period_days = (end_day - start_day) as u64;
+		let rate = FixedPoint::from_percentage(&rate.yearly_interest)?;
+
+		growth = match compounding.period_in_days() {
+			// Compounded growth of each period multiplies
+			Some(period) => growth.checked_mul(compound_growth(rate, period_days, period)?)?,
+			// Simple interest of each period adds up
+			None => {
+				growth
+					.checked_add(simple_growth(rate, period_days)?)?
+					.checked_sub(FixedPoint::one())?
+			},
+		};
+	}
+
+	let principal = FixedPoint::from_cents(balance);
+
+	let interest = principal.checked_mul(growth.checked_sub(FixedPoint::one())?)?;
+
+	Ok(interest.round(INTEREST_DECIMALS))
+}
+
+// The part of the balance from `from_balance` up to the next tier earns `yearly_interest`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tier {
+	pub from_balance:    Cents,
+	pub yearly_interest: BigDecimal,
+}
+
+impl<'a> From<&'a InterestTier> for Tier {
+	fn from(tier: &InterestTier) -> Tier {
+		Tier {
+			from_balance:    tier.from_balance,
+			yearly_interest: tier.yearly_interest.clone(),
+		}
+	}
+}
+
+// The single rate that pays the same as the tiers on `balance`.
+// `base` applies below the first tier, `tiers` must be ordered by `from_balance`.
+// e.g. 20% base with a 5% tier from $50 gives 12.5% on $100
+pub fn blended_rate(
+	balance: Cents,
+	base: &BigDecimal,
+	tiers: &[Tier],
+) -> Result<BigDecimal, Error> {
+	let Cents(total) = balance;
 
-	let rate_perc = rate / 100.0;
+	if total <= 0 || tiers.is_empty() {
+		return Ok(base.clone());
+	}
+
+	let mut weighted = FixedPoint::zero();
+	let mut band_start = 0;
+	let mut band_rate = base.clone();
+
+	for tier in tiers {
+		let Cents(band_end) = tier.from_balance;
+
+		weighted = weighted.checked_add(band_interest(total, band_start, band_end, &band_rate)?)?;
+
+		band_start = band_end.max(band_start);
+		band_rate = tier.yearly_interest.clone();
+	}
+
+	weighted = weighted.checked_add(band_interest(total, band_start, total, &band_rate)?)?;
+
+	weighted
+		.checked_div(FixedPoint::from_int(total))?
+		.to_big_decimal()
+}
+
+// Cents of `balance` between `start` and `end`, weighted by `rate`
+fn band_interest(
+	balance: i64,
+	start: i64,
+	end: i64,
+	rate: &BigDecimal,
+) -> Result<FixedPoint, Error> {
+	let cents = end.min(balance) - start.min(balance);
 
-	let day_diff_duration = to.signed_duration_since(from);
-	let day_diff = day_diff_duration.num_days() as i32;
+	if cents <= 0 {
+		return Ok(FixedPoint::zero());
+	}
 
-	// println!("day_diff = {:?}", day_diff);
+	FixedPoint::from_int(cents).checked_mul(FixedPoint::from_big_decimal(rate)?)
+}
 
-	let daily_rate = ((1.0 + rate_perc).powf(1.0 / 365.0)) - 1.0;
+fn simple_growth(rate: FixedPoint, days: u64) -> Result<FixedPoint, Error> {
+	let interest = rate
+		.checked_mul(FixedPoint::from_int(days as i64))?
+		.checked_div(FixedPoint::from_int(DAYS_IN_YEAR as i64))?;
 
-	// println!("daily_rate = {:?}", daily_rate);
+	FixedPoint::one().checked_add(interest)
+}
 
-	let principal: f64 = match balance {
-		Cents(p) => p as f64,
-	};
+fn compound_growth(
+	rate: FixedPoint,
+	days: u64,
+	(numerator, denominator): (u64, u64),
+) -> Result<FixedPoint, Error> {
+	// Growth for one period is (1 + rate) ^ (numerator / (365 * denominator))
+	let degree = DAYS_IN_YEAR * denominator;
+	let divisor = gcd(numerator, degree);
 
-	// println!("principal = {:?}", principal);
+	let period_growth = FixedPoint::one()
+		.checked_add(rate)?
+		.checked_root(degree / divisor)?
+		.checked_pow(numerator / divisor)?;
 
-	let new_principal = principal * (1.0 + daily_rate).powi(day_diff);
+	let whole_periods = days * denominator / numerator;
+	let remainder = days * denominator % numerator;
 
-	// println!("new_principal = {:?}", new_principal);
+	let compounded = period_growth.checked_pow(whole_periods)?;
 
-	let interest = new_principal - principal;
+	// The unfinished period accrues simply
+	let partial = period_growth
+		.checked_sub(FixedPoint::one())?
+		.checked_mul(FixedPoint::from_int(remainder as i64))?
+		.checked_div(FixedPoint::from_int(numerator as i64))?;
+
+	compounded.checked_mul(FixedPoint::one().checked_add(partial)?)
+}
 
-	Ok(Cents(interest as i64))
+fn gcd(a: u64, b: u64) -> u64 {
+	if b == 0 {
+		a
+	} else {
+		gcd(b, a % b)
+	}
 }
 
 #[cfg(test)]
 mod test {
 	use super::*;
 	use bigdecimal::FromPrimitive;
-	use range_check::Contains;
 
 	#[test]
 	fn it_calculates_the_interest_at_30() {
@@ -52,7 +240,7 @@ mod test {
 		let a = NaiveDate::from_ymd(2016, 1, 1).and_hms(0, 0, 0);
 		let b = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
 
-		let Cents(interest) = call(initial_balance, &rate, a, b).unwrap();
+		let Cents(interest) = call(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
 
 		assert_eq!(interest, 300);
 	}
@@ -65,10 +253,10 @@ mod test {
 		let a = NaiveDate::from_ymd(2016, 1, 1).and_hms(0, 0, 0);
 		let b = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
 
-		let Cents(interest) = call(initial_balance, &rate, a, b).unwrap();
+		let Cents(interest) = call(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
 
-		let range = 995..1005;
-		assert!(range.contains(&interest));
+		// 2016 has 366 days
+		assert_eq!(interest, 1003);
 	}
 
 	#[test]
@@ -79,10 +267,62 @@ mod test {
 		let a = NaiveDate::from_ymd(2016, 1, 1).and_hms(0, 0, 0);
 		let b = NaiveDate::from_ymd(2016, 7, 2).and_hms(0, 0, 0);
 
-		let Cents(interest) = call(initial_balance, &rate, a, b).unwrap();
+		let Cents(interest) = call(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
+
+		assert_eq!(interest, 95);
+	}
+
+	#[test]
+	fn it_pays_exactly_the_yearly_rate_after_365_days() {
+		let initial_balance = Cents(100);
+		let rate = BigDecimal::from_f32(20.0).unwrap();
+
+		let a = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2018, 1, 1).and_hms(0, 0, 0);
+
+		let Cents(interest) = call(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
+
+		assert_eq!(interest, 20);
+	}
+
+	#[test]
+	fn it_keeps_the_fraction_of_a_cent() {
+		let initial_balance = Cents(100);
+		let rate = BigDecimal::from_f32(20.0).unwrap();
+
+		let a = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2017, 1, 2).and_hms(0, 0, 0);
+
+		let interest = precise(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
+
+		assert_eq!(interest.to_string(), "0.049963589");
+	}
+
+	#[test]
+	fn it_doesnt_pay_for_partial_days() {
+		let initial_balance = Cents(1000);
+		let rate = BigDecimal::from_f32(20.0).unwrap();
+
+		let a = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2017, 1, 1).and_hms(23, 0, 0);
+
+		let Cents(interest) = call(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
+
+		assert_eq!(interest, 0);
+	}
+
+	#[test]
+	fn it_is_deterministic() {
+		let initial_balance = Cents(123_456);
+		let rate = BigDecimal::from_f32(10.5).unwrap();
+
+		let a = NaiveDate::from_ymd(2016, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2016, 3, 17).and_hms(0, 0, 0);
 
-		let range = 95..105;
-		assert!(range.contains(&interest));
+		let first = precise(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
+		let second = precise(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
+
+		assert_eq!(first, second);
 	}
 
 	#[test]
@@ -94,15 +334,195 @@ mod test {
 		let b = NaiveDate::from_ymd(2016, 2, 1).and_hms(0, 0, 0);
 		let c = NaiveDate::from_ymd(2016, 3, 1).and_hms(0, 0, 0);
 
-		let interest_a_to_b = call(initial_balance, &rate, a, b).unwrap();
+		let interest_a_to_b = call(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
 		let intermediate_balance = initial_balance + interest_a_to_b;
-		let interest_b_to_c = call(intermediate_balance, &rate, b, c).unwrap();
+		let interest_b_to_c = call(intermediate_balance, &rate, Compounding::Daily, b, c).unwrap();
 		let Cents(final_balance_1) = initial_balance + interest_a_to_b + interest_b_to_c;
 
-		let interest_a_to_c = call(initial_balance, &rate, a, c).unwrap();
+		let interest_a_to_c = call(initial_balance, &rate, Compounding::Daily, a, c).unwrap();
 		let Cents(final_balance_2) = initial_balance + interest_a_to_c;
 
-		let range = (final_balance_1 - 5)..(final_balance_1 + 5);
-		assert!(range.contains(&final_balance_2));
+		// Each payout truncates to cents, so they can only differ by a cent
+		assert!((final_balance_1 - final_balance_2).abs() <= 1);
+	}
+
+	#[test]
+	fn monthly_compounding_pays_the_yearly_rate() {
+		let initial_balance = Cents(1000);
+		let rate = BigDecimal::from_f32(20.0).unwrap();
+
+		let a = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2018, 1, 1).and_hms(0, 0, 0);
+
+		let Cents(interest) = call(initial_balance, &rate, Compounding::Monthly, a, b).unwrap();
+
+		assert_eq!(interest, 200);
+	}
+
+	#[test]
+	fn weekly_compounding_matches_daily_on_whole_weeks() {
+		let initial_balance = Cents(1000);
+		let rate = BigDecimal::from_f32(20.0).unwrap();
+
+		let a = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2017, 1, 15).and_hms(0, 0, 0);
+
+		let weekly = precise(initial_balance, &rate, Compounding::Weekly, a, b).unwrap();
+		let daily = precise(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
+
+		assert_eq!(weekly, daily);
+	}
+
+	#[test]
+	fn monthly_compounding_accrues_simply_within_a_month() {
+		let initial_balance = Cents(1000);
+		let rate = BigDecimal::from_f32(20.0).unwrap();
+
+		let a = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2017, 2, 15).and_hms(0, 0, 0);
+
+		let monthly = precise(initial_balance, &rate, Compounding::Monthly, a, b).unwrap();
+		let daily = precise(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
+
+		assert_eq!(monthly.to_string(), "22.762001507");
+		assert!(monthly > daily);
+	}
+
+	#[test]
+	fn simple_interest_doesnt_compound() {
+		let initial_balance = Cents(1000);
+		let rate = BigDecimal::from_f32(10.0).unwrap();
+
+		let a = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2019, 1, 1).and_hms(0, 0, 0);
+
+		let Cents(simple) = call(initial_balance, &rate, Compounding::Simple, a, b).unwrap();
+		let Cents(daily) = call(initial_balance, &rate, Compounding::Daily, a, b).unwrap();
+
+		assert_eq!(simple, 200);
+		assert_eq!(daily, 210);
+	}
+
+	#[test]
+	fn it_uses_the_rate_in_effect_for_each_period() {
+		let initial_balance = Cents(1000);
+
+		let a = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2017, 1, 11).and_hms(0, 0, 0);
+		let c = NaiveDate::from_ymd(2017, 1, 21).and_hms(0, 0, 0);
+
+		let rates = [
+			Rate {
+				starts_at:       a,
+				yearly_interest: BigDecimal::from_f32(10.0).unwrap(),
+			},
+			Rate {
+				starts_at:       b,
+				yearly_interest: BigDecimal::from_f32(20.0).unwrap(),
+			},
+		];
+
+		let ten = BigDecimal::from_f32(10.0).unwrap();
+		let twenty = BigDecimal::from_f32(20.0).unwrap();
+
+		let both = precise_with_rates(initial_balance, &rates, Compounding::Simple, a, c).unwrap();
+		let first = precise(initial_balance, &ten, Compounding::Simple, a, b).unwrap();
+		let second = precise(initial_balance, &twenty, Compounding::Simple, b, c).unwrap();
+
+		assert_eq!(both, first.checked_add(second).unwrap());
+
+		let only_ten = precise(initial_balance, &ten, Compounding::Daily, a, c).unwrap();
+		let only_twenty = precise(initial_balance, &twenty, Compounding::Daily, a, c).unwrap();
+		let mixed = precise_with_rates(initial_balance, &rates, Compounding::Daily, a, c).unwrap();
+
+		assert!(mixed > only_ten);
+		assert!(mixed < only_twenty);
+	}
+
+	#[test]
+	fn it_ignores_rates_that_ended_before_the_period() {
+		let initial_balance = Cents(1000);
+
+		let a = NaiveDate::from_ymd(2017, 1, 1).and_hms(0, 0, 0);
+		let b = NaiveDate::from_ymd(2017, 2, 1).and_hms(0, 0, 0);
+		let c = NaiveDate::from_ymd(2017, 3, 1).and_hms(0, 0, 0);
+
+		let twenty = BigDecimal::from_f32(20.0).unwrap();
+
+		let rates = [
+			Rate {
+				starts_at:       a,
+				yearly_interest: BigDecimal::from_f32(50.0).unwrap(),
+			},
+			Rate {
+				starts_at:       a + chrono::Duration::days(3),
+				yearly_interest: twenty.clone(),
+			},
+		];
+
+		let with_rates =
+			precise_with_rates(initial_balance, &rates, Compounding::Daily, b, c).unwrap();
+		let without = precise(initial_balance, &twenty, Compounding::Daily, b, c).unwrap();
+
+		assert_eq!(with_rates, without);
+	}
+
+	#[test]
+	fn it_blends_tiered_rates() {
+		let base = BigDecimal::from_f32(20.0).unwrap();
+
+		let tiers = [Tier {
+			from_balance:    Cents(5_000),
+			yearly_interest: BigDecimal::from_f32(5.0).unwrap(),
+		}];
+
+		let below = blended_rate(Cents(4_000), &base, &tiers).unwrap();
+		let above = blended_rate(Cents(10_000), &base, &tiers).unwrap();
+		let empty = blended_rate(Cents(0), &base, &tiers).unwrap();
+
+		assert_eq!(below.to_string(), "20");
+		assert_eq!(above.to_string(), "12.5");
+		assert_eq!(empty, base);
+	}
+
+	#[test]
+	fn it_blends_several_tiers() {
+		let base = BigDecimal::from_f32(20.0).unwrap();
+
+		let tiers = [
+			Tier {
+				from_balance:    Cents(5_000),
+				yearly_interest: BigDecimal::from_f32(10.0).unwrap(),
+			},
+			Tier {
+				from_balance:    Cents(10_000),
+				yearly_interest: BigDecimal::from_f32(0.0).unwrap(),
+			},
+		];
+
+		let rate = blended_rate(Cents(20_000), &base, &tiers).unwrap();
+
+		// (5000 * 20 + 5000 * 10 + 10000 * 0) / 20000
+		assert_eq!(rate.to_string(), "7.5");
+	}
+
+	#[test]
+	fn it_weights_large_bands() {
+		let rate = BigDecimal::from_f32(20.0).unwrap();
+
+		// A $50 band at 20%
+		let band = band_interest(5_000, 0, 5_000, &rate).unwrap();
+
+		assert_eq!(band, FixedPoint::from_int(100_000));
+
+		let tiers = [Tier {
+			from_balance:    Cents(5_000),
+			yearly_interest: BigDecimal::from_f32(5.0).unwrap(),
+		}];
+
+		// $10 million, nearly all of it in the tier
+		let blended = blended_rate(Cents(1_000_000_000), &rate, &tiers).unwrap();
+
+		assert_eq!(blended.to_string(), "5.000075");
 	}
 }
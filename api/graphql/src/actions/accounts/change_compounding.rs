@@ -0,0 +1,83 @@
+use super::pay_interest;
+use crate::{
+	models::{
+		account::{Account, Compounding},
+		schema as db,
+	},
+	utils::db_transaction,
+};
+use diesel::{self, pg::PgConnection, prelude::*};
+use failure::Error;
+
+pub fn call(
+	conn: &PgConnection,
+	account_id: i32,
+	compounding: Compounding,
+) -> Result<Account, Error> {
+	db_transaction::serializable(&conn, || {
+		let account = Account::lock(&conn, account_id)?;
+
+		// Settle the interest earned so far with the previous compounding,
+		// the unfinished period carries on as it stands
+		pay_interest::settle(&conn, &account)?;
+
+		diesel::update(db::accounts::table.filter(db::accounts::id.eq(account_id)))
+			.set(db::accounts::compounding.eq(compounding))
+			.get_result(conn)
+			.map_err(Error::from)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		actions::accounts::get_balance,
+		models::{self, fixed_point::FixedPoint},
+		utils::tests,
+	};
+	use chrono::{Duration, Utc};
+
+	#[test]
+	fn it_changes_the_compounding() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let updated = call(conn, account.id, Compounding::Monthly).unwrap();
+
+			assert_eq!(updated.compounding, Compounding::Monthly);
+		})
+	}
+
+	#[test]
+	fn it_keeps_the_interest_earned_before_the_change() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let now = Utc::now().naive_utc();
+
+			// Twenty days into the first month, a day ended an hour ago
+			let account = account
+				.created_at(conn, now - Duration::days(20) - Duration::hours(1))
+				.compounding(conn, Compounding::Monthly);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.created_at(account.created_at)
+				.save(conn);
+
+			let before = get_balance::detailed(conn, account.id).unwrap();
+
+			assert_eq!(before.cents, 100_000);
+			assert!(before.accrued_interest > FixedPoint::zero());
+
+			call(conn, account.id, Compounding::Daily).unwrap();
+
+			// Nothing is credited again under the new compounding
+			let after = get_balance::detailed(conn, account.id).unwrap();
+
+			assert_eq!(after, before);
+		})
+	}
+}
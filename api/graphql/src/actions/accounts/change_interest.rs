@@ -1,15 +1,71 @@
-use crate::models::{account::Account, schema as db};
+use super::pay_interest;
+use crate::models::{
+	account::Account,
+	interest_rate::{InterestRate, InterestRateAttrs},
+	schema as db,
+	user::User,
+};
 use bigdecimal::BigDecimal;
+use chrono::prelude::*;
 use diesel::{self, pg::PgConnection, prelude::*};
 use failure::Error;
-use super::pay_interest;
 
-pub fn call(conn: &PgConnection, account_id: i32, interest: BigDecimal) -> Result<Account, Error> {
+pub fn call(
+	conn: &PgConnection,
+	account_id: i32,
+	interest: BigDecimal,
+	changed_by: &User,
+) -> Result<Account, Error> {
 	// Create an interest transaction with the current interest
 	pay_interest::call(&conn, account_id)?;
 
-	diesel::update(db::accounts::table.filter(db::accounts::id.eq(account_id)))
-		.set(db::accounts::yearly_interest.eq(interest))
-		.get_result(conn)
-		.map_err(|e| format_err!("{}", e))
+	conn.transaction(|| {
+		let account = Account::find(&conn, account_id)?;
+
+		// Keep the previous rate, so past interest can be calculated with it
+		let history_attrs = InterestRateAttrs {
+			account_id:          account_id,
+			effective_from:      Utc::now().naive_utc(),
+			changed_by_user_id:  Some(changed_by.id),
+			old_yearly_interest: Some(account.yearly_interest),
+			new_yearly_interest: interest.clone(),
+		};
+
+		InterestRate::create(&conn, history_attrs)?;
+
+		diesel::update(db::accounts::table.filter(db::accounts::id.eq(account_id)))
+			.set(db::accounts::yearly_interest.eq(interest))
+			.get_result(conn)
+	})
+	.map_err(|e| format_err!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests;
+	use bigdecimal::FromPrimitive;
+
+	#[test]
+	fn it_records_the_change() {
+		tests::with_db(|conn| {
+			let (account, user, _) = tests::account(&conn);
+
+			let interest = BigDecimal::from_f32(20.0).unwrap();
+
+			let updated = call(conn, account.id, interest.clone(), &user).unwrap();
+
+			assert_eq!(updated.yearly_interest, interest);
+
+			let history = InterestRate::find_by_account_id(conn, account.id).unwrap();
+
+			assert_eq!(history.len(), 1);
+			assert_eq!(history[0].changed_by_user_id, Some(user.id));
+			assert_eq!(
+				history[0].old_yearly_interest,
+				Some(account.yearly_interest)
+			);
+			assert_eq!(history[0].new_yearly_interest, interest);
+		})
+	}
 }
@@ -0,0 +1,98 @@
+use super::{calculate_interest::Tier, pay_interest};
+use crate::models::interest_tier::{InterestTier, InterestTierAttrs};
+use bigdecimal::{BigDecimal, Zero};
+use diesel::{pg::PgConnection, prelude::*};
+use failure::Error;
+
+// Replaces all the tiers of the account
+pub fn call(
+	conn: &PgConnection,
+	account_id: i32,
+	tiers: Vec<Tier>,
+) -> Result<Vec<InterestTier>, Error> {
+	let mut tiers = tiers;
+
+	tiers.sort_by_key(|tier| tier.from_balance);
+
+	for (index, tier) in tiers.iter().enumerate() {
+		if tier.from_balance.0 <= 0 {
+			return Err(format_err!("Tiers must start above zero"));
+		}
+
+		if tier.yearly_interest < BigDecimal::zero() {
+			return Err(format_err!("Invalid interest"));
+		}
+
+		if index > 0 && tiers[index - 1].from_balance == tier.from_balance {
+			return Err(format_err!("Tiers must start at different balances"));
+		}
+	}
+
+	// Settle the interest earned so far with the previous tiers
+	pay_interest::call(&conn, account_id)?;
+
+	conn.transaction(|| {
+		InterestTier::delete_by_account_id(&conn, account_id)?;
+
+		tiers
+			.into_iter()
+			.map(|tier| {
+				let attrs = InterestTierAttrs {
+					account_id:      account_id,
+					from_balance:    tier.from_balance,
+					yearly_interest: tier.yearly_interest,
+				};
+
+				InterestTier::create(&conn, attrs)
+			})
+			.collect()
+	})
+	.map_err(|e| format_err!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{self, cents::Cents},
+		utils::tests,
+	};
+	use bigdecimal::FromPrimitive;
+
+	fn tier(cents: i64, yearly_interest: f32) -> Tier {
+		Tier {
+			from_balance:    Cents(cents),
+			yearly_interest: BigDecimal::from_f32(yearly_interest).unwrap(),
+		}
+	}
+
+	#[test]
+	fn it_replaces_the_tiers() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _old = models::interest_tier::factories::interest_tier_attrs(&account).save(conn);
+
+			let saved = call(conn, account.id, vec![tier(10_000, 1.0), tier(5_000, 5.0)]).unwrap();
+
+			assert_eq!(saved.len(), 2);
+
+			let tiers = InterestTier::find_by_account_id(conn, account.id).unwrap();
+
+			assert_eq!(tiers, saved);
+			assert_eq!(tiers[0].from_balance, Cents(5_000));
+			assert_eq!(tiers[1].from_balance, Cents(10_000));
+		})
+	}
+
+	#[test]
+	fn it_rejects_duplicated_tiers() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let result = call(conn, account.id, vec![tier(5_000, 1.0), tier(5_000, 5.0)]);
+
+			assert!(result.is_err());
+		})
+	}
+}
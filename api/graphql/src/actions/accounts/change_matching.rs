@@ -0,0 +1,134 @@
+use crate::models::{
+	cents::Cents,
+	matching_rule::{DepositSource, MatchPeriod, MatchingRule, MatchingRuleAttrs},
+};
+use bigdecimal::{BigDecimal, Zero};
+use diesel::{pg::PgConnection, prelude::*};
+use failure::Error;
+
+// A dollar for every dollar saved at most
+const MAX_PERCENTAGE: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matching {
+	pub percentage: BigDecimal,
+	pub cap:        Option<Cents>,
+	pub period:     MatchPeriod,
+	pub sources:    Vec<DepositSource>,
+}
+
+// Replaces the account's matching rule, None stops matching
+pub fn call(
+	conn: &PgConnection,
+	account_id: i32,
+	matching: Option<Matching>,
+) -> Result<Option<MatchingRule>, Error> {
+	if let Some(matching) = &matching {
+		if matching.percentage <= BigDecimal::zero()
+			|| matching.percentage > BigDecimal::from(MAX_PERCENTAGE)
+		{
+			return Err(format_err!("Invalid percentage"));
+		}
+
+		if let Some(Cents(cap)) = matching.cap {
+			if cap <= 0 {
+				return Err(format_err!("Invalid cap"));
+			}
+		}
+
+		if matching.sources.is_empty() {
+			return Err(format_err!("Choose the deposits to match"));
+		}
+	}
+
+	conn.transaction(|| {
+		MatchingRule::delete_by_account_id(&conn, account_id)?;
+
+		let matching = match matching {
+			Some(matching) => matching,
+			None => return Ok(None),
+		};
+
+		let mut sources = vec![];
+
+		for source in matching.sources {
+			if !sources.contains(&source) {
+				sources.push(source);
+			}
+		}
+
+		let attrs = MatchingRuleAttrs {
+			account_id: account_id,
+			percentage: matching.percentage,
+			cap:        matching.cap,
+			period:     matching.period,
+			sources:    sources,
+		};
+
+		MatchingRule::create(&conn, attrs).map(Some)
+	})
+	.map_err(|e| format_err!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{models, utils::tests};
+	use bigdecimal::FromPrimitive;
+
+	fn matching(percentage: f32) -> Matching {
+		Matching {
+			percentage: BigDecimal::from_f32(percentage).unwrap(),
+			cap:        Some(Cents(2_000)),
+			period:     MatchPeriod::Monthly,
+			sources:    vec![DepositSource::Direct, DepositSource::Direct],
+		}
+	}
+
+	#[test]
+	fn it_replaces_the_rule() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _old = models::matching_rule::factories::matching_rule_attrs(&account).save(conn);
+
+			let saved = call(conn, account.id, Some(matching(25.0)))
+				.unwrap()
+				.unwrap();
+
+			assert_eq!(saved.sources, vec![DepositSource::Direct]);
+
+			let rule = MatchingRule::find_by_account_id(conn, account.id)
+				.unwrap()
+				.unwrap();
+
+			assert_eq!(rule.id, saved.id);
+			assert_eq!(rule.cap, Some(Cents(2_000)));
+		})
+	}
+
+	#[test]
+	fn it_stops_matching() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _old = models::matching_rule::factories::matching_rule_attrs(&account).save(conn);
+
+			assert_eq!(call(conn, account.id, None).unwrap(), None);
+			assert_eq!(
+				MatchingRule::find_by_account_id(conn, account.id).unwrap(),
+				None
+			);
+		})
+	}
+
+	#[test]
+	fn it_rejects_an_invalid_percentage() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			assert!(call(conn, account.id, Some(matching(0.0))).is_err());
+			assert!(call(conn, account.id, Some(matching(150.0))).is_err());
+		})
+	}
+}
@@ -0,0 +1,136 @@
+use crate::models::{
+	cents::Cents,
+	withdrawal_rule::{WithdrawalRule, WithdrawalRuleAttrs, WithdrawalRuleKind},
+};
+use diesel::{pg::PgConnection, prelude::*};
+use failure::Error;
+
+const MAX_COOLING_OFF_DAYS: i32 = 365;
+
+// `amount` for every kind but CoolingOff, which takes `days`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+	pub kind:   WithdrawalRuleKind,
+	pub amount: Option<Cents>,
+	pub days:   Option<i32>,
+}
+
+// Replaces all the rules of the account
+pub fn call(
+	conn: &PgConnection,
+	account_id: i32,
+	rules: Vec<Rule>,
+) -> Result<Vec<WithdrawalRule>, Error> {
+	for (index, rule) in rules.iter().enumerate() {
+		if rules[..index].iter().any(|other| other.kind == rule.kind) {
+			return Err(format_err!("Only one rule of each kind"));
+		}
+
+		match rule.kind {
+			WithdrawalRuleKind::CoolingOff => {
+				match rule.days {
+					Some(days) if days > 0 && days <= MAX_COOLING_OFF_DAYS => (),
+					_ => return Err(format_err!("Invalid days")),
+				}
+			},
+			_ => {
+				match rule.amount {
+					Some(Cents(cents)) if cents > 0 => (),
+					_ => return Err(format_err!("Invalid amount")),
+				}
+			},
+		}
+	}
+
+	conn.transaction(|| {
+		WithdrawalRule::delete_by_account_id(&conn, account_id)?;
+
+		rules
+			.into_iter()
+			.map(|rule| {
+				// Only keep what the kind uses
+				let attrs = match rule.kind {
+					WithdrawalRuleKind::CoolingOff => {
+						WithdrawalRuleAttrs {
+							account_id: account_id,
+							kind:       rule.kind,
+							amount:     None,
+							days:       rule.days,
+						}
+					},
+					_ => {
+						WithdrawalRuleAttrs {
+							account_id: account_id,
+							kind:       rule.kind,
+							amount:     rule.amount,
+							days:       None,
+						}
+					},
+				};
+
+				WithdrawalRule::create(&conn, attrs)
+			})
+			.collect()
+	})
+	.map_err(|e| format_err!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{models, utils::tests};
+
+	fn limit(cents: i64) -> Rule {
+		Rule {
+			kind:   WithdrawalRuleKind::WeeklyLimit,
+			amount: Some(Cents(cents)),
+			days:   None,
+		}
+	}
+
+	#[test]
+	fn it_replaces_the_rules() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _old = models::withdrawal_rule::factories::withdrawal_rule_attrs(&account)
+				.kind(WithdrawalRuleKind::MinimumBalance)
+				.save(conn);
+
+			let cooling_off = Rule {
+				kind:   WithdrawalRuleKind::CoolingOff,
+				amount: Some(Cents(100)),
+				days:   Some(3),
+			};
+
+			let saved = call(conn, account.id, vec![limit(2_000), cooling_off]).unwrap();
+
+			assert_eq!(saved.len(), 2);
+
+			let rules = WithdrawalRule::find_by_account_id(conn, account.id).unwrap();
+
+			assert_eq!(rules.len(), 2);
+			assert_eq!(rules[0].kind, WithdrawalRuleKind::WeeklyLimit);
+			assert_eq!(rules[1].amount, None);
+			assert_eq!(rules[1].days, Some(3));
+		})
+	}
+
+	#[test]
+	fn it_rejects_invalid_rules() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			assert!(call(conn, account.id, vec![limit(0)]).is_err());
+			assert!(call(conn, account.id, vec![limit(100), limit(200)]).is_err());
+
+			let cooling_off = Rule {
+				kind:   WithdrawalRuleKind::CoolingOff,
+				amount: None,
+				days:   None,
+			};
+
+			assert!(call(conn, account.id, vec![cooling_off]).is_err());
+		})
+	}
+}
@@ -0,0 +1,320 @@
+use crate::{
+	actions::accounts::get_balance,
+	models::{
+		account::Account,
+		cents::Cents,
+		transaction::{Transaction, TransactionKind},
+		withdrawal_rule::{WithdrawalRule, WithdrawalRuleKind},
+	},
+};
+use chrono::{Duration, NaiveDateTime};
+use diesel::pg::PgConnection;
+use failure::{Error, Fail};
+use std::fmt;
+
+// How the withdrawal was asked for.
+// Only direct withdrawals are held to ApprovalAbove, a request is how it is met.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Route {
+	Direct,
+	Request,
+	Approval,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+	pub kind:    WithdrawalRuleKind,
+	pub message: String,
+}
+
+// Every rule the withdrawal breaks, so each can be reported against its rule
+#[derive(Debug)]
+pub struct Violations(pub Vec<Violation>);
+
+impl fmt::Display for Violations {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let messages: Vec<&str> = self.0.iter().map(|v| v.message.as_str()).collect();
+
+		write!(f, "{}", messages.join(", "))
+	}
+}
+
+impl Fail for Violations {}
+
+// Fails with Violations when withdrawing `cents` at `at` breaks any of the account's rules
+pub fn call(
+	conn: &PgConnection,
+	account: &Account,
+	cents: i64,
+	at: NaiveDateTime,
+	route: Route,
+) -> Result<(), Error> {
+	let mut violations = vec![];
+
+	for rule in WithdrawalRule::find_by_account_id(&conn, account.id)? {
+		if let Some(message) = check(&conn, account, &rule, cents, at, route)? {
+			violations.push(Violation {
+				kind:    rule.kind,
+				message: message,
+			});
+		}
+	}
+
+	if violations.is_empty() {
+		Ok(())
+	} else {
+		Err(Error::from(Violations(violations)))
+	}
+}
+
+// The reason the rule is broken, if it is
+fn check(
+	conn: &PgConnection,
+	account: &Account,
+	rule: &WithdrawalRule,
+	cents: i64,
+	at: NaiveDateTime,
+	route: Route,
+) -> Result<Option<String>, Error> {
+	let Cents(amount) = rule.amount.unwrap_or(Cents(0));
+
+	let message = match rule.kind {
+		WithdrawalRuleKind::WeeklyLimit => {
+			let withdrawn = withdrawn_since(&conn, account.id, at - Duration::days(7), at)?;
+
+			if withdrawn + cents > amount {
+				Some(format!(
+					"Withdrawals are limited to {} a week",
+					dollars(amount)
+				))
+			} else {
+				None
+			}
+		},
+		WithdrawalRuleKind::MonthlyLimit => {
+			let withdrawn = withdrawn_since(&conn, account.id, at - Duration::days(30), at)?;
+
+			if withdrawn + cents > amount {
+				Some(format!(
+					"Withdrawals are limited to {} a month",
+					dollars(amount)
+				))
+			} else {
+				None
+			}
+		},
+		WithdrawalRuleKind::MinimumBalance => {
+			let balance = get_balance::call(&conn, account.id)?;
+
+			if balance - cents < amount {
+				Some(format!("{} must stay in the account", dollars(amount)))
+			} else {
+				None
+			}
+		},
+		WithdrawalRuleKind::CoolingOff => {
+			let days = rule.days.unwrap_or(0);
+
+			let since = at - Duration::days(days as i64);
+
+			let deposited = Transaction::find_by_account_id(&conn, account.id, since)?
+				.iter()
+				.any(|t| t.kind == TransactionKind::Deposit && t.created_at <= at);
+
+			if deposited {
+				Some(format!("No withdrawals within {} days of a deposit", days))
+			} else {
+				None
+			}
+		},
+		WithdrawalRuleKind::ApprovalAbove => {
+			if route == Route::Direct && cents > amount {
+				Some(format!(
+					"Withdrawals over {} need approval, use requestWithdraw",
+					dollars(amount)
+				))
+			} else {
+				None
+			}
+		},
+	};
+
+	Ok(message)
+}
+
+// Withdrawals and transfers out in the window that haven't been reversed
+fn withdrawn_since(
+	conn: &PgConnection,
+	account_id: i32,
+	since: NaiveDateTime,
+	at: NaiveDateTime,
+) -> Result<i64, Error> {
+	let mut total = 0;
+
+	for transaction in Transaction::find_by_account_id(&conn, account_id, since)? {
+		if transaction.created_at > at {
+			continue;
+		}
+
+		let Cents(amount) = transaction.amount;
+
+		let cents = match transaction.kind {
+			TransactionKind::Withdrawal => amount,
+			// The leg that leaves the account is negative
+			TransactionKind::Transfer if amount < 0 => -amount,
+			_ => continue,
+		};
+
+		if Transaction::find_reversal(&conn, transaction.id)?.is_none() {
+			total += cents;
+		}
+	}
+
+	Ok(total)
+}
+
+fn dollars(cents: i64) -> String {
+	format!("${}.{:02}", cents / 100, cents % 100)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{models, utils::tests};
+	use chrono::Utc;
+
+	fn violations(result: Result<(), Error>) -> Vec<WithdrawalRuleKind> {
+		match result {
+			Ok(()) => vec![],
+			Err(e) => {
+				e.downcast::<Violations>()
+					.unwrap()
+					.0
+					.into_iter()
+					.map(|v| v.kind)
+					.collect()
+			},
+		}
+	}
+
+	#[test]
+	fn it_counts_recent_withdrawals_towards_the_limit() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(5_000)
+				.balance(5_000)
+				.save(conn);
+
+			let _withdrawal = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Withdrawal)
+				.amount(800)
+				.balance(4_200)
+				.save(conn);
+
+			let _rule = models::withdrawal_rule::factories::withdrawal_rule_attrs(&account)
+				.amount(1_000)
+				.save(conn);
+
+			let now = Utc::now().naive_utc();
+
+			assert_eq!(
+				violations(call(conn, &account, 200, now, Route::Direct)),
+				vec![]
+			);
+			assert_eq!(
+				violations(call(conn, &account, 300, now, Route::Direct)),
+				vec![WithdrawalRuleKind::WeeklyLimit]
+			);
+		})
+	}
+
+	#[test]
+	fn it_counts_transfers_out_towards_the_limit() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(5_000)
+				.balance(5_000)
+				.save(conn);
+
+			let _transfer = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Transfer)
+				.amount(-800)
+				.balance(4_200)
+				.save(conn);
+
+			let _rule = models::withdrawal_rule::factories::withdrawal_rule_attrs(&account)
+				.amount(1_000)
+				.save(conn);
+
+			let now = Utc::now().naive_utc();
+
+			assert_eq!(
+				violations(call(conn, &account, 300, now, Route::Direct)),
+				vec![WithdrawalRuleKind::WeeklyLimit]
+			);
+		})
+	}
+
+	#[test]
+	fn it_reports_every_broken_rule() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(5_000)
+				.balance(5_000)
+				.save(conn);
+
+			let _minimum = models::withdrawal_rule::factories::withdrawal_rule_attrs(&account)
+				.kind(WithdrawalRuleKind::MinimumBalance)
+				.amount(4_000)
+				.save(conn);
+
+			let _cooling_off = models::withdrawal_rule::factories::withdrawal_rule_attrs(&account)
+				.kind(WithdrawalRuleKind::CoolingOff)
+				.days(3)
+				.save(conn);
+
+			let now = Utc::now().naive_utc();
+
+			assert_eq!(
+				violations(call(conn, &account, 2_000, now, Route::Direct)),
+				vec![
+					WithdrawalRuleKind::MinimumBalance,
+					WithdrawalRuleKind::CoolingOff
+				]
+			);
+		})
+	}
+
+	#[test]
+	fn it_only_holds_direct_withdrawals_to_the_approval_threshold() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _rule = models::withdrawal_rule::factories::withdrawal_rule_attrs(&account)
+				.kind(WithdrawalRuleKind::ApprovalAbove)
+				.amount(500)
+				.save(conn);
+
+			let now = Utc::now().naive_utc();
+
+			assert_eq!(
+				violations(call(conn, &account, 600, now, Route::Direct)),
+				vec![WithdrawalRuleKind::ApprovalAbove]
+			);
+			assert_eq!(
+				violations(call(conn, &account, 600, now, Route::Request)),
+				vec![]
+			);
+			assert_eq!(
+				violations(call(conn, &account, 600, now, Route::Approval)),
+				vec![]
+			);
+		})
+	}
+}
@@ -1,12 +1,15 @@
-use bigdecimal::{BigDecimal, FromPrimitive};
+use bigdecimal::{BigDecimal, FromPrimitive, Zero};
+use chrono::prelude::*;
 use diesel::pg::PgConnection;
 use failure::Error;
 
 use crate::models::{
-	account::{Account, AccountAttrs, Kind, State, DEFAULT_YEARLY_INTEREST},
+	account::{Account, AccountAttrs, Compounding, Kind, State, DEFAULT_YEARLY_INTEREST},
+	interest_rate::{InterestRate, InterestRateAttrs},
 	user::User,
 };
 
+// The savings account every investor starts with
 pub fn call(conn: &PgConnection, user: &User) -> Result<Account, Error> {
 	let yearly_interest = BigDecimal::from_u8(DEFAULT_YEARLY_INTEREST).unwrap();
 
@@ -16,7 +19,93 @@ pub fn call(conn: &PgConnection, user: &User) -> Result<Account, Error> {
 		yearly_interest,
 		kind: Kind::Savings,
 		state: State::Active,
+		compounding: Compounding::Daily,
+		matures_at: None,
 	};
 
-	Account::create(conn, attrs).map_err(|e| format_err!("{}", e))
+	open(conn, attrs)
+}
+
+// Opens any kind of account
+pub fn open(conn: &PgConnection, attrs: AccountAttrs) -> Result<Account, Error> {
+	let now = Utc::now().naive_utc();
+
+	let attrs = match (attrs.kind, attrs.matures_at) {
+		(Kind::TermDeposit, Some(matures_at)) if matures_at > now => attrs,
+		(Kind::TermDeposit, _) => {
+			return Err(format_err!("Term deposits must mature in the future"))
+		},
+		(_, Some(_)) => return Err(format_err!("Only term deposits mature")),
+		(Kind::Spending, None) => {
+			AccountAttrs {
+				yearly_interest: BigDecimal::zero(),
+				..attrs
+			}
+		},
+		(Kind::Savings, None) => attrs,
+	};
+
+	let account = Account::create(conn, attrs)?;
+
+	// Start the interest rate history
+	let rate_attrs = InterestRateAttrs {
+		account_id:          account.id,
+		effective_from:      account.created_at,
+		changed_by_user_id:  None,
+		old_yearly_interest: None,
+		new_yearly_interest: account.yearly_interest.clone(),
+	};
+
+	InterestRate::create(conn, rate_attrs)?;
+
+	Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{models, utils::tests};
+	use chrono::Duration;
+
+	#[test]
+	fn it_opens_a_term_deposit() {
+		tests::with_db(|conn| {
+			let (user, _) = tests::user(&conn);
+
+			let matures_at = Utc::today().naive_utc().and_hms(0, 0, 0) + Duration::days(90);
+
+			let attrs = models::account::factories::account_attrs(&user)
+				.kind(Kind::TermDeposit)
+				.matures_at(matures_at);
+
+			let account = open(conn, attrs).unwrap();
+
+			assert_eq!(account.kind, Kind::TermDeposit);
+			assert_eq!(account.matures_at, Some(matures_at));
+		})
+	}
+
+	#[test]
+	fn term_deposits_need_a_maturity_date() {
+		tests::with_db(|conn| {
+			let (user, _) = tests::user(&conn);
+
+			let attrs = models::account::factories::account_attrs(&user).kind(Kind::TermDeposit);
+
+			assert!(open(conn, attrs).is_err());
+		})
+	}
+
+	#[test]
+	fn spending_accounts_dont_earn_interest() {
+		tests::with_db(|conn| {
+			let (user, _) = tests::user(&conn);
+
+			let attrs = models::account::factories::account_attrs(&user).kind(Kind::Spending);
+
+			let account = open(conn, attrs).unwrap();
+
+			assert_eq!(account.yearly_interest, BigDecimal::zero());
+		})
+	}
 }
@@ -1,12 +1,29 @@
-use super::calculate_interest;
-use crate::models::{account::Account, cents::Cents, transaction::Transaction};
+use super::accrue_interest;
+use crate::models::{
+	account::Account, cents::Cents, fixed_point::FixedPoint, posting::Posting,
+	transaction::Transaction,
+};
 use chrono::prelude::*;
 use diesel::pg::PgConnection;
 use failure::Error;
 
+#[derive(Debug, PartialEq)]
+pub struct Balance {
+	pub cents: i64,
+	// Interest that is not part of the balance yet
+	pub accrued_interest: FixedPoint,
+}
+
 pub fn call(conn: &PgConnection, account_id: i32) -> Result<i64, Error> {
+	detailed(conn, account_id).map(|balance| balance.cents)
+}
+
+// The balance is what has been posted to the account in the journal
+pub fn detailed(conn: &PgConnection, account_id: i32) -> Result<Balance, Error> {
 	let account = Account::find(&conn, account_id)?;
 
+	let Cents(posted) = Posting::balance_by_account_id(&conn, account_id)?;
+
 	let previous_transaction_result = Transaction::find_last_by_account_id(&conn, account_id);
 
 	match previous_transaction_result {
@@ -14,17 +31,20 @@ pub fn call(conn: &PgConnection, account_id: i32) -> Result<i64, Error> {
 			let now = Utc::now().naive_utc();
 
 			// Calculate the interest on the fly
-			let Cents(interest) = calculate_interest::call(
-				previous_transaction.balance,
-				&account.yearly_interest,
-				previous_transaction.created_at,
-				now,
-			)?;
+			let accrual = accrue_interest::call(&conn, &account, &previous_transaction, now)?;
 
-			let Cents(cents) = previous_transaction.balance;
+			let Cents(interest) = accrual.payable;
 
-			Ok(cents + interest)
+			Ok(Balance {
+				cents:            posted + interest,
+				accrued_interest: accrual.carried,
+			})
+		},
+		Err(_) => {
+			Ok(Balance {
+				cents:            posted,
+				accrued_interest: FixedPoint::zero(),
+			})
 		},
-		Err(_) => Ok(0),
 	}
 }
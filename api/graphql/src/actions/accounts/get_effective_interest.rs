@@ -0,0 +1,14 @@
+use super::{accrue_interest, calculate_interest, get_balance};
+use crate::models::{account::Account, cents::Cents};
+use bigdecimal::BigDecimal;
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// The yearly interest the current balance earns, blending the tiers
+pub fn call(conn: &PgConnection, account: &Account) -> Result<BigDecimal, Error> {
+	let balance = get_balance::call(&conn, account.id)?;
+
+	let tiers = accrue_interest::tiers(&conn, account)?;
+
+	calculate_interest::blended_rate(Cents(balance), &account.yearly_interest, &tiers)
+}
@@ -1,6 +1,18 @@
+pub mod accrue_interest;
+pub mod archive;
 pub mod authorise;
 pub mod calculate_interest;
+pub mod change_compounding;
 pub mod change_interest;
+pub mod change_interest_tiers;
+pub mod change_matching;
+pub mod change_withdrawal_rules;
+pub mod check_withdrawal_rules;
 pub mod create;
 pub mod get_balance;
+pub mod get_effective_interest;
 pub mod pay_interest;
+pub mod post_scheduled_interest;
+pub mod project_balance;
+pub mod rules;
+pub mod unarchive;
@@ -1,10 +1,16 @@
-use super::calculate_interest;
-use crate::models::{
-	account::Account,
-	cents::Cents,
-	transaction::{Transaction, TransactionAttrs, TransactionKind},
+use super::accrue_interest;
+use crate::{
+	actions::ledger,
+	models::{
+		account::Account,
+		cents::Cents,
+		fixed_point::FixedPoint,
+		posting::Posting,
+		transaction::{Transaction, TransactionAttrs, TransactionKind},
+	},
+	utils::db_transaction,
 };
-use chrono::{prelude::*, Duration};
+use chrono::prelude::*;
 use diesel::{pg::PgConnection, result::Error as DieselError};
 use failure::Error;
 
@@ -15,7 +21,31 @@ pub enum PayInterestResponse {
 }
 
 pub fn call(conn: &PgConnection, account_id: i32) -> Result<PayInterestResponse, Error> {
-	let account = Account::find(&conn, account_id)?;
+	db_transaction::serializable(&conn, || {
+		let account = Account::lock(&conn, account_id)?;
+
+		pay(&conn, &account)
+	})
+}
+
+// Expects the account to be locked
+fn pay(conn: &PgConnection, account: &Account) -> Result<PayInterestResponse, Error> {
+	record(&conn, account, false)
+}
+
+// Pays what is payable and records the interest of the unfinished period as it stands,
+// even when nothing is payable, before the way interest is calculated changes.
+// Expects the account to be locked
+pub fn settle(conn: &PgConnection, account: &Account) -> Result<PayInterestResponse, Error> {
+	record(&conn, account, true)
+}
+
+fn record(
+	conn: &PgConnection,
+	account: &Account,
+	settling: bool,
+) -> Result<PayInterestResponse, Error> {
+	let account_id = account.id;
 
 	let previous_transaction_result = Transaction::find_last_by_account_id(&conn, account_id);
 
@@ -25,53 +55,61 @@ pub fn call(conn: &PgConnection, account_id: i32) -> Result<PayInterestResponse,
 		Err(e) => {
 			match e {
 				DieselError::NotFound => return Ok(PayInterestResponse::NotNeeded),
-				_ => return Err(format_err!("{}", e)),
+				_ => return Err(Error::from(e)),
 			}
 		},
 	};
 
 	// println!("{}", previous_transaction);
 
-	// If the previous transaction was in the last 1 day, then there is no interest to pay
 	let now = Utc::now().naive_utc();
 
-	let threshold = now - Duration::days(1);
+	// Calculate the interest.
+	// Whatever is payable is paid, even when the previous transaction is recent,
+	// so a balance read after this never includes interest that wasn't posted.
+	let accrual = accrue_interest::call(&conn, account, &previous_transaction, now)?;
+
+	let Cents(interest) = accrual.payable;
 
-	if previous_transaction.created_at > threshold {
+	let previously_accrued = FixedPoint::from_big_decimal(&previous_transaction.accrued_interest)?;
+
+	// Nothing credited yet, keep accruing from the previous transaction.
+	// Settling records the accrual unless nothing has been earned since.
+	if interest == 0 && (!settling || accrual.carried == previously_accrued) {
 		return Ok(PayInterestResponse::NotNeeded);
 	}
 
-	// Calculate the interest
-	let Cents(interest) = calculate_interest::call(
-		previous_transaction.balance,
-		&account.yearly_interest,
-		previous_transaction.created_at,
-		now,
-	)?;
-
-	let Cents(previous_balance) = previous_transaction.balance;
+	// The journal is the source of truth for the balance
+	let Cents(previous_balance) = Posting::balance_by_account_id(&conn, account_id)?;
 
 	let new_balance = Cents(previous_balance + interest);
 
-	// Pay interest
+	// Pay interest, carrying what hasn't been credited forward
 	let attrs = TransactionAttrs {
-		account_id: account_id,
-		kind:       TransactionKind::Interest,
-		amount:     Cents(interest),
-		balance:    new_balance,
+		account_id:            account_id,
+		kind:                  TransactionKind::Interest,
+		amount:                Cents(interest),
+		balance:               new_balance,
+		accrued_interest:      accrual.carried.to_big_decimal()?,
+		linked_transaction_id: None,
+		created_at:            None,
+		idempotency_key:       None,
+		memo:                  None,
+		category_id:           None,
 	};
 
-	Transaction::create(conn, attrs)
-		.map(|t| PayInterestResponse::Paid(t))
-		.map_err(|e| format_err!("{}", e))
+	ledger::record::one(&conn, attrs).map(|t| PayInterestResponse::Paid(t))
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::{
+		models::{self, schema::transactions},
+		utils::tests,
+	};
+	use chrono::Duration;
 	use diesel::{self, prelude::*};
-	use crate::models::{self, schema::transactions};
-	use crate::utils::tests;
 
 	#[test]
 	fn it_doesnt_do_anything_if_there_is_no_previous_transaction() {
@@ -86,7 +124,7 @@ mod tests {
 	}
 
 	#[test]
-	fn it_doesnt_pay_if_previous_withing_threshold() {
+	fn it_doesnt_pay_before_a_period_completes() {
 		tests::with_db(|conn| {
 			let client = models::client::factories::client_attrs().save(conn);
 			let user = models::user::factories::user_attrs(&client).save(conn);
@@ -108,8 +146,10 @@ mod tests {
 			let user = models::user::factories::user_attrs(&client).save(conn);
 			let account = models::account::factories::account_attrs(&user).save(conn);
 
-			let transaction =
-				models::transaction::factories::transaction_attrs(&account).save(conn);
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.save(conn);
 
 			// Make the transaction one day old
 			let day_ago = Utc::now().naive_utc() - Duration::days(1) - Duration::hours(1);
@@ -130,4 +170,80 @@ mod tests {
 			}
 		})
 	}
+
+	#[test]
+	fn it_pays_a_period_that_completed_since_a_recent_transaction() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let now = Utc::now().naive_utc();
+
+			// A day of the account ended an hour ago
+			let account = account.created_at(conn, now - Duration::days(10) - Duration::hours(1));
+
+			let _transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.created_at(now - Duration::hours(2))
+				.save(conn);
+
+			match call(conn, account.id).unwrap() {
+				PayInterestResponse::NotNeeded => panic!("Should not be NotNeeded"),
+				PayInterestResponse::Paid(transaction) => assert!(transaction.amount > Cents(0)),
+			}
+		})
+	}
+
+	#[test]
+	fn it_doesnt_pay_less_than_a_cent() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100)
+				.balance(100)
+				.save(conn);
+
+			let day_ago = Utc::now().naive_utc() - Duration::days(1) - Duration::hours(1);
+
+			diesel::update(transactions::table.filter(transactions::id.eq(transaction.id)))
+				.set(transactions::created_at.eq(day_ago))
+				.execute(conn)
+				.unwrap();
+
+			let response = call(conn, account.id).unwrap();
+
+			assert_eq!(response, PayInterestResponse::NotNeeded);
+		})
+	}
+
+	#[test]
+	fn it_pays_once_the_fractions_add_up_to_a_cent() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100)
+				.balance(100)
+				.accrued_interest("0.99")
+				.save(conn);
+
+			let day_ago = Utc::now().naive_utc() - Duration::days(1) - Duration::hours(1);
+
+			diesel::update(transactions::table.filter(transactions::id.eq(transaction.id)))
+				.set(transactions::created_at.eq(day_ago))
+				.execute(conn)
+				.unwrap();
+
+			let response = call(conn, account.id).unwrap();
+
+			match response {
+				PayInterestResponse::NotNeeded => panic!("Should not be NotNeeded"),
+				PayInterestResponse::Paid(returned_transaction) => {
+					assert_eq!(returned_transaction.amount, Cents(1));
+					assert_eq!(returned_transaction.balance, Cents(101));
+				},
+			}
+		})
+	}
 }
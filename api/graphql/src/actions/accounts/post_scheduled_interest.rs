@@ -0,0 +1,164 @@
+use super::pay_interest::{self, PayInterestResponse};
+use crate::models::{
+	account::{Account, State},
+	client::Client,
+	schema as db,
+	transaction::{Transaction, TransactionKind},
+	user::User,
+};
+use chrono::prelude::*;
+use diesel::{pg::PgConnection, prelude::*, result::Error as DieselError};
+use failure::Error;
+
+// Posts interest for every active account that is due,
+// according to the posting frequency of its client.
+// Returns the interest transactions created.
+pub fn call(conn: &PgConnection) -> Result<Vec<Transaction>, Error> {
+	let now = Utc::now().naive_utc();
+
+	let clients = Client::all(&conn)?;
+
+	let mut posted = vec![];
+
+	for client in clients {
+		let users = User::belonging_to(&client).load::<User>(conn)?;
+
+		let accounts = Account::belonging_to(&users)
+			.filter(db::accounts::state.eq(State::Active))
+			.load::<Account>(conn)?;
+
+		for account in accounts {
+			// Nothing to pay on accounts without transactions
+			let last_posted_at = match last_posted_at(conn, &account)? {
+				Some(at) => at,
+				None => continue,
+			};
+
+			if client.interest_posting.next_after(last_posted_at) > now {
+				continue;
+			}
+
+			// One account failing shouldn't stop the others
+			match pay_interest::call(&conn, account.id) {
+				Ok(PayInterestResponse::Paid(transaction)) => posted.push(transaction),
+				Ok(PayInterestResponse::NotNeeded) => (),
+				Err(e) => error!("Failed to post interest for account {}: {}", account.id, e),
+			}
+		}
+	}
+
+	Ok(posted)
+}
+
+// When interest was last posted,
+// or when the account started earning interest if never posted
+fn last_posted_at(conn: &PgConnection, account: &Account) -> Result<Option<NaiveDateTime>, Error> {
+	let last_interest_result =
+		Transaction::find_last_by_account_id_and_kind(&conn, account.id, TransactionKind::Interest);
+
+	let result = match last_interest_result {
+		Err(DieselError::NotFound) => Transaction::find_last_by_account_id(&conn, account.id),
+		other => other,
+	};
+
+	match result {
+		Ok(transaction) => Ok(Some(transaction.created_at)),
+		Err(DieselError::NotFound) => Ok(None),
+		Err(e) => Err(format_err!("{}", e)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{self, posting_frequency::PostingFrequency, schema::transactions},
+		utils::tests,
+	};
+	use chrono::Duration;
+	use diesel;
+
+	fn make_old(conn: &PgConnection, transaction: &Transaction, days: i64) {
+		let created_at = Utc::now().naive_utc() - Duration::days(days) - Duration::hours(1);
+
+		diesel::update(transactions::table.filter(transactions::id.eq(transaction.id)))
+			.set(transactions::created_at.eq(created_at))
+			.execute(conn)
+			.unwrap();
+	}
+
+	#[test]
+	fn it_posts_interest_for_due_accounts() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.save(conn);
+
+			make_old(conn, &transaction, 2);
+
+			let posted = call(conn).unwrap();
+
+			let for_account: Vec<Transaction> = posted
+				.into_iter()
+				.filter(|t| t.account_id == account.id)
+				.collect();
+
+			assert_eq!(for_account.len(), 1);
+			assert_eq!(for_account[0].kind, TransactionKind::Interest);
+		})
+	}
+
+	#[test]
+	fn it_waits_for_the_posting_frequency() {
+		tests::with_db(|conn| {
+			let (account, _, client) = tests::account(&conn);
+
+			client.interest_posting(conn, PostingFrequency::Weekly);
+
+			let deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.save(conn);
+
+			make_old(conn, &deposit, 10);
+
+			let interest = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Interest)
+				.amount(100)
+				.balance(100_100)
+				.save(conn);
+
+			make_old(conn, &interest, 2);
+
+			let posted = call(conn).unwrap();
+
+			assert!(posted.iter().all(|t| t.account_id != account.id));
+		})
+	}
+
+	#[test]
+	fn it_skips_archived_accounts() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.save(conn);
+
+			make_old(conn, &transaction, 2);
+
+			diesel::update(db::accounts::table.find(account.id))
+				.set(db::accounts::state.eq(State::Archived))
+				.execute(conn)
+				.unwrap();
+
+			let posted = call(conn).unwrap();
+
+			assert!(posted.iter().all(|t| t.account_id != account.id));
+		})
+	}
+}
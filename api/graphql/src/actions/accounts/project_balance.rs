@@ -0,0 +1,271 @@
+use super::{
+	accrue_interest,
+	calculate_interest::{self, Tier, DAYS_IN_YEAR},
+	get_balance,
+};
+use crate::models::{
+	account::{Account, Compounding},
+	cents::Cents,
+	fixed_point::FixedPoint,
+};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Duration, NaiveDateTime};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Longest projection, in months
+pub const MAX_MONTHS: i32 = 120;
+
+// The balance at the end of a month of a projection.
+// The starting balance counts as principal.
+#[derive(GraphQLObject, Debug, Clone, PartialEq)]
+pub struct ProjectionPoint {
+	pub at:        NaiveDateTime,
+	pub principal: Cents,
+	pub interest:  Cents,
+	pub balance:   Cents,
+}
+
+// What is being projected, `yearly_interest` is in percent
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+	pub balance:         Cents,
+	pub weekly_deposit:  Cents,
+	pub yearly_interest: BigDecimal,
+	pub tiers:           Vec<Tier>,
+	pub compounding:     Compounding,
+}
+
+// Projects the balance of the account at its current rate, tiers and compounding
+pub fn call(
+	conn: &PgConnection,
+	account: &Account,
+	weekly_deposit: i32,
+	months: i32,
+	now: NaiveDateTime,
+) -> Result<Vec<ProjectionPoint>, Error> {
+	let balance = get_balance::call(&conn, account.id)?;
+
+	// Accounts that don't earn interest only grow with deposits
+	let (yearly_interest, tiers) = if account.kind.earns_interest() {
+		(
+			account.yearly_interest.clone(),
+			accrue_interest::tiers(&conn, account)?,
+		)
+	} else {
+		(BigDecimal::zero(), vec![])
+	};
+
+	let scenario = Scenario {
+		balance:         Cents(balance),
+		weekly_deposit:  Cents(weekly_deposit as i64),
+		yearly_interest: yearly_interest,
+		tiers:           tiers,
+		compounding:     account.compounding,
+	};
+
+	simulate(&scenario, months, now)
+}
+
+// One point for each month from `now`, a month being a twelfth of a year.
+// A deposit is made every week. Like on a real account, compounding periods are
+// counted from `now` and interest is only credited when a period completes,
+// except simple interest which is paid with each deposit.
+pub fn simulate(
+	scenario: &Scenario,
+	months: i32,
+	now: NaiveDateTime,
+) -> Result<Vec<ProjectionPoint>, Error> {
+	if months < 1 || months > MAX_MONTHS {
+		return Err(format_err!("Choose between 1 and {} months", MAX_MONTHS));
+	}
+
+	let Cents(weekly_deposit) = scenario.weekly_deposit;
+
+	if weekly_deposit < 0 {
+		return Err(format_err!("Invalid amount"));
+	}
+
+	let Cents(balance) = scenario.balance;
+
+	if balance < 0 {
+		return Err(format_err!("Invalid balance"));
+	}
+
+	if scenario.yearly_interest < BigDecimal::zero() {
+		return Err(format_err!("Invalid interest"));
+	}
+
+	let mut principal = balance;
+	let mut interest = 0;
+	let mut carried = FixedPoint::zero();
+	let mut last = now;
+	let mut next_deposit = now + Duration::days(7);
+
+	let mut points = vec![];
+
+	for month in 1..=months {
+		let month_end = now + Duration::days(DAYS_IN_YEAR as i64 * month as i64 / 12);
+
+		loop {
+			let period_end = scenario.compounding.period_end(now, last);
+
+			let next = period_end.unwrap_or(next_deposit).min(next_deposit);
+
+			if next > month_end {
+				break;
+			}
+
+			carried = carried.checked_add(earned(scenario, principal, interest, last, next)?)?;
+
+			if period_end == Some(next) || scenario.compounding == Compounding::Simple {
+				let Cents(paid) = carried.to_cents();
+
+				carried = carried.checked_sub(FixedPoint::from_cents(Cents(paid)))?;
+				interest += paid;
+			}
+
+			if next == next_deposit {
+				principal += weekly_deposit;
+				next_deposit = next_deposit + Duration::days(7);
+			}
+
+			last = next;
+		}
+
+		// Interest that hasn't been credited yet is part of the projection too
+		let Cents(accrued) = carried
+			.checked_add(earned(scenario, principal, interest, last, month_end)?)?
+			.to_cents();
+
+		points.push(ProjectionPoint {
+			at:        month_end,
+			principal: Cents(principal),
+			interest:  Cents(interest + accrued),
+			balance:   Cents(principal + interest + accrued),
+		});
+	}
+
+	Ok(points)
+}
+
+// Tiers are blended at the balance the period starts with.
+// With simple interest, interest already paid doesn't earn more interest.
+fn earned(
+	scenario: &Scenario,
+	principal: i64,
+	interest: i64,
+	from: NaiveDateTime,
+	to: NaiveDateTime,
+) -> Result<FixedPoint, Error> {
+	let balance = Cents(principal + interest);
+
+	let rate =
+		calculate_interest::blended_rate(balance, &scenario.yearly_interest, &scenario.tiers)?;
+
+	let earning_balance = match scenario.compounding {
+		Compounding::Simple => Cents(principal),
+		_ => balance,
+	};
+
+	calculate_interest::precise(earning_balance, &rate, scenario.compounding, from, to)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{models, utils::tests};
+	use bigdecimal::FromPrimitive;
+	use chrono::Utc;
+
+	fn scenario(balance: i64, weekly_deposit: i64, yearly_interest: f32) -> Scenario {
+		Scenario {
+			balance:         Cents(balance),
+			weekly_deposit:  Cents(weekly_deposit),
+			yearly_interest: BigDecimal::from_f32(yearly_interest).unwrap(),
+			tiers:           vec![],
+			compounding:     Compounding::Daily,
+		}
+	}
+
+	fn now() -> NaiveDateTime {
+		NaiveDateTime::from_timestamp(1_560_000_000, 0)
+	}
+
+	#[test]
+	fn it_adds_the_weekly_deposits() {
+		let points = simulate(&scenario(1_000, 500, 0.0), 12, now()).unwrap();
+
+		assert_eq!(points.len(), 12);
+
+		// 52 deposits in a year of 365 days
+		let last = points.last().unwrap();
+
+		assert_eq!(last.at, now() + Duration::days(365));
+		assert_eq!(last.principal, Cents(1_000 + 52 * 500));
+		assert_eq!(last.interest, Cents(0));
+	}
+
+	#[test]
+	fn it_earns_interest_on_the_balance() {
+		let points = simulate(&scenario(10_000, 0, 20.0), 12, now()).unwrap();
+
+		let last = points.last().unwrap();
+
+		// A year at 20% without deposits, less the fraction of a cent still accruing
+		assert_eq!(last.principal, Cents(10_000));
+		assert_eq!(last.interest, Cents(1_999));
+		assert_eq!(last.balance, Cents(11_999));
+	}
+
+	#[test]
+	fn it_pays_the_yearly_rate_whatever_the_compounding() {
+		let monthly = Scenario {
+			compounding: Compounding::Monthly,
+			..scenario(10_000, 0, 20.0)
+		};
+
+		let points = simulate(&monthly, 24, now()).unwrap();
+
+		assert_eq!(points[11].interest, Cents(1_999));
+		assert_eq!(points[23].interest, Cents(4_399));
+	}
+
+	#[test]
+	fn it_doesnt_compound_simple_interest() {
+		let simple = Scenario {
+			compounding: Compounding::Simple,
+			..scenario(10_000, 0, 20.0)
+		};
+
+		let points = simulate(&simple, 24, now()).unwrap();
+
+		// 20% of the principal each year
+		assert_eq!(points.last().unwrap().interest, Cents(4_000));
+	}
+
+	#[test]
+	fn it_rejects_invalid_scenarios() {
+		assert!(simulate(&scenario(1_000, 500, 5.0), 0, now()).is_err());
+		assert!(simulate(&scenario(1_000, 500, 5.0), MAX_MONTHS + 1, now()).is_err());
+		assert!(simulate(&scenario(1_000, -500, 5.0), 12, now()).is_err());
+	}
+
+	#[test]
+	fn it_projects_the_account() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(2_000)
+				.balance(2_000)
+				.save(conn);
+
+			let points = call(conn, &account, 500, 6, Utc::now().naive_utc()).unwrap();
+
+			assert_eq!(points.len(), 6);
+			assert_eq!(points[0].principal, Cents(2_000 + 4 * 500));
+			assert!(points[5].interest > Cents(0));
+		})
+	}
+}
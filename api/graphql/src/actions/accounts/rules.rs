@@ -0,0 +1,91 @@
+use crate::models::account::{Account, Kind, State};
+use chrono::NaiveDateTime;
+use failure::Error;
+
+// Whether money can go into the account
+pub fn check_deposit(account: &Account, now: NaiveDateTime) -> Result<(), Error> {
+	check_active(account)?;
+
+	match (account.kind, account.matures_at) {
+		// The term is over, the funds can only be taken out
+		(Kind::TermDeposit, Some(matures_at)) if now >= matures_at => {
+			Err(format_err!("This term deposit has matured"))
+		},
+		_ => Ok(()),
+	}
+}
+
+// Whether money can come out of the account
+pub fn check_withdrawal(account: &Account, now: NaiveDateTime) -> Result<(), Error> {
+	check_active(account)?;
+
+	match (account.kind, account.matures_at) {
+		(Kind::TermDeposit, Some(matures_at)) if now < matures_at => {
+			Err(format_err!("Funds are locked until {}", matures_at.date()))
+		},
+		_ => Ok(()),
+	}
+}
+
+// Archived accounts are closed to any movement
+pub fn check_active(account: &Account) -> Result<(), Error> {
+	match account.state {
+		State::Active => Ok(()),
+		State::Archived => Err(format_err!("This account is archived")),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{models, utils::tests};
+	use chrono::{Duration, Utc};
+
+	#[test]
+	fn term_deposits_are_locked_until_maturity() {
+		tests::with_db(|conn| {
+			let (user, _) = tests::user(&conn);
+
+			let now = Utc::now().naive_utc();
+
+			let account = models::account::factories::account_attrs(&user)
+				.kind(Kind::TermDeposit)
+				.matures_at(now + Duration::days(30))
+				.save(conn);
+
+			assert!(check_deposit(&account, now).is_ok());
+			assert!(check_withdrawal(&account, now).is_err());
+
+			let later = now + Duration::days(31);
+
+			assert!(check_deposit(&account, later).is_err());
+			assert!(check_withdrawal(&account, later).is_ok());
+		})
+	}
+
+	#[test]
+	fn savings_are_not_locked() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let now = Utc::now().naive_utc();
+
+			assert!(check_deposit(&account, now).is_ok());
+			assert!(check_withdrawal(&account, now).is_ok());
+		})
+	}
+
+	#[test]
+	fn archived_accounts_are_closed() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let account = account.state(conn, State::Archived);
+
+			let now = Utc::now().naive_utc();
+
+			assert!(check_deposit(&account, now).is_err());
+			assert!(check_withdrawal(&account, now).is_err());
+		})
+	}
+}
@@ -0,0 +1,45 @@
+use crate::models::{
+	account::{Account, State},
+	schema as db,
+};
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*};
+use failure::Error;
+
+pub fn call(conn: &PgConnection, account_id: i32) -> Result<Account, Error> {
+	let account = Account::find(&conn, account_id)?;
+
+	if account.state != State::Archived {
+		return Err(format_err!("This account is not archived"));
+	}
+
+	diesel::update(db::accounts::table.find(account_id))
+		.set((
+			db::accounts::state.eq(State::Active),
+			db::accounts::archived_at.eq(None::<NaiveDateTime>),
+		))
+		.get_result(conn)
+		.map_err(|e| format_err!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests;
+
+	#[test]
+	fn it_unarchives_the_account() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			assert!(call(conn, account.id).is_err());
+
+			let account = account.archived_at(conn, account.created_at);
+
+			let unarchived = call(conn, account.id).unwrap();
+
+			assert_eq!(unarchived.state, State::Active);
+			assert_eq!(unarchived.archived_at, None);
+		})
+	}
+}
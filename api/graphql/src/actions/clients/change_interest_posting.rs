@@ -0,0 +1,14 @@
+use crate::models::{client::Client, posting_frequency::PostingFrequency, schema as db};
+use diesel::{self, pg::PgConnection, prelude::*};
+use failure::Error;
+
+pub fn call(
+	conn: &PgConnection,
+	client_id: i32,
+	frequency: PostingFrequency,
+) -> Result<Client, Error> {
+	diesel::update(db::clients::table.find(client_id))
+		.set(db::clients::interest_posting.eq(frequency))
+		.get_result(conn)
+		.map_err(|e| format_err!("{}", e))
+}
@@ -0,0 +1,16 @@
+use crate::models::{client::Client, schema as db};
+use diesel::{self, pg::PgConnection, prelude::*};
+use failure::Error;
+
+const MAX_EXPIRY_DAYS: i32 = 365;
+
+pub fn call(conn: &PgConnection, client_id: i32, days: i32) -> Result<Client, Error> {
+	if days < 1 || days > MAX_EXPIRY_DAYS {
+		return Err(format_err!("Expiry must be between 1 and 365 days"));
+	}
+
+	diesel::update(db::clients::table.find(client_id))
+		.set(db::clients::request_expiry_days.eq(days))
+		.get_result(conn)
+		.map_err(|e| format_err!("{}", e))
+}
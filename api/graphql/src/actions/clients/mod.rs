@@ -0,0 +1,2 @@
+pub mod change_interest_posting;
+pub mod change_request_expiry;
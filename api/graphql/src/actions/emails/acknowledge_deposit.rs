@@ -21,11 +21,20 @@ pub fn call(conn: &PgConnection, transaction: &Transaction) -> Result<(), Error>
 	};
 
 	let Cents(cents) = transaction.amount;
-	let Cents(balance) = transaction.balance;
+
+	// The balance includes the parents' match, if there is one
+	let matched = Transaction::find_match(&conn, transaction.id)?;
+
+	let Cents(balance) = matched
+		.as_ref()
+		.map(|matched| matched.balance)
+		.unwrap_or(transaction.balance);
 
 	let email_kind = EmailKind::AcknowledgeDeposit {
 		amount_in_cents:  cents,
 		balance_in_cents: balance,
+		memo:             transaction.memo.clone(),
+		match_in_cents:   matched.map(|matched| matched.amount.0),
 	};
 
 	let email = Email {
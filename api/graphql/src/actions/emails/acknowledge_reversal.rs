@@ -0,0 +1,33 @@
+use crate::{
+	actions::emails::send,
+	models::{account::Account, cents::Cents, transaction::Transaction, user::User},
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+use shared::emails::{Email, EmailKind};
+
+pub fn call(conn: &PgConnection, reversal: &Transaction) -> Result<(), Error> {
+	let account = Account::find(&conn, reversal.account_id)?;
+	let user = User::find(&conn, account.user_id)?;
+
+	let email_address = match user.email {
+		Some(email) => email,
+		None => return Ok(()),
+	};
+
+	let Cents(cents) = reversal.amount;
+	let Cents(balance) = reversal.balance;
+
+	let email_kind = EmailKind::AcknowledgeReversal {
+		amount_in_cents:  cents,
+		balance_in_cents: balance,
+		account_name:     account.name,
+	};
+
+	let email = Email {
+		to:   email_address.to_string(),
+		kind: email_kind,
+	};
+
+	send::call(&email)
+}
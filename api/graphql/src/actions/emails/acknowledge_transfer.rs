@@ -0,0 +1,45 @@
+use crate::{
+	actions::emails::send,
+	models::{account::Account, cents::Cents, transaction::Transaction, user::User},
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+use shared::emails::{Email, EmailKind};
+
+// One email for each account holder, even when they hold both accounts
+pub fn call(conn: &PgConnection, from: &Transaction, to: &Transaction) -> Result<(), Error> {
+	let from_account = Account::find(&conn, from.account_id)?;
+	let to_account = Account::find(&conn, to.account_id)?;
+
+	let mut user_ids = vec![from_account.user_id];
+
+	if to_account.user_id != from_account.user_id {
+		user_ids.push(to_account.user_id);
+	}
+
+	let Cents(cents) = to.amount;
+
+	for user_id in user_ids {
+		let user = User::find(&conn, user_id)?;
+
+		let email_address = match user.email {
+			Some(email) => email,
+			None => continue,
+		};
+
+		let email_kind = EmailKind::AcknowledgeTransfer {
+			amount_in_cents:   cents,
+			from_account_name: from_account.name.clone(),
+			to_account_name:   to_account.name.clone(),
+		};
+
+		let email = Email {
+			to:   email_address.to_string(),
+			kind: email_kind,
+		};
+
+		send::call(&email)?;
+	}
+
+	Ok(())
+}
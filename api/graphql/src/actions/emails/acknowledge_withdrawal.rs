@@ -27,6 +27,7 @@ pub fn call(conn: &PgConnection, transaction: &Transaction) -> Result<(), Error>
 	let email_kind = EmailKind::AcknowledgeWithdrawal {
 		amount_in_cents:  cents,
 		balance_in_cents: balance,
+		memo:             transaction.memo.clone(),
 	};
 
 	let email = Email {
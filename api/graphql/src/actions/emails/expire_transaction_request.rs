@@ -0,0 +1,41 @@
+use crate::{
+	actions::emails::send,
+	models::{account::Account, cents::Cents, transaction_request::TransactionRequest, user::User},
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+use shared::emails::{Email, EmailKind};
+
+// Tells both the account holder and the admins
+pub fn call(conn: &PgConnection, transaction_request: &TransactionRequest) -> Result<(), Error> {
+	let account = Account::find(&conn, transaction_request.account_id)?;
+	let user = User::find(&conn, account.user_id)?;
+
+	let admins = User::find_admins_by_client_id(&conn, user.client_id)?;
+
+	let Cents(cents) = transaction_request.amount;
+
+	// e.g. "deposit"
+	let request_kind = format!("{:?}", transaction_request.kind).to_lowercase();
+
+	let mut email_addresses = vec![user.email.clone()];
+
+	email_addresses.extend(admins.into_iter().map(|admin| admin.email));
+
+	for email_address in email_addresses.into_iter().filter_map(|email| email) {
+		let email_kind = EmailKind::ExpireTransactionRequest {
+			name:            user.name.clone(),
+			amount_in_cents: cents,
+			request_kind:    request_kind.clone(),
+		};
+
+		let email = Email {
+			to:   email_address,
+			kind: email_kind,
+		};
+
+		send::call(&email)?;
+	}
+
+	Ok(())
+}
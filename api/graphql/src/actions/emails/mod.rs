@@ -1,6 +1,11 @@
 pub mod acknowledge_deposit;
+pub mod acknowledge_reversal;
+pub mod acknowledge_transfer;
 pub mod acknowledge_withdrawal;
 pub mod email_confirmation;
+pub mod expire_transaction_request;
+pub mod reach_goal;
+pub mod request_deposit;
 pub mod request_withdrawal;
 pub mod resolve_transaction_request;
 pub mod send;
@@ -0,0 +1,38 @@
+use crate::{
+	actions::emails::send,
+	models::{account::Account, cents::Cents, goal::Goal, user::User},
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+use shared::emails::{Email, EmailKind};
+
+// Tells both the account holder and the admins
+pub fn call(conn: &PgConnection, goal: &Goal) -> Result<(), Error> {
+	let account = Account::find(&conn, goal.account_id)?;
+	let user = User::find(&conn, account.user_id)?;
+
+	let admins = User::find_admins_by_client_id(&conn, user.client_id)?;
+
+	let Cents(cents) = goal.target;
+
+	let mut email_addresses = vec![user.email.clone()];
+
+	email_addresses.extend(admins.into_iter().map(|admin| admin.email));
+
+	for email_address in email_addresses.into_iter().filter_map(|email| email) {
+		let email_kind = EmailKind::ReachGoal {
+			name:            user.name.clone(),
+			goal_name:       goal.name.clone(),
+			target_in_cents: cents,
+		};
+
+		let email = Email {
+			to:   email_address,
+			kind: email_kind,
+		};
+
+		send::call(&email)?;
+	}
+
+	Ok(())
+}
@@ -24,6 +24,7 @@ pub fn call(conn: &PgConnection, transaction_request: &TransactionRequest) -> Re
 	let email_kind = EmailKind::RequestWithdrawal {
 		name:            user.name,
 		amount_in_cents: cents,
+		memo:            transaction_request.memo.clone(),
 	};
 
 	let email = Email {
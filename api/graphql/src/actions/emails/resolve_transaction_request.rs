@@ -24,10 +24,14 @@ pub fn call(conn: &PgConnection, transaction_request: &TransactionRequest) -> Re
 
 	let Cents(cents) = transaction_request.amount;
 
+	// e.g. "deposit"
+	let request_kind = format!("{:?}", transaction_request.kind).to_lowercase();
+
 	match transaction_request.state {
 		TransactionRequestState::Approved => {
 			let email_kind = EmailKind::ApproveTransactionRequest {
 				amount_in_cents: cents,
+				request_kind:    request_kind,
 			};
 
 			let email = Email {
@@ -41,6 +45,7 @@ pub fn call(conn: &PgConnection, transaction_request: &TransactionRequest) -> Re
 		TransactionRequestState::Rejected => {
 			let email_kind = EmailKind::RejectTransactionRequest {
 				amount_in_cents: cents,
+				request_kind:    request_kind,
 			};
 
 			let email = Email {
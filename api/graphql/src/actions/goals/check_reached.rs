@@ -0,0 +1,134 @@
+use crate::{
+	actions::{accounts::get_balance, emails::reach_goal},
+	models::{cents::Cents, goal::Goal},
+};
+use chrono::prelude::*;
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Marks the account's goals the balance has got to and tells the account holder.
+// Returns the goals reached just now.
+pub fn call(conn: &PgConnection, account_id: i32) -> Result<Vec<Goal>, Error> {
+	let goals = Goal::find_by_account_id(&conn, account_id)?;
+
+	if goals.iter().all(|goal| goal.reached_at.is_some()) {
+		return Ok(vec![]);
+	}
+
+	let balance = Cents(get_balance::call(&conn, account_id)?);
+
+	let now = Utc::now().naive_utc();
+
+	let mut reached = vec![];
+
+	for goal in goals {
+		if goal.reached_at.is_some() || goal.target > balance {
+			continue;
+		}
+
+		// None when another check got there first
+		if let Some(goal) = Goal::reach(&conn, goal.id, now)? {
+			if let Err(e) = reach_goal::call(&conn, &goal) {
+				error!("Failed to email reached goal {}: {}", goal.id, e);
+			}
+
+			reached.push(goal);
+		}
+	}
+
+	Ok(reached)
+}
+
+// Checks every account with a goal left to reach,
+// for balances that grew without a deposit e.g. interest or a transfer
+pub fn all(conn: &PgConnection) -> Result<Vec<Goal>, Error> {
+	let mut account_ids: Vec<i32> = Goal::find_unreached(&conn)?
+		.iter()
+		.map(|goal| goal.account_id)
+		.collect();
+
+	account_ids.dedup();
+
+	let mut reached = vec![];
+
+	for account_id in account_ids {
+		// One account failing shouldn't stop the others
+		match call(&conn, account_id) {
+			Ok(goals) => reached.extend(goals),
+			Err(e) => error!("Failed to check the goals of account {}: {}", account_id, e),
+		}
+	}
+
+	Ok(reached)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		actions::transactions::deposit::{self, DepositInput},
+		models::{self, deposit_source::DepositSource},
+		utils::tests,
+	};
+
+	fn deposit(conn: &PgConnection, account_id: i32, cents: i32) {
+		let input = DepositInput {
+			account_id:      account_id,
+			cents:           cents,
+			effective_at:    None,
+			idempotency_key: None,
+			memo:            None,
+			category:        None,
+		};
+
+		deposit::call(conn, input, DepositSource::Direct).unwrap();
+	}
+
+	#[test]
+	fn it_reaches_a_goal_once() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let bike = models::goal::factories::goal_attrs(&account)
+				.target(1_000)
+				.save(conn);
+
+			let car = models::goal::factories::goal_attrs(&account)
+				.target(100_000)
+				.save(conn);
+
+			deposit(conn, account.id, 600);
+
+			assert_eq!(Goal::find(conn, bike.id).unwrap().reached_at, None);
+
+			// The deposit checks the goals itself
+			deposit(conn, account.id, 600);
+
+			assert!(Goal::find(conn, bike.id).unwrap().reached_at.is_some());
+			assert_eq!(Goal::find(conn, car.id).unwrap().reached_at, None);
+
+			assert_eq!(call(conn, account.id).unwrap(), vec![]);
+		})
+	}
+
+	#[test]
+	fn it_checks_every_account() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(2_000)
+				.balance(2_000)
+				.save(conn);
+
+			let goal = models::goal::factories::goal_attrs(&account)
+				.target(2_000)
+				.save(conn);
+
+			let reached = all(conn).unwrap();
+
+			assert_eq!(reached.len(), 1);
+			assert_eq!(reached[0].id, goal.id);
+		})
+	}
+}
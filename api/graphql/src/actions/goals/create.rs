@@ -0,0 +1,136 @@
+use crate::{
+	actions::accounts::rules,
+	models::{
+		account::Account,
+		cents::Cents,
+		goal::{Goal, GoalAttrs},
+	},
+	utils::dates,
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// target_date is POSIX time in seconds.
+// image_url is a picture of what is being saved for.
+#[derive(GraphQLInputObject, Clone)]
+pub struct GoalInput {
+	pub account_id:  i32,
+	pub name:        String,
+	pub cents:       i32,
+	pub target_date: Option<f64>,
+	pub image_url:   Option<String>,
+}
+
+pub fn call(conn: &PgConnection, input: GoalInput) -> Result<Goal, Error> {
+	let account = Account::find(&conn, input.account_id)?;
+
+	let attrs = attrs(
+		&account,
+		&input.name,
+		input.cents,
+		input.target_date,
+		&input.image_url,
+	)?;
+
+	Goal::create(conn, attrs).map_err(Error::from)
+}
+
+// Validates a goal
+pub fn attrs(
+	account: &Account,
+	name: &str,
+	cents: i32,
+	target_date: Option<f64>,
+	image_url: &Option<String>,
+) -> Result<GoalAttrs, Error> {
+	rules::check_active(account)?;
+
+	let name = name.trim();
+
+	if name.is_empty() {
+		return Err(format_err!("Name the goal"));
+	}
+
+	if cents <= 0 {
+		return Err(format_err!("Invalid amount"));
+	}
+
+	let target_date = match target_date {
+		Some(seconds) => Some(dates::from_seconds(seconds).ok_or(format_err!("Invalid date"))?),
+		None => None,
+	};
+
+	let image_url = image_url
+		.as_ref()
+		.map(|url| url.trim().to_string())
+		.filter(|url| !url.is_empty());
+
+	if let Some(url) = &image_url {
+		if !url.starts_with("https://") && !url.starts_with("http://") {
+			return Err(format_err!("Invalid image URL"));
+		}
+	}
+
+	Ok(GoalAttrs {
+		account_id:  account.id,
+		name:        name.to_string(),
+		target:      Cents(cents as i64),
+		target_date: target_date,
+		image_url:   image_url,
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::utils::tests;
+	use chrono::NaiveDateTime;
+
+	fn input(account_id: i32, name: &str, image_url: Option<&str>) -> GoalInput {
+		GoalInput {
+			account_id:  account_id,
+			name:        name.to_string(),
+			cents:       5_000,
+			target_date: Some(1_600_000_000.0),
+			image_url:   image_url.map(|url| url.to_string()),
+		}
+	}
+
+	#[test]
+	fn it_creates_a_goal() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let goal = call(
+				conn,
+				input(account.id, " Bike ", Some("https://example.com/bike.png")),
+			)
+			.unwrap();
+
+			assert_eq!(goal.name, "Bike");
+			assert_eq!(goal.target, Cents(5_000));
+			assert_eq!(
+				goal.target_date,
+				Some(NaiveDateTime::from_timestamp(1_600_000_000, 0))
+			);
+			assert_eq!(goal.reached_at, None);
+		})
+	}
+
+	#[test]
+	fn it_rejects_invalid_goals() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			assert!(call(conn, input(account.id, "  ", None)).is_err());
+			assert!(call(conn, input(account.id, "Bike", Some("javascript:alert(1)"))).is_err());
+
+			let far_off = GoalInput {
+				target_date: Some(1e20),
+				..input(account.id, "Bike", None)
+			};
+
+			assert!(call(conn, far_off).is_err());
+		})
+	}
+}
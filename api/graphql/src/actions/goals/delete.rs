@@ -0,0 +1,12 @@
+use crate::models::goal::Goal;
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Returns the goal that was removed
+pub fn call(conn: &PgConnection, id: i32) -> Result<Goal, Error> {
+	let goal = Goal::find(&conn, id)?;
+
+	Goal::delete(conn, id)?;
+
+	Ok(goal)
+}
@@ -0,0 +1,5 @@
+pub mod check_reached;
+pub mod create;
+pub mod delete;
+pub mod project;
+pub mod update;
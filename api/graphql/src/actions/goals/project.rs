@@ -0,0 +1,291 @@
+use crate::{
+	actions::accounts::{
+		accrue_interest,
+		calculate_interest::{self, Tier},
+		get_balance,
+	},
+	models::{
+		account::{Account, Compounding},
+		cents::Cents,
+		fixed_point::FixedPoint,
+		goal::Goal,
+		transaction::{Transaction, TransactionKind},
+	},
+};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Duration, NaiveDateTime};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Deposits over this many days set the pace of the projection
+pub const DEPOSIT_WINDOW_DAYS: i64 = 90;
+
+// Goals further away than this aren't projected
+pub const MAX_PROJECTION_YEARS: i64 = 10;
+
+// Percent of the target the balance has got to, at most 100.
+// A goal that has been reached stays at 100.
+pub fn progress(conn: &PgConnection, goal: &Goal) -> Result<f64, Error> {
+	if goal.reached_at.is_some() {
+		return Ok(100.0);
+	}
+
+	let balance = get_balance::call(&conn, goal.account_id)?;
+
+	let Cents(target) = goal.target;
+
+	let percentage = balance.max(0) as f64 * 100.0 / target as f64;
+
+	Ok(percentage.min(100.0))
+}
+
+// When the balance is expected to get to the target,
+// if deposits carry on at their recent pace and the account keeps its rates.
+// None when it won't within MAX_PROJECTION_YEARS.
+pub fn completes_at(
+	conn: &PgConnection,
+	goal: &Goal,
+	now: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>, Error> {
+	if goal.reached_at.is_some() {
+		return Ok(goal.reached_at);
+	}
+
+	let account = Account::find(&conn, goal.account_id)?;
+
+	let balance = get_balance::call(&conn, account.id)?;
+
+	let deposited = deposited_since(&conn, account.id, now - Duration::days(DEPOSIT_WINDOW_DAYS))?;
+
+	// Accounts that don't earn interest only grow with deposits
+	let (yearly_interest, tiers) = if account.kind.earns_interest() {
+		(
+			account.yearly_interest.clone(),
+			accrue_interest::tiers(&conn, &account)?,
+		)
+	} else {
+		(BigDecimal::zero(), vec![])
+	};
+
+	project(
+		balance,
+		goal.target,
+		deposited,
+		&yearly_interest,
+		&tiers,
+		account.compounding,
+		now,
+	)
+}
+
+// Steps a week at a time, adding the interest and the deposits expected that week.
+// The interest is added weekly whatever the compounding, which is close enough for a projection,
+// and like a real payout it carries the fraction of a cent to the next week.
+// `deposited` is the total of the last DEPOSIT_WINDOW_DAYS.
+pub fn project(
+	balance: i64,
+	target: Cents,
+	deposited: i64,
+	yearly_interest: &BigDecimal,
+	tiers: &[Tier],
+	compounding: Compounding,
+	now: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>, Error> {
+	let Cents(target) = target;
+
+	if balance >= target {
+		return Ok(Some(now));
+	}
+
+	// Deposits expected in the first `days`, counted from the start so small ones add up
+	let deposits = |days: i64| deposited * days / DEPOSIT_WINDOW_DAYS;
+
+	let max_days = MAX_PROJECTION_YEARS * 365;
+
+	let mut interest = 0;
+	let mut carried = FixedPoint::zero();
+	let mut elapsed = 0;
+
+	while elapsed < max_days {
+		let at = now + Duration::days(elapsed);
+		let current = balance + deposits(elapsed) + interest;
+
+		let rate = calculate_interest::blended_rate(Cents(current), yearly_interest, tiers)?;
+
+		// Interest paid by `days` from now, and the fraction of a cent left over
+		let grown = |days: i64| -> Result<(i64, FixedPoint), Error> {
+			let earned = carried.checked_add(calculate_interest::precise(
+				Cents(current),
+				&rate,
+				compounding,
+				at,
+				at + Duration::days(days),
+			)?)?;
+
+			let Cents(paid) = earned.to_cents();
+
+			Ok((
+				interest + paid,
+				earned.checked_sub(FixedPoint::from_cents(Cents(paid)))?,
+			))
+		};
+
+		let (week_interest, week_carried) = grown(7)?;
+
+		if balance + deposits(elapsed + 7) + week_interest >= target {
+			// Gets there this week, find the day
+			for day in 1..=7 {
+				let (day_interest, _) = grown(day)?;
+
+				if balance + deposits(elapsed + day) + day_interest >= target {
+					return Ok(Some(at + Duration::days(day)));
+				}
+			}
+		}
+
+		// Nothing deposited and not even a fraction of a cent earned, so it never gets there
+		if deposited <= 0 && week_interest == interest && week_carried == carried {
+			return Ok(None);
+		}
+
+		interest = week_interest;
+		carried = week_carried;
+		elapsed += 7;
+	}
+
+	Ok(None)
+}
+
+// Deposits and matches since `since` that haven't been reversed
+fn deposited_since(
+	conn: &PgConnection,
+	account_id: i32,
+	since: NaiveDateTime,
+) -> Result<i64, Error> {
+	let mut total = 0;
+
+	for transaction in Transaction::find_by_account_id(&conn, account_id, since)? {
+		match transaction.kind {
+			TransactionKind::Deposit | TransactionKind::Match => (),
+			_ => continue,
+		}
+
+		if Transaction::find_reversal(&conn, transaction.id)?.is_none() {
+			let Cents(cents) = transaction.amount;
+			total += cents;
+		}
+	}
+
+	Ok(total)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{models, utils::tests};
+	use bigdecimal::FromPrimitive;
+	use chrono::Utc;
+
+	fn at(days: i64) -> NaiveDateTime {
+		NaiveDateTime::from_timestamp(1_560_000_000, 0) + Duration::days(days)
+	}
+
+	#[test]
+	fn it_projects_from_the_deposit_rate() {
+		let no_interest = BigDecimal::zero();
+
+		// $9 over 90 days is 10c a day, so $50 more takes 500 days
+		let projected = project(
+			5_000,
+			Cents(10_000),
+			900,
+			&no_interest,
+			&[],
+			Compounding::Daily,
+			at(0),
+		)
+		.unwrap();
+
+		assert_eq!(projected, Some(at(500)));
+	}
+
+	#[test]
+	fn it_adds_interest() {
+		let interest = BigDecimal::from_f32(20.0).unwrap();
+
+		// 20% a year on $100 gets to $120 in about a year without any deposits
+		let projected = project(
+			10_000,
+			Cents(12_000),
+			0,
+			&interest,
+			&[],
+			Compounding::Daily,
+			at(0),
+		)
+		.unwrap()
+		.unwrap();
+
+		assert!(projected > at(350) && projected < at(380));
+	}
+
+	#[test]
+	fn it_adds_up_interest_of_less_than_a_cent() {
+		let interest = BigDecimal::from_f32(10.0).unwrap();
+
+		// $1 at 10% earns a cent in about 5 weeks
+		let projected = project(
+			100,
+			Cents(101),
+			0,
+			&interest,
+			&[],
+			Compounding::Daily,
+			at(0),
+		)
+		.unwrap()
+		.unwrap();
+
+		assert!(projected > at(30) && projected < at(45));
+	}
+
+	#[test]
+	fn it_doesnt_project_a_goal_that_wont_be_reached() {
+		let no_interest = BigDecimal::zero();
+
+		let projected = project(
+			5_000,
+			Cents(10_000),
+			0,
+			&no_interest,
+			&[],
+			Compounding::Daily,
+			at(0),
+		)
+		.unwrap();
+
+		assert_eq!(projected, None);
+	}
+
+	#[test]
+	fn it_shows_progress() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _transaction = models::transaction::factories::transaction_attrs(&account)
+				.amount(2_500)
+				.balance(2_500)
+				.save(conn);
+
+			let goal = models::goal::factories::goal_attrs(&account)
+				.target(10_000)
+				.save(conn);
+
+			assert_eq!(progress(conn, &goal).unwrap(), 25.0);
+
+			let now = Utc::now().naive_utc();
+
+			assert!(completes_at(conn, &goal, now).unwrap().is_some());
+		})
+	}
+}
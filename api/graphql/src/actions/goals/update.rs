@@ -0,0 +1,36 @@
+use super::create;
+use crate::models::{account::Account, goal::Goal};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Replaces the goal, a new target has to be reached again
+#[derive(GraphQLInputObject, Clone)]
+pub struct UpdateGoalInput {
+	pub goal_id:     i32,
+	pub name:        String,
+	pub cents:       i32,
+	pub target_date: Option<f64>,
+	pub image_url:   Option<String>,
+}
+
+pub fn call(conn: &PgConnection, input: UpdateGoalInput) -> Result<Goal, Error> {
+	let goal = Goal::find(&conn, input.goal_id)?;
+
+	let account = Account::find(&conn, goal.account_id)?;
+
+	let attrs = create::attrs(
+		&account,
+		&input.name,
+		input.cents,
+		input.target_date,
+		&input.image_url,
+	)?;
+
+	let reached_at = if attrs.target == goal.target {
+		goal.reached_at
+	} else {
+		None
+	};
+
+	Goal::update(conn, goal.id, attrs, reached_at).map_err(Error::from)
+}
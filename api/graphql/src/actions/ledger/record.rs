@@ -0,0 +1,161 @@
+use crate::models::{
+	account::Account,
+	cents::Cents,
+	journal_entry::{JournalEntry, JournalEntryAttrs},
+	posting::{Posting, PostingAttrs},
+	transaction::{Transaction, TransactionAttrs, TransactionKind},
+	user::User,
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Every transaction is inserted through here.
+// The legs share one journal entry, each posting its balance change to its account.
+// Whatever the legs don't balance between themselves is posted to the family bank,
+// so a deposit is funded by the bank and a transfer only moves money between accounts.
+// Run it inside a database transaction.
+pub fn call(
+	conn: &PgConnection,
+	kind: TransactionKind,
+	legs: Vec<TransactionAttrs>,
+) -> Result<Vec<Transaction>, Error> {
+	let first = legs.first().ok_or(format_err!("Nothing to record"))?;
+
+	let client_id = client_id_of(&conn, first.account_id)?;
+
+	let entry_attrs = JournalEntryAttrs {
+		client_id: client_id,
+		kind:      kind,
+	};
+
+	let entry = JournalEntry::create(conn, entry_attrs)?;
+
+	let mut transactions = vec![];
+	let mut total = Cents(0);
+
+	for attrs in legs {
+		if client_id != client_id_of(&conn, attrs.account_id)? {
+			return Err(format_err!("Cannot record across clients"));
+		}
+
+		let transaction = Transaction::create(conn, attrs)?;
+
+		let change = transaction.balance_change();
+
+		let posting_attrs = PostingAttrs {
+			journal_entry_id: entry.id,
+			account_id:       Some(transaction.account_id),
+			transaction_id:   Some(transaction.id),
+			amount:           change,
+		};
+
+		Posting::create(conn, posting_attrs)?;
+
+		total = total + change;
+
+		transactions.push(transaction);
+	}
+
+	if total != Cents(0) {
+		let bank_attrs = PostingAttrs {
+			journal_entry_id: entry.id,
+			account_id:       None,
+			transaction_id:   None,
+			amount:           Cents(0) - total,
+		};
+
+		Posting::create(conn, bank_attrs)?;
+	}
+
+	Ok(transactions)
+}
+
+// Records a single transaction
+pub fn one(conn: &PgConnection, attrs: TransactionAttrs) -> Result<Transaction, Error> {
+	let kind = attrs.kind;
+
+	let mut transactions = call(&conn, kind, vec![attrs])?;
+
+	transactions
+		.pop()
+		.ok_or(format_err!("Failed to record the transaction"))
+}
+
+fn client_id_of(conn: &PgConnection, account_id: i32) -> Result<i32, Error> {
+	let account = Account::find(&conn, account_id)?;
+
+	let user = User::find(&conn, account.user_id)?;
+
+	Ok(user.client_id)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{models, utils::tests};
+	use bigdecimal::{BigDecimal, Zero};
+
+	#[test]
+	fn the_family_bank_funds_deposits() {
+		tests::with_db(|conn| {
+			let (account, _, client) = tests::account(&conn);
+
+			let attrs = TransactionAttrs {
+				account_id:            account.id,
+				kind:                  TransactionKind::Deposit,
+				amount:                Cents(500),
+				balance:               Cents(500),
+				accrued_interest:      BigDecimal::zero(),
+				linked_transaction_id: None,
+				created_at:            None,
+				idempotency_key:       None,
+				memo:                  None,
+				category_id:           None,
+			};
+
+			one(conn, attrs).unwrap();
+
+			let balance = Posting::balance_by_account_id(conn, account.id).unwrap();
+			let bank = Posting::family_bank_balance_by_client_id(conn, client.id, None).unwrap();
+
+			assert_eq!(balance, Cents(500));
+			assert_eq!(bank, Cents(-500));
+		})
+	}
+
+	#[test]
+	fn transfers_dont_touch_the_family_bank() {
+		tests::with_db(|conn| {
+			let (account, user, client) = tests::account(&conn);
+
+			let other = models::account::factories::account_attrs(&user).save(conn);
+
+			let leg = |account_id, amount| {
+				TransactionAttrs {
+					account_id:            account_id,
+					kind:                  TransactionKind::Transfer,
+					amount:                Cents(amount),
+					balance:               Cents(amount),
+					accrued_interest:      BigDecimal::zero(),
+					linked_transaction_id: None,
+					created_at:            None,
+					idempotency_key:       None,
+					memo:                  None,
+					category_id:           None,
+				}
+			};
+
+			let legs = vec![leg(account.id, -200), leg(other.id, 200)];
+
+			call(conn, TransactionKind::Transfer, legs).unwrap();
+
+			let bank = Posting::family_bank_balance_by_client_id(conn, client.id, None).unwrap();
+
+			assert_eq!(bank, Cents(0));
+			assert_eq!(
+				Posting::balance_by_account_id(conn, other.id).unwrap(),
+				Cents(200)
+			);
+		})
+	}
+}
@@ -0,0 +1,83 @@
+use super::verify;
+use crate::{
+	models::{account::Account, posting::Posting, transaction::Transaction},
+	utils::db_transaction,
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Rebuilds the stored balances of the account from what has been posted to it.
+// Returns the transactions that changed.
+pub fn call(conn: &PgConnection, account_id: i32) -> Result<Vec<Transaction>, Error> {
+	db_transaction::serializable(&conn, || {
+		// No new transactions while rebuilding
+		Account::lock(&conn, account_id)?;
+
+		let transactions = Transaction::find_all_by_account_id(&conn, account_id)?;
+
+		let postings = Posting::find_by_account_id(&conn, account_id)?;
+
+		let mut repaired = vec![];
+
+		for (transaction, balance) in verify::posted_balances(&transactions, &postings) {
+			if transaction.balance != balance {
+				info!(
+					"Repairing transaction {} balance from {} to {}",
+					transaction.id,
+					transaction.balance.to_string(),
+					balance.to_string()
+				);
+
+				repaired.push(Transaction::set_balance(&conn, transaction.id, balance)?);
+			}
+		}
+
+		Ok(repaired)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{self, cents::Cents, transaction::TransactionKind},
+		utils::tests,
+	};
+
+	#[test]
+	fn it_rebuilds_the_balances() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			let broken = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Withdrawal)
+				.amount(100)
+				.balance(200)
+				.save(conn);
+
+			let following = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Interest)
+				.amount(10)
+				.balance(210)
+				.save(conn);
+
+			// The stored balances drift from what was posted
+			Transaction::set_balance(conn, broken.id, Cents(250)).unwrap();
+			Transaction::set_balance(conn, following.id, Cents(260)).unwrap();
+
+			let repaired = call(conn, account.id).unwrap();
+
+			let ids: Vec<i32> = repaired.iter().map(|t| t.id).collect();
+
+			assert_eq!(ids, vec![broken.id, following.id]);
+			assert_eq!(repaired[1].balance, Cents(210));
+
+			assert_eq!(verify::call(conn, account.id).unwrap(), vec![]);
+		})
+	}
+}
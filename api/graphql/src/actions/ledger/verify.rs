@@ -0,0 +1,239 @@
+use crate::models::{
+	account::Account, cents::Cents, client::Client, posting::Posting, transaction::Transaction,
+	user::User,
+};
+use diesel::{pg::PgConnection, prelude::*};
+use failure::Error;
+use std::collections::HashMap;
+
+#[derive(GraphQLEnum, Debug, Copy, Clone, PartialEq)]
+pub enum LedgerBreakKind {
+	// The stored balance isn't the previous balance plus the amount
+	Chain,
+	// The stored balance isn't what has been posted up to the transaction
+	Posting,
+}
+
+// A transaction whose stored balance doesn't add up
+#[derive(GraphQLObject, Debug, Clone, PartialEq)]
+pub struct LedgerBreak {
+	pub account_id:       i32,
+	pub transaction_id:   i32,
+	pub kind:             LedgerBreakKind,
+	pub expected_balance: Cents,
+	pub stored_balance:   Cents,
+}
+
+// Walks the transactions of the account checking that each balance is
+// the previous balance plus the change, and what the journal has posted
+pub fn call(conn: &PgConnection, account_id: i32) -> Result<Vec<LedgerBreak>, Error> {
+	let transactions = Transaction::find_all_by_account_id(&conn, account_id)?;
+
+	let postings = Posting::find_by_account_id(&conn, account_id)?;
+
+	Ok(check(account_id, &transactions, &postings))
+}
+
+pub fn for_client(conn: &PgConnection, client: &Client) -> Result<Vec<LedgerBreak>, Error> {
+	let users = User::belonging_to(client).load::<User>(conn)?;
+
+	let accounts = Account::belonging_to(&users).load::<Account>(conn)?;
+
+	let mut breaks = vec![];
+
+	for account in accounts {
+		breaks.extend(call(&conn, account.id)?);
+	}
+
+	Ok(breaks)
+}
+
+pub fn all(conn: &PgConnection) -> Result<Vec<LedgerBreak>, Error> {
+	let mut breaks = vec![];
+
+	for client in Client::all(&conn)? {
+		breaks.extend(for_client(&conn, &client)?);
+	}
+
+	Ok(breaks)
+}
+
+// Expects the transactions oldest first.
+// Each chain break is reported against the previous stored balance,
+// so one bad row doesn't make every following row a break.
+// An amount that disagrees with its postings breaks the chain,
+// a balance that drifted from the postings breaks both.
+pub fn check(
+	account_id: i32,
+	transactions: &[Transaction],
+	postings: &[Posting],
+) -> Vec<LedgerBreak> {
+	let mut breaks = vec![];
+	let mut previous = Cents(0);
+
+	for (transaction, posted) in posted_balances(transactions, postings) {
+		let chained = previous + transaction.balance_change();
+
+		if transaction.balance != chained {
+			breaks.push(LedgerBreak {
+				account_id:       account_id,
+				transaction_id:   transaction.id,
+				kind:             LedgerBreakKind::Chain,
+				expected_balance: chained,
+				stored_balance:   transaction.balance,
+			});
+		}
+
+		if transaction.balance != posted {
+			breaks.push(LedgerBreak {
+				account_id:       account_id,
+				transaction_id:   transaction.id,
+				kind:             LedgerBreakKind::Posting,
+				expected_balance: posted,
+				stored_balance:   transaction.balance,
+			});
+		}
+
+		previous = transaction.balance;
+	}
+
+	breaks
+}
+
+// Each transaction with the balance posted up to and including it.
+// Expects the transactions oldest first.
+pub fn posted_balances<'a>(
+	transactions: &'a [Transaction],
+	postings: &[Posting],
+) -> Vec<(&'a Transaction, Cents)> {
+	let mut posted = HashMap::new();
+
+	for posting in postings {
+		if let Some(transaction_id) = posting.transaction_id {
+			let total = posted.entry(transaction_id).or_insert(Cents(0));
+			*total = *total + posting.amount;
+		}
+	}
+
+	let mut balance = Cents(0);
+
+	transactions
+		.iter()
+		.map(|transaction| {
+			balance = balance + posted.get(&transaction.id).cloned().unwrap_or(Cents(0));
+
+			(transaction, balance)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{self, schema::transactions, transaction::TransactionKind},
+		utils::tests,
+	};
+
+	#[test]
+	fn it_finds_no_breaks_in_a_good_chain() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			let _withdrawal = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Withdrawal)
+				.amount(100)
+				.balance(200)
+				.save(conn);
+
+			assert_eq!(call(conn, account.id).unwrap(), vec![]);
+		})
+	}
+
+	#[test]
+	fn it_reports_breaks() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			let broken = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Withdrawal)
+				.amount(100)
+				.balance(200)
+				.save(conn);
+
+			let interest = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Interest)
+				.amount(10)
+				.balance(210)
+				.save(conn);
+
+			// The stored balance drifts from what was posted
+			Transaction::set_balance(conn, broken.id, Cents(250)).unwrap();
+
+			let expected = vec![
+				LedgerBreak {
+					account_id:       account.id,
+					transaction_id:   broken.id,
+					kind:             LedgerBreakKind::Chain,
+					expected_balance: Cents(200),
+					stored_balance:   Cents(250),
+				},
+				LedgerBreak {
+					account_id:       account.id,
+					transaction_id:   broken.id,
+					kind:             LedgerBreakKind::Posting,
+					expected_balance: Cents(200),
+					stored_balance:   Cents(250),
+				},
+				// The following row is checked against the stored balance
+				LedgerBreak {
+					account_id:       account.id,
+					transaction_id:   interest.id,
+					kind:             LedgerBreakKind::Chain,
+					expected_balance: Cents(260),
+					stored_balance:   Cents(210),
+				},
+			];
+
+			assert_eq!(call(conn, account.id).unwrap(), expected);
+		})
+	}
+
+	#[test]
+	fn it_reports_an_amount_that_disagrees_with_its_postings() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			// The balance still matches the postings, the amount doesn't
+			diesel::update(transactions::table.find(deposit.id))
+				.set(transactions::amount.eq(Cents(200)))
+				.execute(conn)
+				.unwrap();
+
+			let expected = LedgerBreak {
+				account_id:       account.id,
+				transaction_id:   deposit.id,
+				kind:             LedgerBreakKind::Chain,
+				expected_balance: Cents(200),
+				stored_balance:   Cents(300),
+			};
+
+			assert_eq!(call(conn, account.id).unwrap(), vec![expected]);
+		})
+	}
+}
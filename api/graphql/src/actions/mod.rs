@@ -1,7 +1,11 @@
 pub mod accounts;
+pub mod clients;
 pub mod emails;
+pub mod goals;
 pub mod invitations;
+pub mod ledger;
 pub mod passwords;
+pub mod scheduled_transactions;
 pub mod sign_ins;
 pub mod sign_ups;
 pub mod transactions;
@@ -0,0 +1,15 @@
+use crate::models::scheduled_transaction::ScheduledTransaction;
+use chrono::prelude::*;
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Stops the rule, its runs are kept
+pub fn call(conn: &PgConnection, id: i32) -> Result<ScheduledTransaction, Error> {
+	let rule = ScheduledTransaction::find(&conn, id)?;
+
+	if rule.archived_at.is_some() {
+		return Err(format_err!("Already removed"));
+	}
+
+	ScheduledTransaction::archive(conn, id, Utc::now().naive_utc()).map_err(Error::from)
+}
@@ -0,0 +1,153 @@
+use crate::{
+	actions::accounts::rules,
+	models::{
+		account::Account,
+		cents::Cents,
+		schedule_frequency::ScheduleFrequency,
+		scheduled_transaction::{ScheduledTransaction, ScheduledTransactionAttrs},
+	},
+	utils::dates,
+};
+use chrono::prelude::*;
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// starts_at is POSIX time in seconds, defaults to now.
+// A schedule starting in the past starts now, missed dates aren't deposited.
+// day_of_month is required with DayOfMonth and not allowed otherwise.
+#[derive(GraphQLInputObject, Clone)]
+pub struct ScheduledTransactionInput {
+	pub account_id:   i32,
+	pub cents:        i32,
+	pub frequency:    ScheduleFrequency,
+	pub day_of_month: Option<i32>,
+	pub starts_at:    Option<f64>,
+}
+
+pub fn call(
+	conn: &PgConnection,
+	input: ScheduledTransactionInput,
+) -> Result<ScheduledTransaction, Error> {
+	let account = Account::find(&conn, input.account_id)?;
+
+	let attrs = attrs(
+		&account,
+		input.cents,
+		input.frequency,
+		input.day_of_month,
+		input.starts_at,
+	)?;
+
+	ScheduledTransaction::create(conn, attrs).map_err(Error::from)
+}
+
+// Validates a schedule and works out its first run
+pub fn attrs(
+	account: &Account,
+	cents: i32,
+	frequency: ScheduleFrequency,
+	day_of_month: Option<i32>,
+	starts_at: Option<f64>,
+) -> Result<ScheduledTransactionAttrs, Error> {
+	if cents <= 0 {
+		return Err(format_err!("Invalid amount"));
+	}
+
+	rules::check_active(account)?;
+
+	match (frequency, day_of_month) {
+		(ScheduleFrequency::DayOfMonth, Some(day)) if day >= 1 && day <= 31 => (),
+		(ScheduleFrequency::DayOfMonth, _) => {
+			return Err(format_err!("Choose a day of the month between 1 and 31"))
+		},
+		(_, Some(_)) => return Err(format_err!("Only DayOfMonth takes a day")),
+		(_, None) => (),
+	}
+
+	let now = Utc::now().naive_utc();
+
+	let starts_at = match starts_at {
+		Some(seconds) => {
+			dates::from_seconds(seconds)
+				.ok_or(format_err!("Invalid date"))?
+				.max(now)
+		},
+		None => now,
+	};
+
+	let next_run_at = frequency.first_at(starts_at, day_of_month.map(|day| day as u32));
+
+	Ok(ScheduledTransactionAttrs {
+		account_id:   account.id,
+		amount:       Cents(cents as i64),
+		frequency:    frequency,
+		day_of_month: day_of_month,
+		starts_at:    starts_at,
+		next_run_at:  Some(next_run_at),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests;
+
+	#[test]
+	fn it_creates_a_weekly_allowance() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let input = ScheduledTransactionInput {
+				account_id:   account.id,
+				cents:        500,
+				frequency:    ScheduleFrequency::Weekly,
+				day_of_month: None,
+				starts_at:    None,
+			};
+
+			let rule = call(conn, input).unwrap();
+
+			assert_eq!(rule.amount, Cents(500));
+			assert_eq!(rule.next_run_at, Some(rule.starts_at));
+		})
+	}
+
+	#[test]
+	fn it_starts_a_past_schedule_now() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let input = ScheduledTransactionInput {
+				account_id:   account.id,
+				cents:        500,
+				frequency:    ScheduleFrequency::Weekly,
+				day_of_month: None,
+				starts_at:    Some(1_000_000_000.0),
+			};
+
+			let before = Utc::now().naive_utc();
+
+			let rule = call(conn, input).unwrap();
+
+			assert!(rule.starts_at >= before);
+			assert_eq!(rule.next_run_at, Some(rule.starts_at));
+		})
+	}
+
+	#[test]
+	fn day_of_month_needs_a_day() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let input = ScheduledTransactionInput {
+				account_id:   account.id,
+				cents:        500,
+				frequency:    ScheduleFrequency::DayOfMonth,
+				day_of_month: None,
+				starts_at:    None,
+			};
+
+			assert!(call(conn, input).is_err());
+		})
+	}
+}
@@ -0,0 +1,4 @@
+pub mod archive;
+pub mod create;
+pub mod run_due;
+pub mod update;
@@ -0,0 +1,224 @@
+use crate::{
+	actions::transactions::deposit::{self, DepositInput},
+	models::{
+		cents::Cents,
+		deposit_source::DepositSource,
+		scheduled_transaction::ScheduledTransaction,
+		scheduled_transaction_run::{ScheduledTransactionRun, ScheduledTransactionRunAttrs},
+		transaction::Transaction,
+	},
+};
+use chrono::prelude::*;
+use diesel::{pg::PgConnection, Connection};
+use failure::Error;
+
+enum Step {
+	// With the deposit it made, if it succeeded
+	Ran(ScheduledTransactionRun, Option<Transaction>),
+	// Already ran for that date
+	Skipped,
+	Done,
+}
+
+// Runs every rule that is due, catching up missed dates one at a time.
+// Each run is logged, and a rule never runs twice for the same date.
+pub fn call(conn: &PgConnection) -> Result<Vec<ScheduledTransactionRun>, Error> {
+	let now = Utc::now().naive_utc();
+
+	let mut runs = vec![];
+
+	for rule in ScheduledTransaction::find_due(&conn, now)? {
+		// One rule failing shouldn't stop the others
+		match run_rule(&conn, rule.id, now) {
+			Ok(rule_runs) => runs.extend(rule_runs),
+			Err(e) => error!("Failed to run scheduled transaction {}: {}", rule.id, e),
+		}
+	}
+
+	Ok(runs)
+}
+
+fn run_rule(
+	conn: &PgConnection,
+	id: i32,
+	now: NaiveDateTime,
+) -> Result<Vec<ScheduledTransactionRun>, Error> {
+	let mut runs = vec![];
+
+	loop {
+		match conn.transaction(|| step(&conn, id, now))? {
+			Step::Ran(run, deposited) => {
+				// Only once the deposit has been committed
+				if let Some(transaction) = deposited {
+					if let Err(e) = deposit::acknowledge(&conn, &transaction) {
+						error!("Failed to acknowledge deposit {}: {}", transaction.id, e);
+					}
+				}
+
+				runs.push(run);
+			},
+			Step::Skipped => (),
+			Step::Done => break,
+		}
+	}
+
+	Ok(runs)
+}
+
+// Runs the rule for its next date if that is due, and moves it on to the date after
+fn step(conn: &PgConnection, id: i32, now: NaiveDateTime) -> Result<Step, Error> {
+	let rule = ScheduledTransaction::lock(&conn, id)?;
+
+	let run_for = match rule.next_run_at {
+		Some(at) if at <= now && rule.archived_at.is_none() => at,
+		_ => return Ok(Step::Done),
+	};
+
+	let day_of_month = rule.day_of_month.map(|day| day as u32);
+
+	let next_run_at = rule
+		.frequency
+		.next_after(rule.starts_at, day_of_month, run_for);
+
+	ScheduledTransaction::set_next_run_at(&conn, rule.id, next_run_at)?;
+
+	let run_attrs = ScheduledTransactionRunAttrs {
+		scheduled_transaction_id: rule.id,
+		run_for:                  run_for,
+	};
+
+	let run = match ScheduledTransactionRun::start(&conn, run_attrs)? {
+		Some(run) => run,
+		None => return Ok(Step::Skipped),
+	};
+
+	let Cents(cents) = rule.amount;
+
+	let input = DepositInput {
+		account_id:      rule.account_id,
+		cents:           cents as i32,
+		effective_at:    None,
+		idempotency_key: None,
+		memo:            None,
+		category:        None,
+	};
+
+	// A failed deposit is logged with the run and doesn't undo it
+	match deposit::record(&conn, &input, DepositSource::Scheduled) {
+		Ok((transaction, _)) => {
+			info!("Scheduled transaction {} deposited {}", rule.id, cents);
+
+			let run = ScheduledTransactionRun::finish(&conn, run.id, Some(transaction.id), None)?;
+
+			Ok(Step::Ran(run, Some(transaction)))
+		},
+		Err(e) => {
+			error!("Scheduled transaction {} failed: {}", rule.id, e);
+
+			let run = ScheduledTransactionRun::finish(&conn, run.id, None, Some(e.to_string()))?;
+
+			Ok(Step::Ran(run, None))
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		actions::accounts,
+		models::{self, schedule_frequency::ScheduleFrequency},
+		utils::tests,
+	};
+	use chrono::Duration;
+
+	#[test]
+	fn it_deposits_the_allowance_once() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let rule =
+				models::scheduled_transaction::factories::scheduled_transaction_attrs(&account)
+					.next_run_at(Utc::now().naive_utc() - Duration::minutes(1))
+					.save(conn);
+
+			let runs = call(conn).unwrap();
+
+			assert_eq!(runs.len(), 1);
+			assert!(runs[0].transaction_id.is_some());
+
+			// Not due again for a week
+			assert_eq!(call(conn).unwrap().len(), 0);
+
+			assert_eq!(accounts::get_balance::call(conn, account.id).unwrap(), 500);
+
+			let rule = ScheduledTransaction::find(conn, rule.id).unwrap();
+
+			assert_eq!(rule.next_run_at, Some(runs[0].run_for + Duration::days(7)));
+		})
+	}
+
+	#[test]
+	fn it_catches_up_missed_runs() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _rule =
+				models::scheduled_transaction::factories::scheduled_transaction_attrs(&account)
+					.next_run_at(Utc::now().naive_utc() - Duration::days(15))
+					.save(conn);
+
+			let runs = call(conn).unwrap();
+
+			assert_eq!(runs.len(), 3);
+			assert_eq!(
+				accounts::get_balance::call(conn, account.id).unwrap(),
+				1_500
+			);
+		})
+	}
+
+	#[test]
+	fn a_one_off_doesnt_run_again() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let rule =
+				models::scheduled_transaction::factories::scheduled_transaction_attrs(&account)
+					.frequency(ScheduleFrequency::Once)
+					.next_run_at(Utc::now().naive_utc() - Duration::minutes(1))
+					.save(conn);
+
+			assert_eq!(call(conn).unwrap().len(), 1);
+
+			let rule = ScheduledTransaction::find(conn, rule.id).unwrap();
+
+			assert_eq!(rule.next_run_at, None);
+		})
+	}
+
+	#[test]
+	fn it_doesnt_run_twice_for_the_same_date() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let run_for = Utc::today().naive_utc().and_hms(0, 0, 0);
+
+			let rule =
+				models::scheduled_transaction::factories::scheduled_transaction_attrs(&account)
+					.frequency(ScheduleFrequency::Once)
+					.next_run_at(run_for)
+					.save(conn);
+
+			let run_attrs = ScheduledTransactionRunAttrs {
+				scheduled_transaction_id: rule.id,
+				run_for:                  run_for,
+			};
+
+			ScheduledTransactionRun::start(conn, run_attrs).unwrap();
+
+			assert_eq!(call(conn).unwrap().len(), 0);
+			assert_eq!(accounts::get_balance::call(conn, account.id).unwrap(), 0);
+		})
+	}
+}
@@ -0,0 +1,40 @@
+use super::create;
+use crate::models::{
+	account::Account, schedule_frequency::ScheduleFrequency,
+	scheduled_transaction::ScheduledTransaction,
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Replaces the schedule, runs start again from starts_at or now if that has gone by
+#[derive(GraphQLInputObject, Clone)]
+pub struct UpdateScheduledTransactionInput {
+	pub scheduled_transaction_id: i32,
+	pub cents:                    i32,
+	pub frequency:                ScheduleFrequency,
+	pub day_of_month:             Option<i32>,
+	pub starts_at:                Option<f64>,
+}
+
+pub fn call(
+	conn: &PgConnection,
+	input: UpdateScheduledTransactionInput,
+) -> Result<ScheduledTransaction, Error> {
+	let rule = ScheduledTransaction::find(&conn, input.scheduled_transaction_id)?;
+
+	if rule.archived_at.is_some() {
+		return Err(format_err!("This scheduled transaction has been removed"));
+	}
+
+	let account = Account::find(&conn, rule.account_id)?;
+
+	let attrs = create::attrs(
+		&account,
+		input.cents,
+		input.frequency,
+		input.day_of_month,
+		input.starts_at,
+	)?;
+
+	ScheduledTransaction::update(conn, rule.id, attrs).map_err(Error::from)
+}
@@ -0,0 +1,410 @@
+use crate::{
+	actions::{accounts, ledger, transactions::describe::Description},
+	models::{
+		account::{Account, Compounding},
+		cents::Cents,
+		fixed_point::FixedPoint,
+		transaction::{Transaction, TransactionAttrs, TransactionKind},
+	},
+};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::prelude::*;
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Inserts a deposit, its match, or a withdrawal that happened at `effective_at`.
+// The balances after it move by the amount, the interest is accrued again from there,
+// and the difference to the interest paid since is corrected by an interest transaction
+// recorded now.
+// Expects the account to be locked.
+pub fn call(
+	conn: &PgConnection,
+	account: &Account,
+	kind: TransactionKind,
+	cents: i64,
+	effective_at: NaiveDateTime,
+	idempotency_key: Option<String>,
+	description: Description,
+) -> Result<Transaction, Error> {
+	let now = Utc::now().naive_utc();
+
+	if effective_at > now {
+		return Err(format_err!("Transactions cannot be dated in the future"));
+	}
+
+	if effective_at < account.created_at {
+		return Err(format_err!(
+			"Transactions cannot be dated before the account was opened"
+		));
+	}
+
+	let change = match kind {
+		TransactionKind::Deposit | TransactionKind::Match => cents,
+		TransactionKind::Withdrawal => -cents,
+		_ => {
+			return Err(format_err!(
+				"Only deposits and withdrawals can be back-dated"
+			))
+		},
+	};
+
+	// Settle the interest up to now first
+	accounts::pay_interest::call(&conn, account.id)?;
+
+	let previous = Transaction::find_last_by_account_id_at(&conn, account.id, effective_at)?;
+
+	let Cents(previous_balance) = previous
+		.as_ref()
+		.map_or(Cents(0), |previous| previous.balance);
+
+	// What it carries is set when the interest is accrued again
+	let attrs = TransactionAttrs {
+		account_id:            account.id,
+		kind:                  kind,
+		amount:                Cents(cents),
+		balance:               Cents(previous_balance + change),
+		accrued_interest:      BigDecimal::zero(),
+		linked_transaction_id: None,
+		created_at:            Some(effective_at),
+		idempotency_key:       idempotency_key,
+		memo:                  description.memo,
+		category_id:           description.category_id,
+	};
+
+	let transaction = ledger::record::one(&conn, attrs)?;
+
+	let later = Transaction::find_by_account_id(&conn, account.id, effective_at)?
+		.into_iter()
+		.filter(|t| t.created_at > effective_at);
+
+	let mut balances = vec![transaction.balance];
+
+	for t in later {
+		let Cents(balance) = t.balance;
+
+		let t = Transaction::set_balance(&conn, t.id, Cents(balance + change))?;
+
+		balances.push(t.balance);
+	}
+
+	// A withdrawal must be covered at every point since
+	if balances.into_iter().any(|balance| balance < Cents(0)) {
+		return Err(format_err!("Not enough balance"));
+	}
+
+	adjust_interest(&conn, account, previous.as_ref(), &transaction, now)
+}
+
+// Accrues the interest again from the transaction before the back-dated one until `now`,
+// the same way it is accrued as the transactions are made, on the corrected balances.
+// Each transaction since gets what it should have carried, and the interest that
+// should have been credited, less what was paid, is credited now.
+// Returns the back-dated transaction as it was corrected.
+fn adjust_interest(
+	conn: &PgConnection,
+	account: &Account,
+	previous: Option<&Transaction>,
+	transaction: &Transaction,
+	now: NaiveDateTime,
+) -> Result<Transaction, Error> {
+	let chain = Transaction::find_by_account_id(&conn, account.id, transaction.created_at)?
+		.into_iter()
+		.filter(|t| t.id == transaction.id || t.created_at > transaction.created_at)
+		.collect::<Vec<Transaction>>();
+
+	let (mut since, mut carried, mut balance) = match previous {
+		Some(previous) => {
+			(
+				previous.created_at,
+				FixedPoint::from_big_decimal(&previous.accrued_interest)?,
+				previous.balance,
+			)
+		},
+		None => (transaction.created_at, FixedPoint::zero(), Cents(0)),
+	};
+
+	let rates = accounts::accrue_interest::rates_since(
+		&conn,
+		account,
+		accounts::accrue_interest::day_start(account, since),
+	)?;
+
+	let tiers = accounts::accrue_interest::tiers(&conn, account)?;
+
+	// Interest paid before the chain, simple interest doesn't earn on it
+	let paid_in_chain = interest_paid(&chain);
+	let paid_before = Transaction::total_interest_by_account_id(&conn, account.id)? - paid_in_chain;
+
+	// Interest that should have been credited, and what was, since `previous`
+	let mut due = Cents(0);
+	let mut paid = Cents(0);
+
+	let mut corrected = transaction.clone();
+
+	for t in &chain {
+		let earning = earning_balance(account, balance, paid_before + paid, due - paid);
+
+		let accrual = accounts::accrue_interest::accrue(
+			account,
+			earning,
+			carried,
+			&rates,
+			&tiers,
+			since,
+			t.created_at,
+		)?;
+
+		due = due + accrual.payable;
+		carried = accrual.carried;
+
+		if t.kind == TransactionKind::Interest {
+			paid = paid + t.amount;
+		}
+
+		let t = Transaction::set_accrued_interest(&conn, t.id, carried.to_big_decimal()?)?;
+
+		if t.id == transaction.id {
+			corrected = t.clone();
+		}
+
+		since = t.created_at;
+		balance = t.balance;
+	}
+
+	let earning = earning_balance(account, balance, paid_before + paid, due - paid);
+
+	let accrual =
+		accounts::accrue_interest::accrue(account, earning, carried, &rates, &tiers, since, now)?;
+
+	let Cents(adjustment) = due + accrual.payable - paid;
+
+	// Accruing from the last transaction gets to the same interest
+	if due == paid && accrual.payable == Cents(0) {
+		return Ok(corrected);
+	}
+
+	let Cents(balance) = balance;
+
+	let attrs = TransactionAttrs {
+		account_id:            account.id,
+		kind:                  TransactionKind::Interest,
+		amount:                Cents(adjustment),
+		balance:               Cents(balance + adjustment),
+		accrued_interest:      accrual.carried.to_big_decimal()?,
+		linked_transaction_id: Some(transaction.id),
+		created_at:            None,
+		idempotency_key:       None,
+		memo:                  None,
+		category_id:           None,
+	};
+
+	ledger::record::one(&conn, attrs)?;
+
+	Ok(corrected)
+}
+
+// Interest credited by the transactions, adjustments included
+fn interest_paid(transactions: &[Transaction]) -> Cents {
+	transactions
+		.iter()
+		.filter(|t| t.kind == TransactionKind::Interest)
+		.fold(Cents(0), |total, t| total + t.amount)
+}
+
+// The part of `balance` that earns interest, had `unpaid` been credited as it should have.
+// With simple interest, interest doesn't earn more interest.
+fn earning_balance(account: &Account, balance: Cents, paid: Cents, unpaid: Cents) -> Cents {
+	let earning = match account.compounding {
+		Compounding::Simple => balance - paid,
+		_ => balance + unpaid,
+	};
+
+	earning.max(Cents(0))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		models::{self, schema::transactions},
+		utils::tests,
+	};
+	use chrono::Duration;
+	use diesel::{self, prelude::*};
+
+	fn age(conn: &PgConnection, transaction: &Transaction, at: NaiveDateTime) {
+		diesel::update(transactions::table.filter(transactions::id.eq(transaction.id)))
+			.set(transactions::created_at.eq(at))
+			.execute(conn)
+			.unwrap();
+	}
+
+	#[test]
+	fn it_moves_the_later_balances() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let now = Utc::now().naive_utc();
+
+			let account = Account {
+				created_at: now - Duration::days(30),
+				..account
+			};
+
+			let first = models::transaction::factories::transaction_attrs(&account)
+				.amount(1_000)
+				.balance(1_000)
+				.save(conn);
+
+			age(conn, &first, now - Duration::days(10));
+
+			let second = models::transaction::factories::transaction_attrs(&account)
+				.amount(500)
+				.balance(1_500)
+				.save(conn);
+
+			// Midnight, as the database keeps microseconds only
+			let at = Utc::today().naive_utc().and_hms(0, 0, 0) - Duration::days(5);
+
+			let deposit = call(
+				conn,
+				&account,
+				TransactionKind::Deposit,
+				200,
+				at,
+				None,
+				Description::default(),
+			)
+			.unwrap();
+
+			assert_eq!(deposit.balance, Cents(1_200));
+			assert_eq!(deposit.created_at, at);
+
+			let second = Transaction::find(conn, second.id).unwrap();
+
+			assert_eq!(second.balance, Cents(1_700));
+		})
+	}
+
+	#[test]
+	fn it_pays_the_interest_missed_since() {
+		tests::with_db(|conn| {
+			let (account, user, _) = tests::account(&conn);
+
+			// Midnight, as the database keeps microseconds only
+			let at = Utc::today().naive_utc().and_hms(0, 0, 0) - Duration::days(365);
+
+			let account = account.created_at(conn, at - Duration::days(10));
+
+			let _first = models::transaction::factories::transaction_attrs(&account)
+				.amount(1_000)
+				.balance(1_000)
+				.created_at(at)
+				.save(conn);
+
+			let deposit = call(
+				conn,
+				&account,
+				TransactionKind::Deposit,
+				100_000,
+				at,
+				None,
+				Description::default(),
+			)
+			.unwrap();
+
+			// The same account, had the deposit been recorded when it was made
+			let on_time = models::account::factories::account_attrs(&user)
+				.save(conn)
+				.created_at(conn, account.created_at);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&on_time)
+				.amount(101_000)
+				.balance(101_000)
+				.created_at(at)
+				.save(conn);
+
+			let expected = match accounts::pay_interest::call(conn, on_time.id).unwrap() {
+				accounts::pay_interest::PayInterestResponse::Paid(interest) => interest,
+				_ => panic!("Should have paid interest"),
+			};
+
+			let adjustment = Transaction::find_last_by_account_id_and_kind(
+				conn,
+				account.id,
+				TransactionKind::Interest,
+			)
+			.unwrap();
+
+			assert_eq!(adjustment.linked_transaction_id, Some(deposit.id));
+			assert_eq!(adjustment.balance, expected.balance);
+			assert_eq!(adjustment.accrued_interest, expected.accrued_interest);
+
+			let paid = Transaction::total_interest_by_account_id(conn, account.id).unwrap();
+
+			assert_eq!(paid, expected.amount);
+			assert!(ledger::verify::call(conn, account.id).unwrap().is_empty());
+		})
+	}
+
+	#[test]
+	fn it_doesnt_overdraw_later_balances() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let now = Utc::now().naive_utc();
+
+			let account = Account {
+				created_at: now - Duration::days(30),
+				..account
+			};
+
+			let first = models::transaction::factories::transaction_attrs(&account)
+				.amount(1_000)
+				.balance(1_000)
+				.save(conn);
+
+			age(conn, &first, now - Duration::days(10));
+
+			// Most of it was spent later on
+			let _second = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Withdrawal)
+				.amount(900)
+				.balance(100)
+				.save(conn);
+
+			let result = call(
+				conn,
+				&account,
+				TransactionKind::Withdrawal,
+				500,
+				now - Duration::days(5),
+				None,
+				Description::default(),
+			);
+
+			assert!(result.is_err());
+		})
+	}
+
+	#[test]
+	fn it_doesnt_date_in_the_future() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let tomorrow = Utc::now().naive_utc() + Duration::days(1);
+
+			let result = call(
+				conn,
+				&account,
+				TransactionKind::Deposit,
+				100,
+				tomorrow,
+				None,
+				Description::default(),
+			);
+
+			assert!(result.is_err());
+		})
+	}
+}
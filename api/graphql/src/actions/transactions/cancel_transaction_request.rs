@@ -0,0 +1,55 @@
+use crate::{
+	models::{
+		transaction_request::TransactionRequest, transaction_request_state::TransactionRequestState,
+	},
+	utils::db_transaction,
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// The account holder taking back a request nobody has resolved yet
+pub fn call(conn: &PgConnection, transaction_request_id: i32) -> Result<TransactionRequest, Error> {
+	db_transaction::serializable(&conn, || {
+		let transaction_request = TransactionRequest::lock(&conn, transaction_request_id)?;
+
+		let cancelled = TransactionRequestState::Cancelled;
+
+		if !transaction_request.state.can_change_to(cancelled) {
+			return Err(format_err!("Only a pending request can be cancelled"));
+		}
+
+		TransactionRequest::resolve(&conn, transaction_request.id, cancelled, None)
+			.map_err(Error::from)
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		actions::transactions::request_withdrawal::{self, RequestWithdrawalInput},
+		utils::tests,
+	};
+
+	#[test]
+	fn it_cancels_a_pending_request_once() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let input = RequestWithdrawalInput {
+				account_id:      account.id,
+				cents:           200,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
+			};
+
+			let transaction_request = request_withdrawal::call(conn, input).unwrap();
+
+			let cancelled = call(conn, transaction_request.id).unwrap();
+
+			assert_eq!(cancelled.state, TransactionRequestState::Cancelled);
+			assert!(call(conn, transaction_request.id).is_err());
+		})
+	}
+}
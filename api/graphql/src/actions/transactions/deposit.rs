@@ -1,56 +1,218 @@
+use chrono::prelude::*;
 use diesel::pg::PgConnection;
 use failure::Error;
 // use models::account::Account;
 use crate::{
-	actions::{accounts, emails::acknowledge_deposit},
+	actions::{
+		accounts,
+		emails::acknowledge_deposit,
+		goals, ledger,
+		transactions::{backdate, describe, match_deposit, split_deposit},
+	},
 	models::{
+		account::Account,
 		cents::Cents,
+		deposit_source::DepositSource,
 		transaction::{Transaction, TransactionAttrs, TransactionKind},
 	},
+	utils::{dates, db_transaction},
 };
 
+// effective_at is POSIX time in seconds, for recording it after the fact.
+// A retried call with the same idempotency_key returns the first transaction,
+// reusing the key for a different deposit fails.
+// category is a name, it is added to the family's categories if it is new.
 #[derive(GraphQLInputObject, Clone)]
 pub struct DepositInput {
-	pub account_id: i32,
-	pub cents:      i32,
+	pub account_id:      i32,
+	pub cents:           i32,
+	pub effective_at:    Option<f64>,
+	pub idempotency_key: Option<String>,
+	pub memo:            Option<String>,
+	pub category:        Option<String>,
+}
+
+// `source` says how the deposit was made, for the account's matching rule
+pub fn call(
+	conn: &PgConnection,
+	input: DepositInput,
+	source: DepositSource,
+) -> Result<Transaction, Error> {
+	let (transaction, created) = record(&conn, &input, source)?;
+
+	// Send an email to the account holder, once
+	if created {
+		acknowledge(&conn, &transaction)?;
+	}
+
+	Ok(transaction)
 }
 
-pub fn call(conn: &PgConnection, input: DepositInput) -> Result<Transaction, Error> {
+// Tells the account holder about a recorded deposit, once it has been committed
+pub fn acknowledge(conn: &PgConnection, transaction: &Transaction) -> Result<(), Error> {
+	acknowledge_deposit::call(&conn, transaction)?;
+
+	// Every account a split deposit went into may have reached a goal
+	for part in Transaction::find_grouped(&conn, transaction.id)? {
+		goals::check_reached::call(&conn, part.account_id)?;
+	}
+
+	Ok(())
+}
+
+// Makes the transaction without telling the account holder, so it can run inside another
+// database transaction. The flag is false when a retry got the first transaction,
+// which has already been matched.
+// When the account is one of the holder's jars the deposit is split across them,
+// and the part deposited in this account is returned.
+pub fn record(
+	conn: &PgConnection,
+	input: &DepositInput,
+	source: DepositSource,
+) -> Result<(Transaction, bool), Error> {
 	// Fail if cents is negative
 	if input.cents <= 0 {
 		return Err(format_err!("Invalid amount"));
 	}
 
-	// Pay interest first
-	accounts::pay_interest::call(&conn, input.account_id)?;
+	// Lock the account, so concurrent changes build on each other's balance
+	db_transaction::serializable(&conn, || {
+		// A deposit split across jars locks them all first, always in the same order
+		let jars = split_deposit::lock_jars(&conn, input.account_id)?;
 
-	let current_balance = accounts::get_balance::call(&conn, input.account_id)?;
+		let account = Account::lock(&conn, input.account_id)?;
 
-	let cents = input.cents as i64;
+		// Checked under the lock, so a retry racing the first call waits for it
+		if let Some(key) = &input.idempotency_key {
+			if let Some(existing) =
+				Transaction::find_by_idempotency_key(&conn, input.account_id, key)?
+			{
+				if existing.kind != TransactionKind::Deposit
+					|| deposited(&conn, &existing)? != input.cents as i64
+					|| !describe::matches(&conn, &existing, &input.memo, &input.category)?
+				{
+					return Err(format_err!("Idempotency key has already been used"));
+				}
 
-	// Calculate running balance
-	let new_balance = Cents(cents + current_balance);
+				return Ok((existing, false));
+			}
+		}
 
-	let attrs = TransactionAttrs {
-		account_id: input.account_id,
-		kind:       TransactionKind::Deposit,
-		amount:     Cents(cents),
-		balance:    new_balance,
-	};
+		let description = describe::call(&conn, input.account_id, &input.memo, &input.category)?;
 
-	let transaction = Transaction::create(conn, attrs).map_err(|e| format_err!("{}", e))?;
+		let effective_at = match input.effective_at {
+			Some(seconds) => Some(dates::from_seconds(seconds).ok_or(format_err!("Invalid date"))?),
+			None => None,
+		};
 
-	// Send an email to the account holder
-	acknowledge_deposit::call(&conn, &transaction)?;
+		// Archived accounts and matured term deposits don't take new funds
+		accounts::rules::check_deposit(&account, effective_at.unwrap_or(Utc::now().naive_utc()))?;
 
-	Ok(transaction)
+		// Only the account deposited to takes a back-dated deposit
+		if let Some(effective_at) = effective_at {
+			let cents = input.cents as i64;
+
+			let transaction = backdate::call(
+				&conn,
+				&account,
+				TransactionKind::Deposit,
+				cents,
+				effective_at,
+				input.idempotency_key.clone(),
+				description,
+			)?;
+
+			match_deposit::call(&conn, &account, &transaction, source, Some(effective_at))?;
+
+			return Ok((transaction, true));
+		}
+
+		if !jars.is_empty() {
+			let cents = input.cents as i64;
+
+			let transactions = split_deposit::call(
+				&conn,
+				input.account_id,
+				&jars,
+				cents,
+				&input.idempotency_key,
+				&description,
+			)?;
+
+			// Each part is matched by its own account's rule
+			for jar in &jars {
+				if let Some(transaction) =
+					transactions.iter().find(|t| t.account_id == jar.account.id)
+				{
+					match_deposit::call(&conn, &jar.account, transaction, source, None)?;
+				}
+			}
+
+			return transactions
+				.into_iter()
+				.find(|transaction| transaction.account_id == input.account_id)
+				.map(|transaction| (transaction, true))
+				.ok_or(format_err!("Failed to record the deposit"));
+		}
+
+		// Pay interest first
+		accounts::pay_interest::call(&conn, input.account_id)?;
+
+		let balance = accounts::get_balance::detailed(&conn, input.account_id)?;
+
+		let current_balance = balance.cents;
+
+		let cents = input.cents as i64;
+
+		// Calculate running balance
+		let new_balance = Cents(cents + current_balance);
+
+		// Interest that hasn't been credited yet is carried by this transaction
+		let attrs = TransactionAttrs {
+			account_id:            input.account_id,
+			kind:                  TransactionKind::Deposit,
+			amount:                Cents(cents),
+			balance:               new_balance,
+			accrued_interest:      balance.accrued_interest.to_big_decimal()?,
+			linked_transaction_id: None,
+			created_at:            None,
+			idempotency_key:       input.idempotency_key.clone(),
+			memo:                  description.memo,
+			category_id:           description.category_id,
+		};
+
+		let transaction = ledger::record::one(&conn, attrs)?;
+
+		// The parents' contribution, when the account has a matching rule
+		match_deposit::call(&conn, &account, &transaction, source, None)?;
+
+		Ok((transaction, true))
+	})
+}
+
+// The whole deposit the transaction is part of, across the jars when it was split
+fn deposited(conn: &PgConnection, transaction: &Transaction) -> Result<i64, Error> {
+	let mut total = 0;
+
+	for part in Transaction::find_grouped(&conn, transaction.id)? {
+		if part.kind == TransactionKind::Deposit {
+			let Cents(cents) = part.amount;
+			total += cents;
+		}
+	}
+
+	Ok(total)
 }
 
 #[cfg(test)]
 mod test {
 	use super::*;
-	use crate::models;
-	use crate::utils::tests;
+	use crate::{
+		models,
+		utils::{db_conn, tests},
+	};
+	use chrono::Duration;
+	use std::thread;
 
 	#[test]
 	fn it_creates_a_transaction() {
@@ -62,11 +224,15 @@ mod test {
 			let account = models::account::factories::account_attrs(&user).save(conn);
 
 			let input = DepositInput {
-				account_id: account.id,
-				cents:      200,
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
 			};
 
-			let transaction = call(conn, input).unwrap();
+			let transaction = call(conn, input, DepositSource::Direct).unwrap();
 
 			assert_eq!(transaction.account_id, account.id);
 			assert_eq!(transaction.amount, Cents(200));
@@ -84,11 +250,35 @@ mod test {
 			let account = models::account::factories::account_attrs(&user).save(conn);
 
 			let input = DepositInput {
-				account_id: account.id,
-				cents:      -200,
+				account_id:      account.id,
+				cents:           -200,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
 			};
 
-			let result = call(conn, input);
+			let result = call(conn, input, DepositSource::Direct);
+
+			assert!(result.is_err());
+		})
+	}
+
+	#[test]
+	fn it_fails_with_an_invalid_date() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let input = DepositInput {
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    Some(1e20),
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
+			};
+
+			let result = call(conn, input, DepositSource::Direct);
 
 			assert!(result.is_err());
 		})
@@ -108,17 +298,196 @@ mod test {
 			// 	.save(conn);
 
 			let _prev2 = models::transaction::factories::transaction_attrs(&account)
+				.amount(2)
 				.balance(2)
 				.save(conn);
 
 			let input = DepositInput {
-				account_id: account.id,
-				cents:      4,
+				account_id:      account.id,
+				cents:           4,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
 			};
 
-			let transaction = call(conn, input).unwrap();
+			let transaction = call(conn, input, DepositSource::Direct).unwrap();
 
 			assert_eq!(transaction.balance, Cents(6));
 		})
 	}
+
+	#[test]
+	fn it_returns_the_first_transaction_for_a_repeated_key() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let input = DepositInput {
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: Some("deposit-1".to_string()),
+				memo:            None,
+				category:        None,
+			};
+
+			let first = call(conn, input.clone(), DepositSource::Direct).unwrap();
+			let second = call(conn, input, DepositSource::Direct).unwrap();
+
+			assert_eq!(first.id, second.id);
+
+			let balance = accounts::get_balance::call(conn, account.id).unwrap();
+
+			assert_eq!(balance, 200);
+		})
+	}
+
+	#[test]
+	fn it_doesnt_reuse_a_key_for_another_deposit() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let input = DepositInput {
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: Some("deposit-1".to_string()),
+				memo:            None,
+				category:        None,
+			};
+
+			let _first = call(conn, input.clone(), DepositSource::Direct).unwrap();
+
+			let more = DepositInput {
+				cents: 500,
+				..input.clone()
+			};
+
+			assert!(call(conn, more, DepositSource::Direct).is_err());
+
+			let described = DepositInput {
+				memo: Some("From grandma".to_string()),
+				..input
+			};
+
+			assert!(call(conn, described, DepositSource::Direct).is_err());
+
+			let balance = accounts::get_balance::call(conn, account.id).unwrap();
+
+			assert_eq!(balance, 200);
+		})
+	}
+
+	#[test]
+	fn it_posts_the_interest_of_a_day_that_ended_between_deposits() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let now = Utc::now().naive_utc();
+
+			// A day of the account ended an hour ago
+			let account = account.created_at(conn, now - Duration::days(10) - Duration::hours(1));
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.created_at(now - Duration::hours(2))
+				.save(conn);
+
+			let input = DepositInput {
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
+			};
+
+			let _first = call(conn, input.clone(), DepositSource::Direct).unwrap();
+			let second = call(conn, input, DepositSource::Direct).unwrap();
+
+			let interest = Transaction::find_last_by_account_id_and_kind(
+				conn,
+				account.id,
+				TransactionKind::Interest,
+			)
+			.unwrap();
+
+			let Cents(interest) = interest.amount;
+
+			assert!(interest > 0);
+			assert_eq!(second.balance, Cents(100_000 + interest + 400));
+			assert!(ledger::verify::call(conn, account.id).unwrap().is_empty());
+		})
+	}
+
+	#[test]
+	fn it_records_the_memo_and_category() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let input = DepositInput {
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            Some("From grandma".to_string()),
+				category:        Some("Birthday money".to_string()),
+			};
+
+			let transaction = call(conn, input, DepositSource::Direct).unwrap();
+
+			assert_eq!(transaction.memo, Some("From grandma".to_string()));
+
+			let category_id = transaction.category_id.unwrap();
+			let category = models::category::Category::find(conn, category_id).unwrap();
+
+			assert_eq!(category.name, "Birthday money");
+		})
+	}
+
+	#[test]
+	fn concurrent_deposits_dont_lose_updates() {
+		tests::with_db_cleaner(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let account_id = account.id;
+
+			let handles: Vec<_> = (0..4)
+				.map(|_| {
+					thread::spawn(move || {
+						let conn = db_conn::get_test_connection();
+
+						let input = DepositInput {
+							account_id:      account_id,
+							cents:           100,
+							effective_at:    None,
+							idempotency_key: None,
+							memo:            None,
+							category:        None,
+						};
+
+						call(&conn, input, DepositSource::Direct).unwrap()
+					})
+				})
+				.collect();
+
+			let mut balances: Vec<Cents> = handles
+				.into_iter()
+				.map(|handle| handle.join().unwrap().balance)
+				.collect();
+
+			balances.sort();
+
+			// Each deposit built on the previous one
+			assert_eq!(
+				balances,
+				vec![Cents(100), Cents(200), Cents(300), Cents(400)]
+			);
+
+			let balance = accounts::get_balance::call(conn, account.id).unwrap();
+
+			assert_eq!(balance, 400);
+		})
+	}
 }
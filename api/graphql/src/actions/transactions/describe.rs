@@ -0,0 +1,130 @@
+use crate::models::{account::Account, category::Category, transaction::Transaction, user::User};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+const MAX_MEMO_LENGTH: usize = 200;
+const MAX_CATEGORY_LENGTH: usize = 50;
+
+// What the money is for, e.g. "birthday money" in "presents"
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Description {
+	pub memo:        Option<String>,
+	pub category_id: Option<i32>,
+}
+
+// Blank values are left out.
+// The category is looked up by name within the account's client, and made if it is new.
+pub fn call(
+	conn: &PgConnection,
+	account_id: i32,
+	memo: &Option<String>,
+	category: &Option<String>,
+) -> Result<Description, Error> {
+	let memo = present(memo);
+
+	if let Some(memo) = &memo {
+		if memo.len() > MAX_MEMO_LENGTH {
+			return Err(format_err!("Memo is too long"));
+		}
+	}
+
+	let category_id = match present(category) {
+		Some(name) => {
+			if name.len() > MAX_CATEGORY_LENGTH {
+				return Err(format_err!("Category is too long"));
+			}
+
+			let client_id = client_id_of(&conn, account_id)?;
+
+			Some(Category::find_or_create(&conn, client_id, &name)?.id)
+		},
+		None => None,
+	};
+
+	Ok(Description {
+		memo:        memo,
+		category_id: category_id,
+	})
+}
+
+// The account's category with this name, without making it
+pub fn find_category(
+	conn: &PgConnection,
+	account_id: i32,
+	name: &str,
+) -> Result<Option<Category>, Error> {
+	let client_id = client_id_of(&conn, account_id)?;
+
+	Category::find_by_name(&conn, client_id, name.trim()).map_err(Error::from)
+}
+
+// Whether the transaction was described with this memo and category,
+// looking the category up without making it
+pub fn matches(
+	conn: &PgConnection,
+	transaction: &Transaction,
+	memo: &Option<String>,
+	category: &Option<String>,
+) -> Result<bool, Error> {
+	let category_id = match present(category) {
+		Some(name) => {
+			match find_category(&conn, transaction.account_id, &name)? {
+				Some(category) => Some(category.id),
+				None => return Ok(false),
+			}
+		},
+		None => None,
+	};
+
+	Ok(transaction.memo == present(memo) && transaction.category_id == category_id)
+}
+
+fn present(value: &Option<String>) -> Option<String> {
+	value
+		.as_ref()
+		.map(|value| value.trim().to_string())
+		.filter(|value| !value.is_empty())
+}
+
+fn client_id_of(conn: &PgConnection, account_id: i32) -> Result<i32, Error> {
+	let account = Account::find(&conn, account_id)?;
+
+	let user = User::find(&conn, account.user_id)?;
+
+	Ok(user.client_id)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::utils::tests;
+
+	#[test]
+	fn it_shares_categories_within_a_client() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let category = Some("Toys".to_string());
+
+			let first = call(conn, account.id, &None, &category).unwrap();
+			let second = call(conn, account.id, &None, &category).unwrap();
+
+			assert!(first.category_id.is_some());
+			assert_eq!(first.category_id, second.category_id);
+		})
+	}
+
+	#[test]
+	fn it_leaves_out_blanks() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let blank = Some(" ".to_string());
+
+			let description = call(conn, account.id, &blank, &blank).unwrap();
+
+			assert_eq!(description.memo, None);
+			assert_eq!(description.category_id, None);
+		})
+	}
+}
@@ -0,0 +1,115 @@
+use crate::{
+	actions::emails::expire_transaction_request,
+	models::{
+		client::Client, transaction_request::TransactionRequest,
+		transaction_request_state::TransactionRequestState,
+	},
+	utils::db_transaction,
+};
+use chrono::{prelude::*, Duration};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Expires the requests left pending for longer than their client allows,
+// and tells the account holder and the admins.
+pub fn call(conn: &PgConnection) -> Result<Vec<TransactionRequest>, Error> {
+	let now = Utc::now().naive_utc();
+
+	let mut expired = vec![];
+
+	for client in Client::all(&conn)? {
+		let before = now - Duration::days(client.request_expiry_days as i64);
+
+		let pending = TransactionRequest::find_pending_by_client(&conn, &client, before)?;
+
+		for transaction_request in pending {
+			// One request failing shouldn't stop the others
+			match expire(&conn, transaction_request.id) {
+				Ok(Some(transaction_request)) => expired.push(transaction_request),
+				Ok(None) => (),
+				Err(e) => error!("Failed to expire request {}: {}", transaction_request.id, e),
+			}
+		}
+	}
+
+	for transaction_request in &expired {
+		if let Err(e) = expire_transaction_request::call(&conn, transaction_request) {
+			error!(
+				"Failed to email expired request {}: {}",
+				transaction_request.id, e
+			);
+		}
+	}
+
+	Ok(expired)
+}
+
+// None if it was resolved since it was found
+fn expire(conn: &PgConnection, id: i32) -> Result<Option<TransactionRequest>, Error> {
+	db_transaction::serializable(&conn, || {
+		let transaction_request = TransactionRequest::lock(&conn, id)?;
+
+		let expired = TransactionRequestState::Expired;
+
+		if !transaction_request.state.can_change_to(expired) {
+			return Ok(None);
+		}
+
+		TransactionRequest::resolve(&conn, id, expired, None)
+			.map(Some)
+			.map_err(Error::from)
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		actions::transactions::request_withdrawal::{self, RequestWithdrawalInput},
+		models::schema::transaction_requests,
+		utils::tests,
+	};
+	use diesel::{self, prelude::*};
+
+	fn request(conn: &PgConnection, account_id: i32) -> TransactionRequest {
+		let input = RequestWithdrawalInput {
+			account_id:      account_id,
+			cents:           200,
+			idempotency_key: None,
+			memo:            None,
+			category:        None,
+		};
+
+		request_withdrawal::call(conn, input).unwrap()
+	}
+
+	#[test]
+	fn it_expires_old_pending_requests() {
+		tests::with_db(|conn| {
+			let (account, _, client) = tests::account(&conn);
+
+			let _client = client.request_expiry_days(conn, 7);
+
+			let old = request(conn, account.id);
+			let recent = request(conn, account.id);
+
+			let week_ago = Utc::now().naive_utc() - Duration::days(7) - Duration::hours(1);
+
+			diesel::update(transaction_requests::table.find(old.id))
+				.set(transaction_requests::created_at.eq(week_ago))
+				.execute(conn)
+				.unwrap();
+
+			let expired = call(conn).unwrap();
+
+			assert!(expired.iter().any(|t| t.id == old.id));
+			assert!(expired.iter().all(|t| t.id != recent.id));
+
+			let old = TransactionRequest::find(conn, old.id).unwrap();
+			let recent = TransactionRequest::find(conn, recent.id).unwrap();
+
+			assert_eq!(old.state, TransactionRequestState::Expired);
+			assert_eq!(recent.state, TransactionRequestState::Pending);
+		})
+	}
+}
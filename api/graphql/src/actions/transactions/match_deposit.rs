@@ -0,0 +1,211 @@
+use crate::{
+	actions::{
+		accounts, ledger,
+		transactions::{backdate, describe::Description},
+	},
+	models::{
+		account::Account,
+		cents::Cents,
+		matching_rule::{DepositSource, MatchingRule},
+		transaction::{Transaction, TransactionAttrs, TransactionKind},
+	},
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{Duration, NaiveDateTime};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Records the parents' match for a deposit just made, when the account's rule covers it.
+// The match is rounded down to the cent and linked to the deposit.
+// `effective_at` is set for a back-dated deposit, the match is dated the same.
+// Expects the account to be locked.
+pub fn call(
+	conn: &PgConnection,
+	account: &Account,
+	deposit: &Transaction,
+	source: DepositSource,
+	effective_at: Option<NaiveDateTime>,
+) -> Result<Option<Transaction>, Error> {
+	let rule = match MatchingRule::find_by_account_id(&conn, account.id)? {
+		Some(rule) => rule,
+		None => return Ok(None),
+	};
+
+	if !rule.sources.contains(&source) {
+		return Ok(None);
+	}
+
+	let Cents(deposited) = deposit.amount;
+
+	let matched = (BigDecimal::from(deposited) * &rule.percentage / BigDecimal::from(100))
+		.with_scale(0)
+		.to_i64()
+		.ok_or(format_err!("Failed to calculate the match"))?;
+
+	// Only what is left of the cap for the period
+	let cents = match rule.cap {
+		Some(Cents(cap)) => {
+			let since = deposit.created_at - Duration::days(rule.period.days());
+
+			let so_far = matched_since(&conn, account.id, since, deposit.created_at)?;
+
+			matched.min(cap - so_far)
+		},
+		None => matched,
+	};
+
+	if cents <= 0 {
+		return Ok(None);
+	}
+
+	let transaction = match effective_at {
+		Some(effective_at) => {
+			let transaction = backdate::call(
+				&conn,
+				account,
+				TransactionKind::Match,
+				cents,
+				effective_at,
+				None,
+				Description::default(),
+			)?;
+
+			Transaction::link(&conn, transaction.id, deposit.id)?
+		},
+		None => {
+			let balance = accounts::get_balance::detailed(&conn, account.id)?;
+
+			// Interest that hasn't been credited yet is carried by this transaction
+			let attrs = TransactionAttrs {
+				account_id:            account.id,
+				kind:                  TransactionKind::Match,
+				amount:                Cents(cents),
+				balance:               Cents(balance.cents + cents),
+				accrued_interest:      balance.accrued_interest.to_big_decimal()?,
+				linked_transaction_id: Some(deposit.id),
+				created_at:            None,
+				idempotency_key:       None,
+				memo:                  None,
+				category_id:           None,
+			};
+
+			ledger::record::one(&conn, attrs)?
+		},
+	};
+
+	Ok(Some(transaction))
+}
+
+// Matches in the window that haven't been reversed
+fn matched_since(
+	conn: &PgConnection,
+	account_id: i32,
+	since: NaiveDateTime,
+	at: NaiveDateTime,
+) -> Result<i64, Error> {
+	let mut total = 0;
+
+	for transaction in Transaction::find_by_account_id(&conn, account_id, since)? {
+		if transaction.kind != TransactionKind::Match || transaction.created_at > at {
+			continue;
+		}
+
+		if Transaction::find_reversal(&conn, transaction.id)?.is_none() {
+			let Cents(cents) = transaction.amount;
+			total += cents;
+		}
+	}
+
+	Ok(total)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		actions::transactions::deposit::{self, DepositInput},
+		models,
+		utils::tests,
+	};
+
+	fn deposit(
+		conn: &PgConnection,
+		account_id: i32,
+		cents: i32,
+		source: DepositSource,
+	) -> Transaction {
+		let input = DepositInput {
+			account_id:      account_id,
+			cents:           cents,
+			effective_at:    None,
+			idempotency_key: None,
+			memo:            None,
+			category:        None,
+		};
+
+		deposit::call(conn, input, source).unwrap()
+	}
+
+	#[test]
+	fn it_matches_a_percentage_of_the_deposit() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _rule = models::matching_rule::factories::matching_rule_attrs(&account).save(conn);
+
+			let transaction = deposit(conn, account.id, 1_001, DepositSource::Direct);
+
+			let matched = Transaction::find_match(conn, transaction.id)
+				.unwrap()
+				.unwrap();
+
+			assert_eq!(matched.kind, TransactionKind::Match);
+			assert_eq!(matched.amount, Cents(500));
+			assert_eq!(matched.balance, Cents(1_501));
+			assert_eq!(
+				accounts::get_balance::call(conn, account.id).unwrap(),
+				1_501
+			);
+		})
+	}
+
+	#[test]
+	fn it_stops_at_the_cap() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _rule = models::matching_rule::factories::matching_rule_attrs(&account)
+				.cap(700)
+				.save(conn);
+
+			let first = deposit(conn, account.id, 1_000, DepositSource::Direct);
+			let second = deposit(conn, account.id, 1_000, DepositSource::Direct);
+			let third = deposit(conn, account.id, 1_000, DepositSource::Direct);
+
+			let amount = |deposit: &Transaction| {
+				Transaction::find_match(conn, deposit.id)
+					.unwrap()
+					.map(|matched| matched.amount)
+			};
+
+			assert_eq!(amount(&first), Some(Cents(500)));
+			assert_eq!(amount(&second), Some(Cents(200)));
+			assert_eq!(amount(&third), None);
+		})
+	}
+
+	#[test]
+	fn it_only_matches_the_chosen_sources() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _rule = models::matching_rule::factories::matching_rule_attrs(&account)
+				.sources(vec![DepositSource::Request])
+				.save(conn);
+
+			let transaction = deposit(conn, account.id, 1_000, DepositSource::Scheduled);
+
+			assert_eq!(Transaction::find_match(conn, transaction.id).unwrap(), None);
+		})
+	}
+}
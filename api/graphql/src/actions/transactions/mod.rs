@@ -1,4 +1,14 @@
+pub mod backdate;
+pub mod cancel_transaction_request;
 pub mod deposit;
+pub mod describe;
+pub mod expire_transaction_requests;
+pub mod match_deposit;
+pub mod request;
+pub mod request_deposit;
 pub mod request_withdrawal;
 pub mod resolve_transaction_request;
+pub mod reverse;
+pub mod split_deposit;
+pub mod transfer;
 pub mod withdraw;
@@ -0,0 +1,94 @@
+use crate::{
+	actions::{
+		accounts::{self, check_withdrawal_rules::Route},
+		transactions::describe,
+	},
+	models::{
+		account::Account,
+		cents::Cents,
+		transaction_kind::TransactionKind,
+		transaction_request::{TransactionRequest, TransactionRequestAttrs},
+		transaction_request_state::TransactionRequestState,
+	},
+};
+use chrono::prelude::*;
+use diesel::{
+	pg::PgConnection,
+	result::{DatabaseErrorKind, Error as DieselError},
+};
+use failure::Error;
+
+// Makes a pending request for an admin to resolve, shared by deposits and withdrawals.
+// The flag is false when a retry with the same idempotency_key got the first request.
+pub fn create(
+	conn: &PgConnection,
+	kind: TransactionKind,
+	account_id: i32,
+	cents: i32,
+	idempotency_key: &Option<String>,
+	memo: &Option<String>,
+	category: &Option<String>,
+) -> Result<(TransactionRequest, bool), Error> {
+	// Fail if cents is negative
+	if cents <= 0 {
+		return Err(format_err!("Invalid amount"));
+	}
+
+	if let Some(key) = idempotency_key {
+		if let Some(existing) = TransactionRequest::find_by_idempotency_key(&conn, account_id, key)?
+		{
+			return Ok((existing, false));
+		}
+	}
+
+	let account = Account::find(&conn, account_id)?;
+
+	// Don't ask for what the account doesn't allow
+	let now = Utc::now().naive_utc();
+
+	match kind {
+		TransactionKind::Deposit => accounts::rules::check_deposit(&account, now)?,
+		TransactionKind::Withdrawal => {
+			accounts::rules::check_withdrawal(&account, now)?;
+
+			// Checked again when it is approved, as the balance may have changed by then
+			accounts::check_withdrawal_rules::call(
+				&conn,
+				&account,
+				cents as i64,
+				now,
+				Route::Request,
+			)?
+		},
+		_ => {
+			return Err(format_err!(
+				"Only deposits and withdrawals can be requested"
+			))
+		},
+	}
+
+	let description = describe::call(&conn, account_id, memo, category)?;
+
+	let attrs = TransactionRequestAttrs {
+		account_id:      account_id,
+		kind:            kind,
+		amount:          Cents(cents as i64),
+		state:           TransactionRequestState::Pending,
+		idempotency_key: idempotency_key.clone(),
+		memo:            description.memo,
+		category_id:     description.category_id,
+	};
+
+	match TransactionRequest::create(conn, attrs) {
+		Ok(transaction_request) => Ok((transaction_request, true)),
+		// A retry that raced the first call, which has made the request
+		Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+			let key = idempotency_key.clone().unwrap_or_default();
+
+			TransactionRequest::find_by_idempotency_key(&conn, account_id, &key)?
+				.map(|existing| (existing, false))
+				.ok_or(format_err!("Request not found"))
+		},
+		Err(e) => Err(format_err!("{}", e)),
+	}
+}
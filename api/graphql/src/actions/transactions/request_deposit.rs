@@ -0,0 +1,88 @@
+use crate::{
+	actions::{emails::request_deposit, transactions::request},
+	models::{transaction_kind::TransactionKind, transaction_request::TransactionRequest},
+};
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// Money the investor has handed over, e.g. cash given to a parent.
+// It is only deposited once an admin approves the request.
+#[derive(GraphQLInputObject, Clone)]
+pub struct RequestDepositInput {
+	pub account_id:      i32,
+	pub cents:           i32,
+	pub idempotency_key: Option<String>,
+	pub memo:            Option<String>,
+	pub category:        Option<String>,
+}
+
+pub fn call(conn: &PgConnection, input: RequestDepositInput) -> Result<TransactionRequest, Error> {
+	let (transaction_request, created) = request::create(
+		&conn,
+		TransactionKind::Deposit,
+		input.account_id,
+		input.cents,
+		&input.idempotency_key,
+		&input.memo,
+		&input.category,
+	)?;
+
+	// Send email
+	if created {
+		request_deposit::call(&conn, &transaction_request)?;
+	}
+
+	Ok(transaction_request)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		models::{self, transaction_request_state::TransactionRequestState},
+		utils::tests,
+	};
+	use chrono::prelude::*;
+
+	#[test]
+	fn it_creates_a_pending_deposit_request() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let input = RequestDepositInput {
+				account_id:      account.id,
+				cents:           1_000,
+				idempotency_key: None,
+				memo:            Some("Gave Dad $10".to_string()),
+				category:        None,
+			};
+
+			let transaction_request = call(conn, input).unwrap();
+
+			assert_eq!(transaction_request.kind, TransactionKind::Deposit);
+			assert_eq!(transaction_request.state, TransactionRequestState::Pending);
+		})
+	}
+
+	#[test]
+	fn it_doesnt_request_a_deposit_into_a_matured_term_deposit() {
+		tests::with_db(|conn| {
+			let (user, _) = tests::user(&conn);
+
+			let account = models::account::factories::account_attrs(&user)
+				.kind(models::account::Kind::TermDeposit)
+				.matures_at(Utc::now().naive_utc() - chrono::Duration::days(1))
+				.save(conn);
+
+			let input = RequestDepositInput {
+				account_id:      account.id,
+				cents:           1_000,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
+			};
+
+			assert!(call(conn, input).is_err());
+		})
+	}
+}
@@ -1,44 +1,65 @@
 use crate::{
-	actions::emails::request_withdrawal,
-	models::{
-		cents::Cents,
-		transaction_kind::TransactionKind,
-		transaction_request::{TransactionRequest, TransactionRequestAttrs},
-		transaction_request_state::TransactionRequestState,
-	},
+	actions::{emails::request_withdrawal, transactions::request},
+	models::{transaction_kind::TransactionKind, transaction_request::TransactionRequest},
 };
 use diesel::pg::PgConnection;
 use failure::Error;
 
+// A retried call with the same idempotency_key returns the first request.
+// category is a name, it is added to the family's categories if it is new.
 #[derive(GraphQLInputObject, Clone)]
 pub struct RequestWithdrawalInput {
-	pub account_id: i32,
-	pub cents:      i32,
+	pub account_id:      i32,
+	pub cents:           i32,
+	pub idempotency_key: Option<String>,
+	pub memo:            Option<String>,
+	pub category:        Option<String>,
 }
 
 pub fn call(
 	conn: &PgConnection,
 	input: RequestWithdrawalInput,
 ) -> Result<TransactionRequest, Error> {
-	// Fail if cents is negative
-	if input.cents <= 0 {
-		return Err(format_err!("Invalid amount"));
+	let (transaction_request, created) = request::create(
+		&conn,
+		TransactionKind::Withdrawal,
+		input.account_id,
+		input.cents,
+		&input.idempotency_key,
+		&input.memo,
+		&input.category,
+	)?;
+
+	// Send email
+	if created {
+		request_withdrawal::call(&conn, &transaction_request)?;
 	}
 
-	let amount = input.cents as i64;
+	Ok(transaction_request)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::utils::tests;
 
-	let attrs = TransactionRequestAttrs {
-		account_id: input.account_id,
-		kind:       TransactionKind::Withdrawal,
-		amount:     Cents(amount),
-		state:      TransactionRequestState::Pending,
-	};
+	#[test]
+	fn it_returns_the_first_request_for_a_repeated_key() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
 
-	let transaction_request =
-		TransactionRequest::create(conn, attrs).map_err(|e| format_err!("{}", e))?;
+			let input = RequestWithdrawalInput {
+				account_id:      account.id,
+				cents:           200,
+				idempotency_key: Some("request-1".to_string()),
+				memo:            None,
+				category:        None,
+			};
 
-	// Send email
-	request_withdrawal::call(&conn, &transaction_request)?;
+			let first = call(conn, input.clone()).unwrap();
+			let second = call(conn, input).unwrap();
 
-	Ok(transaction_request)
+			assert_eq!(first.id, second.id);
+		})
+	}
 }
@@ -1,11 +1,20 @@
 use crate::{
-	actions::emails::resolve_transaction_request,
+	actions::{
+		accounts::check_withdrawal_rules::Route,
+		emails::resolve_transaction_request,
+		transactions::{
+			deposit::{self, DepositInput},
+			withdraw::{self, WithdrawalInput},
+		},
+	},
 	models::{
-		schema as db, transaction_request::TransactionRequest,
+		category::Category, cents::Cents, deposit_source::DepositSource, transaction::Transaction,
+		transaction_kind::TransactionKind, transaction_request::TransactionRequest,
 		transaction_request_state::TransactionRequestState,
 	},
+	utils::db_transaction,
 };
-use diesel::{self, pg::PgConnection, prelude::*};
+use diesel::pg::PgConnection;
 use failure::Error;
 
 #[derive(GraphQLInputObject, Clone)]
@@ -18,14 +27,232 @@ pub fn call(
 	conn: &PgConnection,
 	input: ResolveTransactionRequestInput,
 ) -> Result<TransactionRequest, Error> {
-	let filter = db::transaction_requests::id.eq(input.transaction_request_id);
+	// Cancelling and expiring are left to the account holder and the jobs
+	match input.outcome {
+		TransactionRequestState::Approved | TransactionRequestState::Rejected => (),
+		_ => return Err(format_err!("A request can only be approved or rejected")),
+	}
+
+	let transaction_request = db_transaction::serializable(&conn, || {
+		let transaction_request = TransactionRequest::lock(&conn, input.transaction_request_id)?;
+
+		if !transaction_request.state.can_change_to(input.outcome) {
+			return Err(format_err!("Only a pending request can be resolved"));
+		}
 
-	let transaction_request = diesel::update(db::transaction_requests::table.filter(filter))
-		.set(db::transaction_requests::state.eq(input.outcome))
-		.get_result(conn)?;
+		// The money moves with the approval, or the request stays pending
+		let transaction_id = match input.outcome {
+			TransactionRequestState::Approved => Some(execute(&conn, &transaction_request)?.id),
+			_ => None,
+		};
+
+		TransactionRequest::resolve(&conn, transaction_request.id, input.outcome, transaction_id)
+			.map_err(Error::from)
+	})?;
 
 	// Send email
 	resolve_transaction_request::call(&conn, &transaction_request)?;
 
 	Ok(transaction_request)
 }
+
+// Makes the deposit or withdrawal asked for, expects to run in a database transaction
+fn execute(
+	conn: &PgConnection,
+	transaction_request: &TransactionRequest,
+) -> Result<Transaction, Error> {
+	let Cents(cents) = transaction_request.amount;
+
+	let category = match transaction_request.category_id {
+		Some(category_id) => Some(Category::find(&conn, category_id)?.name),
+		None => None,
+	};
+
+	let recorded = match transaction_request.kind {
+		TransactionKind::Deposit => {
+			let input = DepositInput {
+				account_id:      transaction_request.account_id,
+				cents:           cents as i32,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            transaction_request.memo.clone(),
+				category:        category,
+			};
+
+			deposit::record(&conn, &input, DepositSource::Request)?
+		},
+		TransactionKind::Withdrawal => {
+			let input = WithdrawalInput {
+				account_id:      transaction_request.account_id,
+				cents:           cents as i32,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            transaction_request.memo.clone(),
+				category:        category,
+			};
+
+			withdraw::record(&conn, &input, Route::Approval)?
+		},
+		_ => {
+			return Err(format_err!(
+				"Only deposits and withdrawals can be requested"
+			))
+		},
+	};
+
+	let (transaction, _) = recorded;
+
+	Ok(transaction)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		actions::{
+			accounts,
+			transactions::{
+				request_deposit::{self, RequestDepositInput},
+				request_withdrawal::{self, RequestWithdrawalInput},
+			},
+		},
+		models,
+		utils::tests,
+	};
+
+	fn request_withdrawal(conn: &PgConnection, account_id: i32, cents: i32) -> TransactionRequest {
+		let input = RequestWithdrawalInput {
+			account_id:      account_id,
+			cents:           cents,
+			idempotency_key: None,
+			memo:            None,
+			category:        None,
+		};
+
+		request_withdrawal::call(conn, input).unwrap()
+	}
+
+	fn resolve(
+		conn: &PgConnection,
+		transaction_request: &TransactionRequest,
+		outcome: TransactionRequestState,
+	) -> Result<TransactionRequest, Error> {
+		let input = ResolveTransactionRequestInput {
+			transaction_request_id: transaction_request.id,
+			outcome:                outcome,
+		};
+
+		call(conn, input)
+	}
+
+	#[test]
+	fn it_withdraws_on_approval() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			let transaction_request = request_withdrawal(conn, account.id, 200);
+
+			let resolved = resolve(
+				conn,
+				&transaction_request,
+				TransactionRequestState::Approved,
+			)
+			.unwrap();
+
+			assert_eq!(resolved.state, TransactionRequestState::Approved);
+
+			let transaction = Transaction::find(conn, resolved.transaction_id.unwrap()).unwrap();
+
+			assert_eq!(transaction.kind, TransactionKind::Withdrawal);
+			assert_eq!(transaction.amount, Cents(200));
+			assert_eq!(accounts::get_balance::call(conn, account.id).unwrap(), 100);
+		})
+	}
+
+	#[test]
+	fn it_stays_pending_without_enough_balance() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction_request = request_withdrawal(conn, account.id, 200);
+
+			let result = resolve(
+				conn,
+				&transaction_request,
+				TransactionRequestState::Approved,
+			);
+
+			assert!(result.is_err());
+
+			let transaction_request =
+				TransactionRequest::find(conn, transaction_request.id).unwrap();
+
+			assert_eq!(transaction_request.state, TransactionRequestState::Pending);
+			assert_eq!(transaction_request.transaction_id, None);
+		})
+	}
+
+	#[test]
+	fn it_only_resolves_once() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let transaction_request = request_withdrawal(conn, account.id, 200);
+
+			resolve(
+				conn,
+				&transaction_request,
+				TransactionRequestState::Rejected,
+			)
+			.unwrap();
+
+			let again = resolve(
+				conn,
+				&transaction_request,
+				TransactionRequestState::Approved,
+			);
+			let back = resolve(conn, &transaction_request, TransactionRequestState::Pending);
+
+			assert!(again.is_err());
+			assert!(back.is_err());
+		})
+	}
+
+	#[test]
+	fn it_deposits_on_approval() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let request_input = RequestDepositInput {
+				account_id:      account.id,
+				cents:           1_000,
+				idempotency_key: None,
+				memo:            Some("Gave Dad $10".to_string()),
+				category:        None,
+			};
+
+			let transaction_request = request_deposit::call(conn, request_input).unwrap();
+
+			let resolved = resolve(
+				conn,
+				&transaction_request,
+				TransactionRequestState::Approved,
+			)
+			.unwrap();
+
+			let transaction = Transaction::find(conn, resolved.transaction_id.unwrap()).unwrap();
+
+			assert_eq!(transaction.kind, TransactionKind::Deposit);
+			assert_eq!(transaction.memo, Some("Gave Dad $10".to_string()));
+			assert_eq!(
+				accounts::get_balance::call(conn, account.id).unwrap(),
+				1_000
+			);
+		})
+	}
+}
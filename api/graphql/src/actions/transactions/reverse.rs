@@ -0,0 +1,345 @@
+use crate::{
+	actions::{accounts, emails::acknowledge_reversal, ledger},
+	models::{
+		account::Account,
+		cents::Cents,
+		transaction::{Transaction, TransactionAttrs, TransactionKind},
+	},
+	utils::db_transaction,
+};
+use chrono::prelude::*;
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// `reversals` has one row for each transaction undone, both legs for a transfer
+// and the match along with a deposit.
+// `adjustments` correct the interest for the time the mistake was on the balance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reversal {
+	pub reversals:   Vec<Transaction>,
+	pub adjustments: Vec<Transaction>,
+}
+
+// Undoes a mistaken deposit, withdrawal, match or transfer.
+// Unlike a compensating withdrawal this can leave the balance below zero.
+pub fn call(conn: &PgConnection, transaction_id: i32) -> Result<Reversal, Error> {
+	let reversal = db_transaction::serializable(&conn, || {
+		let original = Transaction::find(&conn, transaction_id)?;
+
+		let mut originals = match original.kind {
+			TransactionKind::Deposit | TransactionKind::Withdrawal | TransactionKind::Match => {
+				vec![original]
+			},
+			TransactionKind::Transfer => {
+				let linked_id = original
+					.linked_transaction_id
+					.ok_or(format_err!("Transfer is missing its other side"))?;
+
+				let linked = Transaction::find(&conn, linked_id)?;
+
+				vec![original, linked]
+			},
+			_ => {
+				return Err(format_err!(
+					"Only deposits, withdrawals, matches and transfers can be reversed"
+				))
+			},
+		};
+
+		// Always lock in the same order
+		let mut account_ids: Vec<i32> = originals.iter().map(|t| t.account_id).collect();
+
+		account_ids.sort();
+
+		let mut locked = vec![];
+
+		for account_id in account_ids {
+			locked.push(Account::lock(&conn, account_id)?);
+		}
+
+		for original in &originals {
+			if Transaction::find_reversal(&conn, original.id)?.is_some() {
+				return Err(format_err!("Transaction has already been reversed"));
+			}
+		}
+
+		// A deposit takes its match with it
+		let matched = unreversed_match(&conn, &originals[0])?;
+
+		let now = Utc::now().naive_utc();
+
+		let mut legs = vec![];
+
+		for original in &originals {
+			// Pay interest first
+			accounts::pay_interest::call(&conn, original.account_id)?;
+
+			legs.push(leg(&conn, original)?);
+		}
+
+		let mut reversals = ledger::record::call(&conn, TransactionKind::Reversal, legs)?;
+
+		// After the deposit's reversal, so its balance follows on from it
+		if let Some(matched) = matched {
+			reversals.push(ledger::record::one(&conn, leg(&conn, &matched)?)?);
+			originals.push(matched);
+		}
+
+		let mut adjustments = vec![];
+
+		for (original, reversal) in originals.iter().zip(reversals.iter()) {
+			let account = locked
+				.iter()
+				.find(|account| account.id == original.account_id)
+				.ok_or(format_err!("Account not locked"))?;
+
+			if let Some(attrs) = adjustment(&conn, account, original, reversal, now)? {
+				adjustments.push(ledger::record::one(&conn, attrs)?);
+			}
+		}
+
+		Ok(Reversal {
+			reversals:   reversals,
+			adjustments: adjustments,
+		})
+	})?;
+
+	for transaction in &reversal.reversals {
+		acknowledge_reversal::call(&conn, transaction)?;
+	}
+
+	Ok(reversal)
+}
+
+// The match of a deposit, unless it has been reversed on its own
+fn unreversed_match(
+	conn: &PgConnection,
+	original: &Transaction,
+) -> Result<Option<Transaction>, Error> {
+	if original.kind != TransactionKind::Deposit {
+		return Ok(None);
+	}
+
+	match Transaction::find_match(&conn, original.id)? {
+		Some(matched) => {
+			match Transaction::find_reversal(&conn, matched.id)? {
+				Some(_) => Ok(None),
+				None => Ok(Some(matched)),
+			}
+		},
+		None => Ok(None),
+	}
+}
+
+// Builds the row undoing `original`, expects the account to be locked
+fn leg(conn: &PgConnection, original: &Transaction) -> Result<TransactionAttrs, Error> {
+	let balance = accounts::get_balance::detailed(&conn, original.account_id)?;
+
+	let amount = Cents(0) - original.balance_change();
+
+	let Cents(change) = amount;
+
+	// Interest that hasn't been credited yet is carried by this transaction
+	let attrs = TransactionAttrs {
+		account_id:            original.account_id,
+		kind:                  TransactionKind::Reversal,
+		amount:                amount,
+		balance:               Cents(balance.cents + change),
+		accrued_interest:      balance.accrued_interest.to_big_decimal()?,
+		linked_transaction_id: Some(original.id),
+		created_at:            None,
+		idempotency_key:       None,
+		memo:                  None,
+		category_id:           None,
+	};
+
+	Ok(attrs)
+}
+
+// The interest the mistaken amount earned, or didn't earn, until now.
+// Interest taken back is capped at what has been paid since the mistake.
+fn adjustment(
+	conn: &PgConnection,
+	account: &Account,
+	original: &Transaction,
+	reversal: &Transaction,
+	now: NaiveDateTime,
+) -> Result<Option<TransactionAttrs>, Error> {
+	let Cents(change) = original.balance_change();
+
+	let earned = accounts::accrue_interest::earned_on(
+		&conn,
+		account,
+		Cents(change.abs()),
+		original.created_at,
+		now,
+	)?
+	.to_cents();
+
+	let amount = if change > 0 {
+		let paid = interest_paid_since(&conn, account.id, original.created_at)?;
+
+		Cents(0) - earned.min(paid.max(Cents(0)))
+	} else {
+		earned
+	};
+
+	if amount == Cents(0) {
+		return Ok(None);
+	}
+
+	let balance = accounts::get_balance::detailed(&conn, account.id)?;
+
+	let Cents(interest) = amount;
+
+	let attrs = TransactionAttrs {
+		account_id:            account.id,
+		kind:                  TransactionKind::Interest,
+		amount:                amount,
+		balance:               Cents(balance.cents + interest),
+		accrued_interest:      balance.accrued_interest.to_big_decimal()?,
+		linked_transaction_id: Some(reversal.id),
+		created_at:            None,
+		idempotency_key:       None,
+		memo:                  None,
+		category_id:           None,
+	};
+
+	Ok(Some(attrs))
+}
+
+fn interest_paid_since(
+	conn: &PgConnection,
+	account_id: i32,
+	since: NaiveDateTime,
+) -> Result<Cents, Error> {
+	let transactions = Transaction::find_by_account_id(&conn, account_id, since)?;
+
+	let paid = transactions
+		.into_iter()
+		.filter(|transaction| transaction.kind == TransactionKind::Interest)
+		.fold(Cents(0), |total, transaction| total + transaction.amount);
+
+	Ok(paid)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		models::{self, schema::transactions},
+		utils::tests,
+	};
+	use chrono::Duration;
+	use diesel::{self, prelude::*};
+
+	#[test]
+	fn it_reverses_a_deposit_below_the_balance() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(500)
+				.balance(500)
+				.save(conn);
+
+			// Most of it has been withdrawn since
+			let _withdrawal = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Withdrawal)
+				.amount(400)
+				.balance(100)
+				.save(conn);
+
+			let reversal = call(conn, deposit.id).unwrap();
+
+			assert_eq!(reversal.reversals.len(), 1);
+			assert_eq!(reversal.reversals[0].amount, Cents(-500));
+			assert_eq!(reversal.reversals[0].balance, Cents(-400));
+			assert_eq!(
+				reversal.reversals[0].linked_transaction_id,
+				Some(deposit.id)
+			);
+			assert!(reversal.adjustments.is_empty());
+
+			assert_eq!(accounts::get_balance::call(conn, account.id).unwrap(), -400);
+		})
+	}
+
+	#[test]
+	fn it_only_reverses_once() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(500)
+				.balance(500)
+				.save(conn);
+
+			call(conn, deposit.id).unwrap();
+
+			assert!(call(conn, deposit.id).is_err());
+		})
+	}
+
+	#[test]
+	fn it_reverses_the_match_with_the_deposit() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(1_000)
+				.balance(1_000)
+				.save(conn);
+
+			let matched = models::transaction::factories::transaction_attrs(&account)
+				.kind(TransactionKind::Match)
+				.amount(500)
+				.balance(1_500)
+				.save(conn);
+
+			Transaction::link(conn, matched.id, deposit.id).unwrap();
+
+			let reversal = call(conn, deposit.id).unwrap();
+
+			assert_eq!(reversal.reversals.len(), 2);
+			assert_eq!(
+				reversal.reversals[1].linked_transaction_id,
+				Some(matched.id)
+			);
+			assert_eq!(reversal.reversals[1].balance, Cents(0));
+
+			assert_eq!(accounts::get_balance::call(conn, account.id).unwrap(), 0);
+		})
+	}
+
+	#[test]
+	fn it_takes_back_interest_paid_on_a_mistaken_deposit() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(100_000)
+				.balance(100_000)
+				.save(conn);
+
+			let year_ago = Utc::now().naive_utc() - Duration::days(365) - Duration::hours(1);
+
+			diesel::update(transactions::table.filter(transactions::id.eq(deposit.id)))
+				.set(transactions::created_at.eq(year_ago))
+				.execute(conn)
+				.unwrap();
+
+			let reversal = call(conn, deposit.id).unwrap();
+
+			assert_eq!(reversal.adjustments.len(), 1);
+
+			let adjustment = &reversal.adjustments[0];
+
+			assert_eq!(adjustment.kind, TransactionKind::Interest);
+			assert!(adjustment.amount < Cents(0));
+
+			// Nothing is left of the deposit or its interest
+			assert_eq!(accounts::get_balance::call(conn, account.id).unwrap(), 0);
+		})
+	}
+}
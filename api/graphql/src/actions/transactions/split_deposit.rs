@@ -0,0 +1,220 @@
+use crate::{
+	actions::{accounts, ledger, transactions::describe::Description},
+	models::{
+		account::Account,
+		cents::Cents,
+		split_rule::SplitRule,
+		transaction::{Transaction, TransactionAttrs, TransactionKind},
+	},
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::prelude::*;
+use diesel::pg::PgConnection;
+use failure::Error;
+
+// An account the deposit is shared into
+pub struct Jar {
+	pub account:    Account,
+	pub percentage: BigDecimal,
+}
+
+// Locks the accounts a deposit to `account_id` is split across, in id order.
+// Empty unless the holder has split rules and the account is one of them,
+// so a deposit to any other account goes only there.
+pub fn lock_jars(conn: &PgConnection, account_id: i32) -> Result<Vec<Jar>, Error> {
+	let account = Account::find(&conn, account_id)?;
+
+	let rules = SplitRule::find_by_user_id(&conn, account.user_id)?;
+
+	if !rules.iter().any(|rule| rule.account_id == account_id) {
+		return Ok(vec![]);
+	}
+
+	rules
+		.into_iter()
+		.map(|rule| {
+			Ok(Jar {
+				account:    Account::lock(&conn, rule.account_id)?,
+				percentage: rule.percentage,
+			})
+		})
+		.collect()
+}
+
+// Records the deposit as one journal entry with a deposit into each jar.
+// Each share is rounded down to the cent. The account deposited to gets what
+// the other jars didn't take, including the share of a jar that has been archived
+// or has matured since the rules were saved.
+// The idempotency_key is kept on every part, the one in `account_id` is found by a retry.
+// Expects the jars to be locked.
+pub fn call(
+	conn: &PgConnection,
+	account_id: i32,
+	jars: &[Jar],
+	cents: i64,
+	idempotency_key: &Option<String>,
+	description: &Description,
+) -> Result<Vec<Transaction>, Error> {
+	let now = Utc::now().naive_utc();
+
+	let mut shares = vec![];
+
+	for jar in jars {
+		let usable = accounts::rules::check_deposit(&jar.account, now).is_ok();
+
+		let share = if jar.account.id == account_id || !usable {
+			0
+		} else {
+			(BigDecimal::from(cents) * &jar.percentage / BigDecimal::from(100))
+				.with_scale(0)
+				.to_i64()
+				.ok_or(format_err!("Failed to calculate the split"))?
+		};
+
+		shares.push(share);
+	}
+
+	let taken = shares.iter().sum::<i64>();
+
+	let mut legs = vec![];
+
+	for (jar, share) in jars.iter().zip(shares) {
+		let share = if jar.account.id == account_id {
+			cents - taken
+		} else {
+			share
+		};
+
+		if share <= 0 {
+			continue;
+		}
+
+		// Pay interest first
+		accounts::pay_interest::call(&conn, jar.account.id)?;
+
+		let balance = accounts::get_balance::detailed(&conn, jar.account.id)?;
+
+		// Interest that hasn't been credited yet is carried by this transaction
+		legs.push(TransactionAttrs {
+			account_id:            jar.account.id,
+			kind:                  TransactionKind::Deposit,
+			amount:                Cents(share),
+			balance:               Cents(balance.cents + share),
+			accrued_interest:      balance.accrued_interest.to_big_decimal()?,
+			linked_transaction_id: None,
+			created_at:            None,
+			idempotency_key:       idempotency_key.clone(),
+			memo:                  description.memo.clone(),
+			category_id:           description.category_id,
+		});
+	}
+
+	ledger::record::call(&conn, TransactionKind::Deposit, legs)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		actions::transactions::deposit::{self, DepositInput},
+		models::{self, deposit_source::DepositSource},
+		utils::tests,
+	};
+
+	fn deposit(conn: &PgConnection, account_id: i32, cents: i32) -> Transaction {
+		let input = DepositInput {
+			account_id:      account_id,
+			cents:           cents,
+			effective_at:    None,
+			idempotency_key: None,
+			memo:            None,
+			category:        None,
+		};
+
+		deposit::call(conn, input, DepositSource::Scheduled).unwrap()
+	}
+
+	#[test]
+	fn it_splits_a_deposit_across_the_jars() {
+		tests::with_db(|conn| {
+			let (save, user, _) = tests::account(&conn);
+
+			let spend = models::account::factories::account_attrs(&user).save(conn);
+			let give = models::account::factories::account_attrs(&user).save(conn);
+
+			for (account, percentage) in &[(&save, 50.0), (&spend, 40.0), (&give, 10.0)] {
+				models::split_rule::factories::split_rule_attrs(account, *percentage).save(conn);
+			}
+
+			let transaction = deposit(conn, save.id, 1_005);
+
+			// The cent left over stays in the account deposited to
+			assert_eq!(transaction.account_id, save.id);
+			assert_eq!(transaction.amount, Cents(503));
+
+			let grouped = Transaction::find_grouped(conn, transaction.id).unwrap();
+
+			let amounts: Vec<(i32, Cents)> =
+				grouped.iter().map(|t| (t.account_id, t.amount)).collect();
+
+			assert_eq!(
+				amounts,
+				vec![
+					(save.id, Cents(503)),
+					(spend.id, Cents(402)),
+					(give.id, Cents(100))
+				]
+			);
+			assert_eq!(accounts::get_balance::call(conn, give.id).unwrap(), 100);
+		})
+	}
+
+	#[test]
+	fn it_doesnt_split_deposits_to_other_accounts() {
+		tests::with_db(|conn| {
+			let (save, user, _) = tests::account(&conn);
+
+			let spend = models::account::factories::account_attrs(&user).save(conn);
+			let term = models::account::factories::account_attrs(&user).save(conn);
+
+			models::split_rule::factories::split_rule_attrs(&save, 50.0).save(conn);
+			models::split_rule::factories::split_rule_attrs(&spend, 50.0).save(conn);
+
+			let transaction = deposit(conn, term.id, 1_000);
+
+			assert_eq!(transaction.amount, Cents(1_000));
+			assert_eq!(
+				Transaction::find_grouped(conn, transaction.id)
+					.unwrap()
+					.len(),
+				1
+			);
+		})
+	}
+	#[test]
+	fn it_gives_the_share_of_an_archived_jar_to_the_account_deposited_to() {
+		tests::with_db(|conn| {
+			let (save, user, _) = tests::account(&conn);
+
+			let spend = models::account::factories::account_attrs(&user).save(conn);
+			let give = models::account::factories::account_attrs(&user).save(conn);
+
+			for (account, percentage) in &[(&save, 50.0), (&spend, 40.0), (&give, 10.0)] {
+				models::split_rule::factories::split_rule_attrs(account, *percentage).save(conn);
+			}
+
+			// Archived after the rules were saved
+			spend.archived_at(conn, Utc::now().naive_utc());
+
+			let transaction = deposit(conn, save.id, 1_005);
+
+			let grouped = Transaction::find_grouped(conn, transaction.id).unwrap();
+
+			let amounts: Vec<(i32, Cents)> =
+				grouped.iter().map(|t| (t.account_id, t.amount)).collect();
+
+			assert_eq!(amounts, vec![(save.id, Cents(905)), (give.id, Cents(100))]);
+			assert_eq!(accounts::get_balance::call(conn, spend.id).unwrap(), 0);
+		})
+	}
+}
@@ -0,0 +1,257 @@
+use crate::{
+	actions::{
+		accounts::{self, check_withdrawal_rules::Route},
+		emails::acknowledge_transfer,
+		ledger,
+	},
+	models::{
+		account::Account,
+		cents::Cents,
+		transaction::{Transaction, TransactionAttrs, TransactionKind},
+		user::User,
+	},
+	utils::db_transaction,
+};
+use chrono::prelude::*;
+use diesel::pg::PgConnection;
+use failure::Error;
+
+#[derive(GraphQLInputObject, Clone)]
+pub struct TransferInput {
+	pub from_account_id: i32,
+	pub to_account_id:   i32,
+	pub cents:           i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transfer {
+	pub from: Transaction,
+	pub to:   Transaction,
+}
+
+pub fn call(conn: &PgConnection, input: TransferInput) -> Result<Transfer, Error> {
+	let transfer = record(&conn, &input)?;
+
+	// Send an email to both account holders
+	acknowledge_transfer::call(&conn, &transfer.from, &transfer.to)?;
+
+	Ok(transfer)
+}
+
+// Makes the transfer without telling the account holders,
+// so it can run inside another database transaction
+pub fn record(conn: &PgConnection, input: &TransferInput) -> Result<Transfer, Error> {
+	// Fail if cents is negative
+	if input.cents <= 0 {
+		return Err(format_err!("Invalid amount"));
+	}
+
+	if input.from_account_id == input.to_account_id {
+		return Err(format_err!("Cannot transfer to the same account"));
+	}
+
+	let from_account = Account::find(&conn, input.from_account_id)?;
+	let to_account = Account::find(&conn, input.to_account_id)?;
+
+	// Money stays within the family
+	let from_user = User::find(&conn, from_account.user_id)?;
+	let to_user = User::find(&conn, to_account.user_id)?;
+
+	if from_user.client_id != to_user.client_id {
+		return Err(format_err!("Cannot transfer to another client"));
+	}
+
+	let cents = input.cents as i64;
+
+	db_transaction::serializable(&conn, || {
+		// Always lock in the same order, so opposite transfers don't deadlock
+		let (from_account, to_account) = if from_account.id < to_account.id {
+			let from_account = Account::lock(&conn, from_account.id)?;
+			(from_account, Account::lock(&conn, to_account.id)?)
+		} else {
+			let to_account = Account::lock(&conn, to_account.id)?;
+			(Account::lock(&conn, from_account.id)?, to_account)
+		};
+
+		let now = Utc::now().naive_utc();
+
+		accounts::rules::check_withdrawal(&from_account, now)?;
+		accounts::rules::check_deposit(&to_account, now)?;
+
+		// Money leaving the account is held to its withdrawal limits
+		accounts::check_withdrawal_rules::call(&conn, &from_account, cents, now, Route::Direct)?;
+
+		let legs = vec![
+			leg(&conn, from_account.id, -cents)?,
+			leg(&conn, to_account.id, cents)?,
+		];
+
+		// Both legs are one journal entry
+		let transactions = ledger::record::call(&conn, TransactionKind::Transfer, legs)?;
+
+		let (from, to) = match transactions.as_slice() {
+			[from, to] => (from.clone(), to.clone()),
+			_ => return Err(format_err!("Failed to record the transfer")),
+		};
+
+		let from = Transaction::link(&conn, from.id, to.id)?;
+		let to = Transaction::link(&conn, to.id, from.id)?;
+
+		Ok(Transfer { from, to })
+	})
+}
+
+// Builds the transfer row of the account, `amount` is negative when money leaves.
+// Expects the account to be locked.
+fn leg(conn: &PgConnection, account_id: i32, amount: i64) -> Result<TransactionAttrs, Error> {
+	// Pay interest first
+	accounts::pay_interest::call(&conn, account_id)?;
+
+	let balance = accounts::get_balance::detailed(&conn, account_id)?;
+
+	let new_balance = balance.cents + amount;
+
+	// Do not allow transferring past the account balance
+	if new_balance < 0 {
+		return Err(format_err!("Not enough balance"));
+	}
+
+	// Interest that hasn't been credited yet is carried by this transaction
+	let attrs = TransactionAttrs {
+		account_id:            account_id,
+		kind:                  TransactionKind::Transfer,
+		amount:                Cents(amount),
+		balance:               Cents(new_balance),
+		accrued_interest:      balance.accrued_interest.to_big_decimal()?,
+		linked_transaction_id: None,
+		created_at:            None,
+		idempotency_key:       None,
+		memo:                  None,
+		category_id:           None,
+	};
+
+	Ok(attrs)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{models, utils::tests};
+
+	#[test]
+	fn it_moves_money_between_accounts() {
+		tests::with_db(|conn| {
+			let (account, user, client) = tests::account(&conn);
+
+			let sibling = models::user::factories::user_attrs(&client)
+				.username("alex")
+				.save(conn);
+
+			let other = models::account::factories::account_attrs(&sibling).save(conn);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			let input = TransferInput {
+				from_account_id: account.id,
+				to_account_id:   other.id,
+				cents:           200,
+			};
+
+			let transfer = call(conn, input).unwrap();
+
+			assert_eq!(transfer.from.amount, Cents(-200));
+			assert_eq!(transfer.from.balance, Cents(100));
+			assert_eq!(transfer.to.amount, Cents(200));
+			assert_eq!(transfer.to.balance, Cents(200));
+
+			assert_eq!(transfer.from.linked_transaction_id, Some(transfer.to.id));
+			assert_eq!(transfer.to.linked_transaction_id, Some(transfer.from.id));
+
+			assert_ne!(user.id, sibling.id);
+		})
+	}
+
+	#[test]
+	fn it_doesnt_allow_overdrawing() {
+		tests::with_db(|conn| {
+			let (account, user, _) = tests::account(&conn);
+
+			let other = models::account::factories::account_attrs(&user).save(conn);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(100)
+				.balance(100)
+				.save(conn);
+
+			let input = TransferInput {
+				from_account_id: account.id,
+				to_account_id:   other.id,
+				cents:           200,
+			};
+
+			assert!(call(conn, input).is_err());
+
+			assert_eq!(accounts::get_balance::call(conn, other.id).unwrap(), 0);
+		})
+	}
+
+	#[test]
+	fn it_doesnt_transfer_to_another_client() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let other_client = tests::client(&conn);
+
+			let stranger = models::user::factories::user_attrs(&other_client)
+				.username("alex")
+				.save(conn);
+
+			let other = models::account::factories::account_attrs(&stranger).save(conn);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			let input = TransferInput {
+				from_account_id: account.id,
+				to_account_id:   other.id,
+				cents:           200,
+			};
+
+			assert!(call(conn, input).is_err());
+		})
+	}
+
+	#[test]
+	fn it_holds_transfers_to_the_withdrawal_rules() {
+		tests::with_db(|conn| {
+			let (account, user, _) = tests::account(&conn);
+
+			let other = models::account::factories::account_attrs(&user).save(conn);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(5_000)
+				.balance(5_000)
+				.save(conn);
+
+			let _rule = models::withdrawal_rule::factories::withdrawal_rule_attrs(&account)
+				.amount(1_000)
+				.save(conn);
+
+			let input = TransferInput {
+				from_account_id: account.id,
+				to_account_id:   other.id,
+				cents:           800,
+			};
+
+			assert!(call(conn, input.clone()).is_ok());
+
+			// The first transfer counts towards the weekly limit
+			assert!(call(conn, input).is_err());
+		})
+	}
+}
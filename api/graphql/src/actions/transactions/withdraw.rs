@@ -1,60 +1,148 @@
+use chrono::prelude::*;
 use diesel::pg::PgConnection;
 use failure::Error;
 // use models::account::Account;
 use crate::{
-	actions::{accounts, emails::acknowledge_withdrawal},
+	actions::{
+		accounts::{self, check_withdrawal_rules::Route},
+		emails::acknowledge_withdrawal,
+		ledger,
+		transactions::{backdate, describe},
+	},
 	models::{
+		account::Account,
 		cents::Cents,
 		transaction::{Transaction, TransactionAttrs, TransactionKind},
 	},
+	utils::{dates, db_transaction},
 };
 
+// effective_at is POSIX time in seconds, for recording it after the fact.
+// A retried call with the same idempotency_key returns the first transaction,
+// reusing the key for a different withdrawal fails.
+// category is a name, it is added to the family's categories if it is new.
 #[derive(GraphQLInputObject, Clone)]
 pub struct WithdrawalInput {
-	pub account_id: i32,
-	pub cents:      i32,
+	pub account_id:      i32,
+	pub cents:           i32,
+	pub effective_at:    Option<f64>,
+	pub idempotency_key: Option<String>,
+	pub memo:            Option<String>,
+	pub category:        Option<String>,
 }
 
 pub fn call(conn: &PgConnection, input: WithdrawalInput) -> Result<Transaction, Error> {
-	// Fail if cents is negative
-	if input.cents <= 0 {
-		return Err(format_err!("Invalid amount"));
-	}
-
-	// Pay interest first
-	accounts::pay_interest::call(&conn, input.account_id)?;
+	let (transaction, created) = record(&conn, &input, Route::Direct)?;
 
-	let current_balance = accounts::get_balance::call(&conn, input.account_id)?;
-
-	let amount = input.cents as i64;
-
-	// Calculate running balance
-	let new_balance = current_balance - amount;
-
-	// Do not allow withdrawing past the account balance
-	if new_balance < 0 {
-		return Err(format_err!("Not enough balance"));
+	if created {
+		acknowledge_withdrawal::call(&conn, &transaction)?;
 	}
 
-	let attrs = TransactionAttrs {
-		account_id: input.account_id,
-		kind:       TransactionKind::Withdrawal,
-		amount:     Cents(amount),
-		balance:    Cents(new_balance),
-	};
-
-	let transaction = Transaction::create(conn, attrs).map_err(|e| format_err!("{}", e))?;
+	Ok(transaction)
+}
 
-	acknowledge_withdrawal::call(&conn, &transaction)?;
+// Makes the transaction without telling the account holder, so it can run inside another
+// database transaction. The flag is false when a retry got the first transaction.
+// `route` says which of the account's withdrawal rules apply.
+pub fn record(
+	conn: &PgConnection,
+	input: &WithdrawalInput,
+	route: Route,
+) -> Result<(Transaction, bool), Error> {
+	// Fail if cents is negative
+	if input.cents <= 0 {
+		return Err(format_err!("Invalid amount"));
+	}
 
-	Ok(transaction)
+	// Lock the account, so concurrent changes build on each other's balance
+	db_transaction::serializable(&conn, || {
+		let account = Account::lock(&conn, input.account_id)?;
+
+		// Checked under the lock, so a retry racing the first call waits for it
+		if let Some(key) = &input.idempotency_key {
+			if let Some(existing) =
+				Transaction::find_by_idempotency_key(&conn, input.account_id, key)?
+			{
+				if existing.kind != TransactionKind::Withdrawal
+					|| existing.amount != Cents(input.cents as i64)
+					|| !describe::matches(&conn, &existing, &input.memo, &input.category)?
+				{
+					return Err(format_err!("Idempotency key has already been used"));
+				}
+
+				return Ok((existing, false));
+			}
+		}
+
+		let description = describe::call(&conn, input.account_id, &input.memo, &input.category)?;
+
+		let effective_at = match input.effective_at {
+			Some(seconds) => Some(dates::from_seconds(seconds).ok_or(format_err!("Invalid date"))?),
+			None => None,
+		};
+
+		let at = effective_at.unwrap_or(Utc::now().naive_utc());
+
+		// Archived accounts and term deposits before maturity are locked
+		accounts::rules::check_withdrawal(&account, at)?;
+
+		// The limits set for the account, every broken one is reported
+		accounts::check_withdrawal_rules::call(&conn, &account, input.cents as i64, at, route)?;
+
+		if let Some(effective_at) = effective_at {
+			let cents = input.cents as i64;
+
+			return backdate::call(
+				&conn,
+				&account,
+				TransactionKind::Withdrawal,
+				cents,
+				effective_at,
+				input.idempotency_key.clone(),
+				description,
+			)
+			.map(|transaction| (transaction, true));
+		}
+
+		// Pay interest first
+		accounts::pay_interest::call(&conn, input.account_id)?;
+
+		let balance = accounts::get_balance::detailed(&conn, input.account_id)?;
+
+		let current_balance = balance.cents;
+
+		let amount = input.cents as i64;
+
+		// Calculate running balance
+		let new_balance = current_balance - amount;
+
+		// Do not allow withdrawing past the account balance
+		if new_balance < 0 {
+			return Err(format_err!("Not enough balance"));
+		}
+
+		// Interest that hasn't been credited yet is carried by this transaction
+		let attrs = TransactionAttrs {
+			account_id:            input.account_id,
+			kind:                  TransactionKind::Withdrawal,
+			amount:                Cents(amount),
+			balance:               Cents(new_balance),
+			accrued_interest:      balance.accrued_interest.to_big_decimal()?,
+			linked_transaction_id: None,
+			created_at:            None,
+			idempotency_key:       input.idempotency_key.clone(),
+			memo:                  description.memo,
+			category_id:           description.category_id,
+		};
+
+		ledger::record::one(&conn, attrs).map(|transaction| (transaction, true))
+	})
 }
 
 #[cfg(test)]
 mod test {
 	use super::*;
-	use crate::models;
-	use crate::utils::tests;
+	use crate::{models, utils::tests};
 
 	#[test]
 	fn it_creates_a_transaction() {
@@ -66,12 +154,17 @@ mod test {
 			let account = models::account::factories::account_attrs(&user).save(conn);
 
 			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
 				.balance(300)
 				.save(conn);
 
 			let input = WithdrawalInput {
-				account_id: account.id,
-				cents:      200,
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
 			};
 
 			let transaction = call(conn, input).unwrap();
@@ -92,8 +185,12 @@ mod test {
 			let account = models::account::factories::account_attrs(&user).save(conn);
 
 			let input = WithdrawalInput {
-				account_id: account.id,
-				cents:      -200,
+				account_id:      account.id,
+				cents:           -200,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
 			};
 
 			let result = call(conn, input);
@@ -112,8 +209,12 @@ mod test {
 			let account = models::account::factories::account_attrs(&user).save(conn);
 
 			let input = WithdrawalInput {
-				account_id: account.id,
-				cents:      200,
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
 			};
 
 			let result = call(conn, input);
@@ -132,12 +233,17 @@ mod test {
 			let account = models::account::factories::account_attrs(&user).save(conn);
 
 			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
 				.balance(300)
 				.save(conn);
 
 			let input = WithdrawalInput {
-				account_id: account.id,
-				cents:      200,
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
 			};
 
 			let transaction = call(conn, input).unwrap();
@@ -145,4 +251,129 @@ mod test {
 			assert_eq!(transaction.balance, Cents(100));
 		})
 	}
+
+	#[test]
+	fn it_doesnt_withdraw_from_a_locked_term_deposit() {
+		tests::with_db(|conn| {
+			let (user, _) = tests::user(&conn);
+
+			let account = models::account::factories::account_attrs(&user)
+				.kind(models::account::Kind::TermDeposit)
+				.matures_at(Utc::now().naive_utc() + chrono::Duration::days(30))
+				.save(conn);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			let input = WithdrawalInput {
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
+			};
+
+			let result = call(conn, input);
+
+			assert!(result.is_err());
+		})
+	}
+
+	#[test]
+	fn it_doesnt_reuse_a_key_for_another_kind() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _deposit = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.idempotency_key("retry-1")
+				.save(conn);
+
+			let input = WithdrawalInput {
+				account_id:      account.id,
+				cents:           200,
+				effective_at:    None,
+				idempotency_key: Some("retry-1".to_string()),
+				memo:            None,
+				category:        None,
+			};
+
+			let result = call(conn, input);
+
+			assert!(result.is_err());
+		})
+	}
+
+	#[test]
+	fn it_doesnt_reuse_a_key_for_another_withdrawal() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(300)
+				.balance(300)
+				.save(conn);
+
+			let input = WithdrawalInput {
+				account_id:      account.id,
+				cents:           100,
+				effective_at:    None,
+				idempotency_key: Some("withdrawal-1".to_string()),
+				memo:            None,
+				category:        None,
+			};
+
+			let first = call(conn, input.clone()).unwrap();
+
+			assert_eq!(call(conn, input.clone()).unwrap().id, first.id);
+
+			let more = WithdrawalInput {
+				cents: 200,
+				..input
+			};
+
+			assert!(call(conn, more).is_err());
+
+			let balance = accounts::get_balance::call(conn, account.id).unwrap();
+
+			assert_eq!(balance, 200);
+		})
+	}
+
+	#[test]
+	fn it_reports_the_broken_withdrawal_rules() {
+		tests::with_db(|conn| {
+			let (account, _, _) = tests::account(&conn);
+
+			let _prev = models::transaction::factories::transaction_attrs(&account)
+				.amount(3_000)
+				.balance(3_000)
+				.save(conn);
+
+			let _rule = models::withdrawal_rule::factories::withdrawal_rule_attrs(&account)
+				.kind(models::withdrawal_rule::WithdrawalRuleKind::ApprovalAbove)
+				.amount(1_000)
+				.save(conn);
+
+			let input = WithdrawalInput {
+				account_id:      account.id,
+				cents:           2_000,
+				effective_at:    None,
+				idempotency_key: None,
+				memo:            None,
+				category:        None,
+			};
+
+			let error = call(conn, input).unwrap_err();
+
+			let errors = crate::utils::mutations::failure_to_mutation_errors(error);
+
+			assert_eq!(errors.len(), 1);
+			assert_eq!(errors[0].key, "APPROVAL_ABOVE");
+		})
+	}
 }
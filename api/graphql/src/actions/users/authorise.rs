@@ -19,3 +19,16 @@ pub fn can_archive(conn: &PgConnection, current_user: &User, user_id: i32) -> Re
 
 	Ok(is_authorised)
 }
+
+// Admins manage the investors of their client
+pub fn can_admin(conn: &PgConnection, current_user: &User, user_id: i32) -> Result<bool, Error> {
+	if current_user.role != Role::Admin {
+		return Ok(false);
+	}
+
+	let user = User::find(&conn, user_id)?;
+
+	let is_authorised = current_user.client_id == user.client_id;
+
+	Ok(is_authorised)
+}
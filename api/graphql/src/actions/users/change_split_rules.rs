@@ -0,0 +1,133 @@
+use crate::{
+	actions::accounts,
+	models::{
+		account::Account,
+		split_rule::{SplitRule, SplitRuleAttrs},
+	},
+};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::prelude::*;
+use diesel::{pg::PgConnection, prelude::*};
+use failure::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Split {
+	pub account_id: i32,
+	pub percentage: BigDecimal,
+}
+
+// Replaces how the investor's deposits are split, an empty list stops splitting
+pub fn call(
+	conn: &PgConnection,
+	user_id: i32,
+	splits: Vec<Split>,
+) -> Result<Vec<SplitRule>, Error> {
+	let now = Utc::now().naive_utc();
+
+	let mut total = BigDecimal::zero();
+
+	for (index, split) in splits.iter().enumerate() {
+		if splits[..index]
+			.iter()
+			.any(|other| other.account_id == split.account_id)
+		{
+			return Err(format_err!("Only one split for each account"));
+		}
+
+		if split.percentage <= BigDecimal::zero() {
+			return Err(format_err!("Invalid percentage"));
+		}
+
+		let account = Account::find(&conn, split.account_id)?;
+
+		if account.user_id != user_id {
+			return Err(format_err!("Account belongs to another investor"));
+		}
+
+		// Every account has to be able to take its share
+		accounts::rules::check_deposit(&account, now)?;
+
+		total = total + &split.percentage;
+	}
+
+	if !splits.is_empty() && total != BigDecimal::from(100) {
+		return Err(format_err!("The percentages must add up to 100"));
+	}
+
+	conn.transaction(|| {
+		SplitRule::delete_by_user_id(&conn, user_id)?;
+
+		splits
+			.into_iter()
+			.map(|split| {
+				let attrs = SplitRuleAttrs {
+					user_id:    user_id,
+					account_id: split.account_id,
+					percentage: split.percentage,
+				};
+
+				SplitRule::create(&conn, attrs)
+			})
+			.collect()
+	})
+	.map_err(|e| format_err!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{models, utils::tests};
+	use bigdecimal::FromPrimitive;
+
+	fn split(account: &Account, percentage: f32) -> Split {
+		Split {
+			account_id: account.id,
+			percentage: BigDecimal::from_f32(percentage).unwrap(),
+		}
+	}
+
+	#[test]
+	fn it_replaces_the_splits() {
+		tests::with_db(|conn| {
+			let (save, user, _) = tests::account(&conn);
+
+			let spend = models::account::factories::account_attrs(&user).save(conn);
+
+			let _old = models::split_rule::factories::split_rule_attrs(&save, 100.0).save(conn);
+
+			let saved = call(conn, user.id, vec![split(&save, 60.0), split(&spend, 40.0)]).unwrap();
+
+			assert_eq!(saved.len(), 2);
+			assert_eq!(SplitRule::find_by_user_id(conn, user.id).unwrap(), saved);
+		})
+	}
+
+	#[test]
+	fn it_needs_the_percentages_to_add_up() {
+		tests::with_db(|conn| {
+			let (save, user, _) = tests::account(&conn);
+
+			let spend = models::account::factories::account_attrs(&user).save(conn);
+
+			let result = call(conn, user.id, vec![split(&save, 60.0), split(&spend, 30.0)]);
+
+			assert!(result.is_err());
+		})
+	}
+
+	#[test]
+	fn it_only_splits_into_the_investors_accounts() {
+		tests::with_db(|conn| {
+			let (save, user, client) = tests::account(&conn);
+
+			let sibling = models::user::factories::user_attrs(&client)
+				.username("alex")
+				.save(conn);
+			let other = models::account::factories::account_attrs(&sibling).save(conn);
+
+			let result = call(conn, user.id, vec![split(&save, 50.0), split(&other, 50.0)]);
+
+			assert!(result.is_err());
+		})
+	}
+}
@@ -1,5 +1,6 @@
 pub mod archive;
 pub mod authorise;
+pub mod change_split_rules;
 pub mod confirm_email;
 pub mod create;
 pub mod decode_token;
@@ -0,0 +1,78 @@
+#[macro_use]
+extern crate failure;
+#[macro_use]
+extern crate log;
+extern crate env_logger;
+
+use api::{actions, models::cents::Cents, utils::db_conn};
+use failure::Error;
+
+// Maintenance commands, e.g.
+// cargo run --bin ledger -- verify
+// cargo run --bin ledger -- repair 12 --confirm
+fn main() {
+	env_logger::init();
+
+	let args: Vec<String> = std::env::args().skip(1).collect();
+
+	if let Err(e) = run(&args) {
+		error!("{}", e);
+		std::process::exit(1);
+	}
+}
+
+fn run(args: &[String]) -> Result<(), Error> {
+	let pool = db_conn::init_pool();
+	let conn = pool.get()?;
+
+	let command = args.get(0).map(String::as_str);
+
+	match command {
+		Some("verify") => {
+			let breaks = actions::ledger::verify::all(&conn)?;
+
+			for b in &breaks {
+				let Cents(expected) = b.expected_balance;
+				let Cents(stored) = b.stored_balance;
+
+				println!(
+					"account {} transaction {}: {:?} expected {} stored {}",
+					b.account_id, b.transaction_id, b.kind, expected, stored
+				);
+			}
+
+			println!("{} breaks found", breaks.len());
+
+			Ok(())
+		},
+		Some("repair") => {
+			let account_id = args
+				.get(1)
+				.ok_or(format_err!("Missing account id"))?
+				.parse::<i32>()?;
+
+			if !args.iter().any(|arg| arg == "--confirm") {
+				let breaks = actions::ledger::verify::call(&conn, account_id)?;
+
+				println!(
+					"{} breaks found, run again with --confirm to repair account {}",
+					breaks.len(),
+					account_id
+				);
+
+				return Ok(());
+			}
+
+			let transactions = actions::ledger::repair::call(&conn, account_id)?;
+
+			println!("Repaired {} transactions", transactions.len());
+
+			Ok(())
+		},
+		_ => {
+			Err(format_err!(
+				"Usage: ledger verify | ledger repair <account_id> --confirm"
+			))
+		},
+	}
+}
@@ -1,15 +1,45 @@
 use crate::graph::{
 	app::mutations::{
+		archive_account::{self, ArchiveAccountInput, ArchiveAccountResponse},
 		archive_user::{self, ArchiveUserResponse},
+		cancel_transaction_request::{self, CancelTransactionRequestResponse},
+		change_compounding::{
+			self, ChangeAccountCompoundingInput, ChangeAccountCompoundingResponse,
+		},
 		change_interest::{self, ChangeAccountInterestInput, ChangeAccountInterestResponse},
+		change_interest_posting::{
+			self, ChangeInterestPostingInput, ChangeInterestPostingResponse,
+		},
+		change_interest_tiers::{
+			self, ChangeAccountInterestTiersInput, ChangeAccountInterestTiersResponse,
+		},
+		change_matching::{self, ChangeAccountMatchingInput, ChangeAccountMatchingResponse},
+		change_request_expiry::{self, ChangeRequestExpiryInput, ChangeRequestExpiryResponse},
+		change_split_rules::{self, ChangeSplitRulesInput, ChangeSplitRulesResponse},
+		change_withdrawal_rules::{
+			self, ChangeAccountWithdrawalRulesInput, ChangeAccountWithdrawalRulesResponse,
+		},
+		create_account::{self, CreateAccountInput, CreateAccountResponse},
+		create_goal::{self, GoalInput, GoalResponse},
+		create_scheduled_transaction::{
+			self, ScheduledTransactionInput, ScheduledTransactionResponse,
+		},
 		create_user::{self, CreateUserInput, CreateUserResponse},
+		delete_goal, delete_scheduled_transaction,
 		deposit::{self, DepositInput, DepositResponse},
 		invite_admin::{self, InvitationInput, InvitationResponse},
+		repair_ledger::{self, RepairLedgerInput, RepairLedgerResponse},
+		request_deposit::{self, RequestDepositInput, RequestDepositResponse},
 		request_withdrawal::{self, RequestWithdrawalInput, RequestWithdrawalResponse},
 		resolve_transaction_request::{
 			self, ResolveTransactionRequestInput, ResolveTransactionRequestResponse,
 		},
+		reverse_transaction::{self, ReverseTransactionResponse},
+		transfer::{self, TransferInput, TransferResponse},
+		unarchive_account::{self, UnarchiveAccountResponse},
 		unarchive_user::{self, UnarchiveUserResponse},
+		update_goal::{self, UpdateGoalInput},
+		update_scheduled_transaction::{self, UpdateScheduledTransactionInput},
 		withdraw::{self, WithdrawalInput, WithdrawalResponse},
 	},
 	AppContext,
@@ -36,24 +66,79 @@ graphql_object!(AppMutationRoot: AppContext | &self | {
 		unarchive_user::call(executor, user_id)
 	}
 
+	field changeSplitRules(&executor, input: ChangeSplitRulesInput) -> FieldResult<ChangeSplitRulesResponse> {
+		change_split_rules::call(executor, input)
+	}
+
 	field inviteAdmin(&executor, input: InvitationInput) -> FieldResult<InvitationResponse> {
 		invite_admin::call(executor, input)
 	}
 
+	// clients
+	field changeInterestPosting(&executor, input: ChangeInterestPostingInput) -> FieldResult<ChangeInterestPostingResponse> {
+		change_interest_posting::call(executor, input)
+	}
+
+	field changeRequestExpiry(&executor, input: ChangeRequestExpiryInput) -> FieldResult<ChangeRequestExpiryResponse> {
+		change_request_expiry::call(executor, input)
+	}
+
 	// accounts
+	field createAccount(&executor, input: CreateAccountInput) -> FieldResult<CreateAccountResponse> {
+		create_account::call(executor, input)
+	}
+
+	field archiveAccount(&executor, input: ArchiveAccountInput) -> FieldResult<ArchiveAccountResponse> {
+		archive_account::call(executor, input)
+	}
+
+	field unarchiveAccount(&executor, account_id: i32) -> FieldResult<UnarchiveAccountResponse> {
+		unarchive_account::call(executor, account_id)
+	}
+
 	field changeAccountInterest(&executor, input: ChangeAccountInterestInput) -> FieldResult<ChangeAccountInterestResponse> {
 		change_interest::call(executor, input)
 	}
 
+	field changeAccountInterestTiers(&executor, input: ChangeAccountInterestTiersInput) -> FieldResult<ChangeAccountInterestTiersResponse> {
+		change_interest_tiers::call(executor, input)
+	}
+
+	field changeAccountWithdrawalRules(&executor, input: ChangeAccountWithdrawalRulesInput) -> FieldResult<ChangeAccountWithdrawalRulesResponse> {
+		change_withdrawal_rules::call(executor, input)
+	}
+
+	field changeAccountMatching(&executor, input: ChangeAccountMatchingInput) -> FieldResult<ChangeAccountMatchingResponse> {
+		change_matching::call(executor, input)
+	}
+
+	field changeAccountCompounding(&executor, input: ChangeAccountCompoundingInput) -> FieldResult<ChangeAccountCompoundingResponse> {
+		change_compounding::call(executor, input)
+	}
+
+	field repairLedger(&executor, input: RepairLedgerInput) -> FieldResult<RepairLedgerResponse> {
+		repair_ledger::call(executor, input)
+	}
+
 	// transactions
 	field requestWithdraw(&executor, input: RequestWithdrawalInput) -> FieldResult<RequestWithdrawalResponse> {
 		request_withdrawal::call(executor, input)
 	}
 
+	// Money the investor has handed over, deposited once an admin approves
+	field requestDeposit(&executor, input: RequestDepositInput) -> FieldResult<RequestDepositResponse> {
+		request_deposit::call(executor, input)
+	}
+
 	field resolveTransactionRequest(&executor, input: ResolveTransactionRequestInput) -> FieldResult<ResolveTransactionRequestResponse> {
 		resolve_transaction_request::call(executor, input)
 	}
 
+	// Only the account holder can take back their request
+	field cancelTransactionRequest(&executor, transaction_request_id: i32) -> FieldResult<CancelTransactionRequestResponse> {
+		cancel_transaction_request::call(executor, transaction_request_id)
+	}
+
 	field deposit(&executor, input: DepositInput) -> FieldResult<DepositResponse> {
 		deposit::call(executor, input)
 	}
@@ -62,4 +147,38 @@ graphql_object!(AppMutationRoot: AppContext | &self | {
 		withdraw::call(executor, input)
 	}
 
+	field transfer(&executor, input: TransferInput) -> FieldResult<TransferResponse> {
+		transfer::call(executor, input)
+	}
+
+	field reverseTransaction(&executor, transaction_id: i32) -> FieldResult<ReverseTransactionResponse> {
+		reverse_transaction::call(executor, transaction_id)
+	}
+
+	// scheduled transactions
+	field createScheduledTransaction(&executor, input: ScheduledTransactionInput) -> FieldResult<ScheduledTransactionResponse> {
+		create_scheduled_transaction::call(executor, input)
+	}
+
+	field updateScheduledTransaction(&executor, input: UpdateScheduledTransactionInput) -> FieldResult<ScheduledTransactionResponse> {
+		update_scheduled_transaction::call(executor, input)
+	}
+
+	field deleteScheduledTransaction(&executor, scheduled_transaction_id: i32) -> FieldResult<ScheduledTransactionResponse> {
+		delete_scheduled_transaction::call(executor, scheduled_transaction_id)
+	}
+
+	// goals
+	field createGoal(&executor, input: GoalInput) -> FieldResult<GoalResponse> {
+		create_goal::call(executor, input)
+	}
+
+	field updateGoal(&executor, input: UpdateGoalInput) -> FieldResult<GoalResponse> {
+		update_goal::call(executor, input)
+	}
+
+	field deleteGoal(&executor, goal_id: i32) -> FieldResult<GoalResponse> {
+		delete_goal::call(executor, goal_id)
+	}
+
 });
@@ -0,0 +1,81 @@
+use crate::{
+	actions,
+	graph::AppContext,
+	models::account::Account,
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use juniper::{Executor, FieldError, FieldResult};
+
+#[derive(Clone, GraphQLInputObject)]
+pub struct ArchiveAccountInput {
+	pub account_id:          i32,
+	pub sweep_to_account_id: Option<i32>,
+}
+
+#[derive(Clone)]
+pub struct ArchiveAccountResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	account: Option<Account>,
+}
+
+graphql_object!(ArchiveAccountResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field account() -> &Option<Account> {
+		&self.account
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: ArchiveAccountInput,
+) -> FieldResult<ArchiveAccountResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise both accounts
+	let can = actions::accounts::authorise::can_admin(&conn, input.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	if let Some(sweep_to_account_id) = input.sweep_to_account_id {
+		let can_sweep =
+			actions::accounts::authorise::can_admin(&conn, sweep_to_account_id, &current_user)?;
+
+		if can_sweep == false {
+			return Err(FieldError::from("Unauthorised"));
+		}
+	}
+
+	let result =
+		actions::accounts::archive::call(&conn, input.account_id, input.sweep_to_account_id);
+
+	let response = match result {
+		Ok(account) => {
+			ArchiveAccountResponse {
+				success: true,
+				errors:  vec![],
+				account: Some(account),
+			}
+		},
+		Err(e) => {
+			ArchiveAccountResponse {
+				success: false,
+				errors:  failure_to_mutation_errors(e),
+				account: None,
+			}
+		},
+	};
+
+	Ok(response)
+}
@@ -0,0 +1,68 @@
+use juniper::{Executor, FieldError, FieldResult};
+
+use crate::{
+	actions::{accounts::authorise, transactions::cancel_transaction_request},
+	graph::AppContext,
+	models::transaction_request::TransactionRequest,
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+
+#[derive(Clone)]
+pub struct CancelTransactionRequestResponse {
+	success:             bool,
+	errors:              Vec<MutationError>,
+	transaction_request: Option<TransactionRequest>,
+}
+
+graphql_object!(CancelTransactionRequestResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field transaction_request() -> &Option<TransactionRequest> {
+		&self.transaction_request
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	transaction_request_id: i32,
+) -> FieldResult<CancelTransactionRequestResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise, only whoever the request is for can cancel it
+	let transaction_request = TransactionRequest::find(&conn, transaction_request_id)?;
+
+	let is_holder = authorise::is_holder(&conn, transaction_request.account_id, &current_user)?;
+
+	if is_holder == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = cancel_transaction_request::call(&conn, transaction_request_id);
+
+	let response = match result {
+		Ok(transaction_request) => {
+			CancelTransactionRequestResponse {
+				success:             true,
+				errors:              vec![],
+				transaction_request: Some(transaction_request),
+			}
+		},
+		Err(e) => {
+			CancelTransactionRequestResponse {
+				success:             false,
+				errors:              failure_to_mutation_errors(e),
+				transaction_request: None,
+			}
+		},
+	};
+
+	Ok(response)
+}
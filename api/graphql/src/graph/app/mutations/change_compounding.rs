@@ -0,0 +1,72 @@
+use crate::{
+	actions,
+	graph::AppContext,
+	models::account::{Account, Compounding},
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use juniper::{Executor, FieldError, FieldResult};
+
+#[derive(Clone, GraphQLInputObject)]
+pub struct ChangeAccountCompoundingInput {
+	pub account_id:  i32,
+	pub compounding: Compounding,
+}
+
+#[derive(Clone)]
+pub struct ChangeAccountCompoundingResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	account: Option<Account>,
+}
+
+graphql_object!(ChangeAccountCompoundingResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field account() -> &Option<Account> {
+		&self.account
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: ChangeAccountCompoundingInput,
+) -> FieldResult<ChangeAccountCompoundingResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_admin(&conn, input.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result =
+		actions::accounts::change_compounding::call(&conn, input.account_id, input.compounding);
+
+	let response = match result {
+		Ok(account) => {
+			ChangeAccountCompoundingResponse {
+				success: true,
+				errors:  vec![],
+				account: Some(account),
+			}
+		},
+		Err(e) => {
+			ChangeAccountCompoundingResponse {
+				success: false,
+				errors:  failure_to_mutation_errors(e),
+				account: None,
+			}
+		},
+	};
+
+	Ok(response)
+}
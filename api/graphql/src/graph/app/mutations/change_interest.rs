@@ -53,7 +53,12 @@ pub fn call(
 	let yearly_interest = BigDecimal::from_f64(input.yearly_interest)
 		.ok_or(format_err!("Failed to convert yearly_interest"))?;
 
-	let result = actions::accounts::change_interest::call(&conn, input.account_id, yearly_interest);
+	let result = actions::accounts::change_interest::call(
+		&conn,
+		input.account_id,
+		yearly_interest,
+		&current_user,
+	);
 
 	let response = match result {
 		Ok(account) => {
@@ -0,0 +1,72 @@
+use crate::{
+	actions,
+	graph::AppContext,
+	models::{client::Client, posting_frequency::PostingFrequency, role::Role},
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use juniper::{Executor, FieldError, FieldResult};
+
+#[derive(Clone, GraphQLInputObject)]
+pub struct ChangeInterestPostingInput {
+	pub frequency: PostingFrequency,
+}
+
+pub struct ChangeInterestPostingResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	client:  Option<Client>,
+}
+
+graphql_object!(ChangeInterestPostingResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field client() -> &Option<Client> {
+		&self.client
+	}
+});
+
+// Changes how often interest is posted for the accounts of the current client
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: ChangeInterestPostingInput,
+) -> FieldResult<ChangeInterestPostingResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	if current_user.role != Role::Admin {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = actions::clients::change_interest_posting::call(
+		&conn,
+		current_user.client_id,
+		input.frequency,
+	);
+
+	let response = match result {
+		Ok(client) => {
+			ChangeInterestPostingResponse {
+				success: true,
+				errors:  vec![],
+				client:  Some(client),
+			}
+		},
+		Err(e) => {
+			ChangeInterestPostingResponse {
+				success: false,
+				errors:  failure_to_mutation_errors(e),
+				client:  None,
+			}
+		},
+	};
+
+	Ok(response)
+}
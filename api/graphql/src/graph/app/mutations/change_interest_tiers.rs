@@ -0,0 +1,95 @@
+use crate::{
+	actions::{self, accounts::calculate_interest::Tier},
+	graph::AppContext,
+	models::{account::Account, cents::Cents},
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use bigdecimal::{BigDecimal, FromPrimitive};
+use failure::Error;
+use juniper::{Executor, FieldError, FieldResult};
+
+#[derive(Clone, GraphQLInputObject)]
+pub struct InterestTierInput {
+	pub from_cents:      i32,
+	pub yearly_interest: f64,
+}
+
+// Replaces all the tiers, an empty list removes them
+#[derive(Clone, GraphQLInputObject)]
+pub struct ChangeAccountInterestTiersInput {
+	pub account_id: i32,
+	pub tiers:      Vec<InterestTierInput>,
+}
+
+#[derive(Clone)]
+pub struct ChangeAccountInterestTiersResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	account: Option<Account>,
+}
+
+graphql_object!(ChangeAccountInterestTiersResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field account() -> &Option<Account> {
+		&self.account
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: ChangeAccountInterestTiersInput,
+) -> FieldResult<ChangeAccountInterestTiersResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_admin(&conn, input.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let tiers = input
+		.tiers
+		.iter()
+		.map(|tier| {
+			let yearly_interest = BigDecimal::from_f64(tier.yearly_interest)
+				.ok_or(format_err!("Failed to convert yearly_interest"))?;
+
+			Ok(Tier {
+				from_balance:    Cents(tier.from_cents as i64),
+				yearly_interest: yearly_interest,
+			})
+		})
+		.collect::<Result<Vec<Tier>, Error>>()?;
+
+	let result = actions::accounts::change_interest_tiers::call(&conn, input.account_id, tiers)
+		.and_then(|_| Account::find(&conn, input.account_id).map_err(Error::from));
+
+	let response = match result {
+		Ok(account) => {
+			ChangeAccountInterestTiersResponse {
+				success: true,
+				errors:  vec![],
+				account: Some(account),
+			}
+		},
+		Err(e) => {
+			ChangeAccountInterestTiersResponse {
+				success: false,
+				errors:  failure_to_mutation_errors(e),
+				account: None,
+			}
+		},
+	};
+
+	Ok(response)
+}
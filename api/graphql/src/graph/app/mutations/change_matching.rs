@@ -0,0 +1,104 @@
+use crate::{
+	actions::{self, accounts::change_matching::Matching},
+	graph::AppContext,
+	models::{
+		account::Account,
+		cents::Cents,
+		matching_rule::{DepositSource, MatchPeriod},
+	},
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use bigdecimal::{BigDecimal, FromPrimitive};
+use failure::Error;
+use juniper::{Executor, FieldError, FieldResult};
+
+// e.g. 50 percent of direct deposits, up to 2000 cents a month.
+// Without cap_cents every qualifying deposit is matched in full.
+#[derive(Clone, GraphQLInputObject)]
+pub struct MatchingInput {
+	pub percentage: f64,
+	pub cap_cents:  Option<i32>,
+	pub period:     MatchPeriod,
+	pub sources:    Vec<DepositSource>,
+}
+
+// A null matching stops matching
+#[derive(Clone, GraphQLInputObject)]
+pub struct ChangeAccountMatchingInput {
+	pub account_id: i32,
+	pub matching:   Option<MatchingInput>,
+}
+
+#[derive(Clone)]
+pub struct ChangeAccountMatchingResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	account: Option<Account>,
+}
+
+graphql_object!(ChangeAccountMatchingResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field account() -> &Option<Account> {
+		&self.account
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: ChangeAccountMatchingInput,
+) -> FieldResult<ChangeAccountMatchingResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_admin(&conn, input.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let matching = match input.matching {
+		Some(matching) => {
+			let percentage = BigDecimal::from_f64(matching.percentage)
+				.ok_or(format_err!("Failed to convert percentage"))?;
+
+			Some(Matching {
+				percentage: percentage,
+				cap:        matching.cap_cents.map(|cents| Cents(cents as i64)),
+				period:     matching.period,
+				sources:    matching.sources,
+			})
+		},
+		None => None,
+	};
+
+	let result = actions::accounts::change_matching::call(&conn, input.account_id, matching)
+		.and_then(|_| Account::find(&conn, input.account_id).map_err(Error::from));
+
+	let response = match result {
+		Ok(account) => {
+			ChangeAccountMatchingResponse {
+				success: true,
+				errors:  vec![],
+				account: Some(account),
+			}
+		},
+		Err(e) => {
+			ChangeAccountMatchingResponse {
+				success: false,
+				errors:  failure_to_mutation_errors(e),
+				account: None,
+			}
+		},
+	};
+
+	Ok(response)
+}
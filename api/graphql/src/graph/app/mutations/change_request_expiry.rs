@@ -0,0 +1,69 @@
+use crate::{
+	actions,
+	graph::AppContext,
+	models::{client::Client, role::Role},
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use juniper::{Executor, FieldError, FieldResult};
+
+#[derive(Clone, GraphQLInputObject)]
+pub struct ChangeRequestExpiryInput {
+	pub days: i32,
+}
+
+pub struct ChangeRequestExpiryResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	client:  Option<Client>,
+}
+
+graphql_object!(ChangeRequestExpiryResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field client() -> &Option<Client> {
+		&self.client
+	}
+});
+
+// Changes how long requests of the current client stay pending before they expire
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: ChangeRequestExpiryInput,
+) -> FieldResult<ChangeRequestExpiryResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	if current_user.role != Role::Admin {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result =
+		actions::clients::change_request_expiry::call(&conn, current_user.client_id, input.days);
+
+	let response = match result {
+		Ok(client) => {
+			ChangeRequestExpiryResponse {
+				success: true,
+				errors:  vec![],
+				client:  Some(client),
+			}
+		},
+		Err(e) => {
+			ChangeRequestExpiryResponse {
+				success: false,
+				errors:  failure_to_mutation_errors(e),
+				client:  None,
+			}
+		},
+	};
+
+	Ok(response)
+}
@@ -0,0 +1,96 @@
+use crate::{
+	actions::{self, users::change_split_rules::Split},
+	graph::AppContext,
+	models::user::User,
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use bigdecimal::{BigDecimal, FromPrimitive};
+use failure::Error;
+use juniper::{Executor, FieldError, FieldResult};
+
+#[derive(Clone, GraphQLInputObject)]
+pub struct SplitInput {
+	pub account_id: i32,
+	pub percentage: f64,
+}
+
+// Replaces how the investor's deposits are split, an empty list stops splitting.
+// The percentages add up to 100.
+#[derive(Clone, GraphQLInputObject)]
+pub struct ChangeSplitRulesInput {
+	pub user_id: i32,
+	pub splits:  Vec<SplitInput>,
+}
+
+#[derive(Clone)]
+pub struct ChangeSplitRulesResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	user:    Option<User>,
+}
+
+graphql_object!(ChangeSplitRulesResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field user() -> &Option<User> {
+		&self.user
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: ChangeSplitRulesInput,
+) -> FieldResult<ChangeSplitRulesResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	let can = actions::users::authorise::can_admin(&conn, &current_user, input.user_id)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let splits = input
+		.splits
+		.iter()
+		.map(|split| {
+			let percentage = BigDecimal::from_f64(split.percentage)
+				.ok_or(format_err!("Failed to convert percentage"))?;
+
+			Ok(Split {
+				account_id: split.account_id,
+				percentage: percentage,
+			})
+		})
+		.collect::<Result<Vec<Split>, Error>>()?;
+
+	let result = actions::users::change_split_rules::call(&conn, input.user_id, splits)
+		.and_then(|_| User::find(&conn, input.user_id).map_err(Error::from));
+
+	let response = match result {
+		Ok(user) => {
+			ChangeSplitRulesResponse {
+				success: true,
+				errors:  vec![],
+				user:    Some(user),
+			}
+		},
+		Err(e) => {
+			ChangeSplitRulesResponse {
+				success: false,
+				errors:  failure_to_mutation_errors(e),
+				user:    None,
+			}
+		},
+	};
+
+	Ok(response)
+}
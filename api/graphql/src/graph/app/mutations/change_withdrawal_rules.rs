@@ -0,0 +1,94 @@
+use crate::{
+	actions::{self, accounts::change_withdrawal_rules::Rule},
+	graph::AppContext,
+	models::{account::Account, cents::Cents, withdrawal_rule::WithdrawalRuleKind},
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use failure::Error;
+use juniper::{Executor, FieldError, FieldResult};
+
+// `cents` for every kind but COOLING_OFF, which takes `days`
+#[derive(Clone, GraphQLInputObject)]
+pub struct WithdrawalRuleInput {
+	pub kind:  WithdrawalRuleKind,
+	pub cents: Option<i32>,
+	pub days:  Option<i32>,
+}
+
+// Replaces all the rules, an empty list removes them
+#[derive(Clone, GraphQLInputObject)]
+pub struct ChangeAccountWithdrawalRulesInput {
+	pub account_id: i32,
+	pub rules:      Vec<WithdrawalRuleInput>,
+}
+
+#[derive(Clone)]
+pub struct ChangeAccountWithdrawalRulesResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	account: Option<Account>,
+}
+
+graphql_object!(ChangeAccountWithdrawalRulesResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field account() -> &Option<Account> {
+		&self.account
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: ChangeAccountWithdrawalRulesInput,
+) -> FieldResult<ChangeAccountWithdrawalRulesResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_admin(&conn, input.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let rules = input
+		.rules
+		.iter()
+		.map(|rule| {
+			Rule {
+				kind:   rule.kind,
+				amount: rule.cents.map(|cents| Cents(cents as i64)),
+				days:   rule.days,
+			}
+		})
+		.collect();
+
+	let result = actions::accounts::change_withdrawal_rules::call(&conn, input.account_id, rules)
+		.and_then(|_| Account::find(&conn, input.account_id).map_err(Error::from));
+
+	let response = match result {
+		Ok(account) => {
+			ChangeAccountWithdrawalRulesResponse {
+				success: true,
+				errors:  vec![],
+				account: Some(account),
+			}
+		},
+		Err(e) => {
+			ChangeAccountWithdrawalRulesResponse {
+				success: false,
+				errors:  failure_to_mutation_errors(e),
+				account: None,
+			}
+		},
+	};
+
+	Ok(response)
+}
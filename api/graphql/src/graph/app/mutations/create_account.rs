@@ -0,0 +1,115 @@
+use crate::{
+	actions,
+	graph::AppContext,
+	models::{
+		account::{Account, AccountAttrs, Compounding, Kind, State, DEFAULT_YEARLY_INTEREST},
+		role::Role,
+		user::User,
+	},
+	utils::{
+		dates,
+		mutations::{failure_to_mutation_errors, MutationError},
+	},
+};
+use bigdecimal::{BigDecimal, FromPrimitive};
+use juniper::{Executor, FieldError, FieldResult};
+
+// matures_at is POSIX time in seconds, required for term deposits
+#[derive(Clone, GraphQLInputObject)]
+pub struct CreateAccountInput {
+	pub user_id:         i32,
+	pub name:            String,
+	pub kind:            Kind,
+	pub yearly_interest: Option<f64>,
+	pub compounding:     Option<Compounding>,
+	pub matures_at:      Option<f64>,
+}
+
+#[derive(Clone)]
+pub struct CreateAccountResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	account: Option<Account>,
+}
+
+graphql_object!(CreateAccountResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field account() -> &Option<Account> {
+		&self.account
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: CreateAccountInput,
+) -> FieldResult<CreateAccountResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	let can = actions::users::authorise::can_admin(&conn, &current_user, input.user_id)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let user = User::find(&conn, input.user_id)?;
+
+	if user.role != Role::Investor {
+		return Err(FieldError::from("Only investors can have accounts"));
+	}
+
+	let yearly_interest = match input.yearly_interest {
+		Some(yearly_interest) => {
+			BigDecimal::from_f64(yearly_interest)
+				.ok_or(format_err!("Failed to convert yearly_interest"))?
+		},
+		None => BigDecimal::from_u8(DEFAULT_YEARLY_INTEREST).unwrap(),
+	};
+
+	let matures_at = match input.matures_at {
+		Some(seconds) => {
+			Some(dates::from_seconds(seconds).ok_or(FieldError::from("Invalid date"))?)
+		},
+		None => None,
+	};
+
+	let attrs = AccountAttrs {
+		user_id:         user.id,
+		name:            input.name,
+		yearly_interest: yearly_interest,
+		kind:            input.kind,
+		state:           State::Active,
+		compounding:     input.compounding.unwrap_or(Compounding::Daily),
+		matures_at:      matures_at,
+	};
+
+	let result = actions::accounts::create::open(&conn, attrs);
+
+	let response = match result {
+		Ok(account) => {
+			CreateAccountResponse {
+				success: true,
+				errors:  vec![],
+				account: Some(account),
+			}
+		},
+		Err(e) => {
+			CreateAccountResponse {
+				success: false,
+				errors:  failure_to_mutation_errors(e),
+				account: None,
+			}
+		},
+	};
+
+	Ok(response)
+}
@@ -0,0 +1,70 @@
+pub use crate::actions::goals::create::GoalInput;
+use crate::{
+	actions,
+	graph::AppContext,
+	models::goal::Goal,
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use failure::Error;
+use juniper::{Executor, FieldError, FieldResult};
+
+// Shared by the goal mutations
+#[derive(Clone)]
+pub struct GoalResponse {
+	success: bool,
+	errors:  Vec<MutationError>,
+	goal:    Option<Goal>,
+}
+
+graphql_object!(GoalResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field goal() -> &Option<Goal> {
+		&self.goal
+	}
+});
+
+impl GoalResponse {
+	pub fn from_result(result: Result<Goal, Error>) -> Self {
+		match result {
+			Ok(goal) => {
+				GoalResponse {
+					success: true,
+					errors:  vec![],
+					goal:    Some(goal),
+				}
+			},
+			Err(e) => {
+				GoalResponse {
+					success: false,
+					errors:  failure_to_mutation_errors(e),
+					goal:    None,
+				}
+			},
+		}
+	}
+}
+
+// The account holder sets their own goals, the admins can too
+pub fn call(executor: &Executor<AppContext>, input: GoalInput) -> FieldResult<GoalResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_access(&conn, input.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = actions::goals::create::call(&conn, input);
+
+	Ok(GoalResponse::from_result(result))
+}
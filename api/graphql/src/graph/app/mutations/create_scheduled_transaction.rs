@@ -0,0 +1,72 @@
+pub use crate::actions::scheduled_transactions::create::ScheduledTransactionInput;
+use crate::{
+	actions,
+	graph::AppContext,
+	models::scheduled_transaction::ScheduledTransaction,
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use failure::Error;
+use juniper::{Executor, FieldError, FieldResult};
+
+// Shared by the scheduled transaction mutations
+#[derive(Clone)]
+pub struct ScheduledTransactionResponse {
+	success:               bool,
+	errors:                Vec<MutationError>,
+	scheduled_transaction: Option<ScheduledTransaction>,
+}
+
+graphql_object!(ScheduledTransactionResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field scheduled_transaction() -> &Option<ScheduledTransaction> {
+		&self.scheduled_transaction
+	}
+});
+
+impl ScheduledTransactionResponse {
+	pub fn from_result(result: Result<ScheduledTransaction, Error>) -> Self {
+		match result {
+			Ok(scheduled_transaction) => {
+				ScheduledTransactionResponse {
+					success:               true,
+					errors:                vec![],
+					scheduled_transaction: Some(scheduled_transaction),
+				}
+			},
+			Err(e) => {
+				ScheduledTransactionResponse {
+					success:               false,
+					errors:                failure_to_mutation_errors(e),
+					scheduled_transaction: None,
+				}
+			},
+		}
+	}
+}
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: ScheduledTransactionInput,
+) -> FieldResult<ScheduledTransactionResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_admin(&conn, input.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = actions::scheduled_transactions::create::call(&conn, input);
+
+	Ok(ScheduledTransactionResponse::from_result(result))
+}
@@ -0,0 +1,22 @@
+use super::create_goal::GoalResponse;
+use crate::{actions, graph::AppContext, models::goal::Goal};
+use juniper::{Executor, FieldError, FieldResult};
+
+pub fn call(executor: &Executor<AppContext>, goal_id: i32) -> FieldResult<GoalResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	let goal = Goal::find(&conn, goal_id)?;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_access(&conn, goal.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = actions::goals::delete::call(&conn, goal_id);
+
+	Ok(GoalResponse::from_result(result))
+}
@@ -0,0 +1,26 @@
+use super::create_scheduled_transaction::ScheduledTransactionResponse;
+use crate::{actions, graph::AppContext, models::scheduled_transaction::ScheduledTransaction};
+use juniper::{Executor, FieldError, FieldResult};
+
+// Stops the rule, the runs so far are kept
+pub fn call(
+	executor: &Executor<AppContext>,
+	scheduled_transaction_id: i32,
+) -> FieldResult<ScheduledTransactionResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	let rule = ScheduledTransaction::find(&conn, scheduled_transaction_id)?;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_admin(&conn, rule.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = actions::scheduled_transactions::archive::call(&conn, scheduled_transaction_id);
+
+	Ok(ScheduledTransactionResponse::from_result(result))
+}
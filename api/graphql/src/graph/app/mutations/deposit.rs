@@ -4,7 +4,7 @@ pub use crate::actions::transactions::deposit::{self, DepositInput};
 use crate::{
 	actions::accounts::authorise,
 	graph::AppContext,
-	models::transaction::Transaction,
+	models::{deposit_source::DepositSource, transaction::Transaction},
 	utils::mutations::{failure_to_mutation_errors, MutationError},
 };
 
@@ -41,7 +41,7 @@ pub fn call(executor: &Executor<AppContext>, input: DepositInput) -> FieldResult
 		return Err(FieldError::from("Unauthorised"));
 	}
 
-	let result = deposit::call(&conn, input);
+	let result = deposit::call(&conn, input, DepositSource::Direct);
 
 	let response = match result {
 		Ok(transaction) => {
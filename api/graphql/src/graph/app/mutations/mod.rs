@@ -1,9 +1,30 @@
+pub mod archive_account;
 pub mod archive_user;
+pub mod cancel_transaction_request;
+pub mod change_compounding;
 pub mod change_interest;
+pub mod change_interest_posting;
+pub mod change_interest_tiers;
+pub mod change_matching;
+pub mod change_request_expiry;
+pub mod change_split_rules;
+pub mod change_withdrawal_rules;
+pub mod create_account;
+pub mod create_goal;
+pub mod create_scheduled_transaction;
 pub mod create_user;
+pub mod delete_goal;
+pub mod delete_scheduled_transaction;
 pub mod deposit;
 pub mod invite_admin;
+pub mod repair_ledger;
+pub mod request_deposit;
 pub mod request_withdrawal;
 pub mod resolve_transaction_request;
+pub mod reverse_transaction;
+pub mod transfer;
+pub mod unarchive_account;
 pub mod unarchive_user;
+pub mod update_goal;
+pub mod update_scheduled_transaction;
 pub mod withdraw;
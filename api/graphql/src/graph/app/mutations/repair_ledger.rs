@@ -0,0 +1,77 @@
+use crate::{
+	actions,
+	graph::AppContext,
+	models::transaction::Transaction,
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+use juniper::{Executor, FieldError, FieldResult};
+
+// Nothing is changed unless confirm is true
+#[derive(Clone, GraphQLInputObject)]
+pub struct RepairLedgerInput {
+	pub account_id: i32,
+	pub confirm:    bool,
+}
+
+#[derive(Clone)]
+pub struct RepairLedgerResponse {
+	success:      bool,
+	errors:       Vec<MutationError>,
+	transactions: Vec<Transaction>,
+}
+
+graphql_object!(RepairLedgerResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	// The transactions whose balance was rebuilt
+	field transactions() -> &Vec<Transaction> {
+		&self.transactions
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: RepairLedgerInput,
+) -> FieldResult<RepairLedgerResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_admin(&conn, input.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = if input.confirm {
+		actions::ledger::repair::call(&conn, input.account_id)
+	} else {
+		Err(format_err!("Please confirm the repair"))
+	};
+
+	let response = match result {
+		Ok(transactions) => {
+			RepairLedgerResponse {
+				success:      true,
+				errors:       vec![],
+				transactions: transactions,
+			}
+		},
+		Err(e) => {
+			RepairLedgerResponse {
+				success:      false,
+				errors:       failure_to_mutation_errors(e),
+				transactions: vec![],
+			}
+		},
+	};
+
+	Ok(response)
+}
@@ -0,0 +1,84 @@
+use juniper::{Executor, FieldError, FieldResult};
+
+use crate::{
+	actions::{accounts::authorise, transactions::reverse},
+	graph::AppContext,
+	models::transaction::Transaction,
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+
+#[derive(Clone)]
+pub struct ReverseTransactionResponse {
+	success:     bool,
+	errors:      Vec<MutationError>,
+	reversals:   Vec<Transaction>,
+	adjustments: Vec<Transaction>,
+}
+
+graphql_object!(ReverseTransactionResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field reversals() -> &Vec<Transaction> {
+		&self.reversals
+	}
+
+	// Interest corrections
+	field adjustments() -> &Vec<Transaction> {
+		&self.adjustments
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	transaction_id: i32,
+) -> FieldResult<ReverseTransactionResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	let transaction = Transaction::find(&conn, transaction_id)?;
+
+	// Authorise every account the transaction touched
+	let mut account_ids = vec![transaction.account_id];
+
+	if let Some(linked_id) = transaction.linked_transaction_id {
+		account_ids.push(Transaction::find(&conn, linked_id)?.account_id);
+	}
+
+	for account_id in account_ids {
+		let can = authorise::can_admin(&conn, account_id, &current_user)?;
+
+		if can == false {
+			return Err(FieldError::from("Unauthorised"));
+		}
+	}
+
+	let result = reverse::call(&conn, transaction_id);
+
+	let response = match result {
+		Ok(reversal) => {
+			ReverseTransactionResponse {
+				success:     true,
+				errors:      vec![],
+				reversals:   reversal.reversals,
+				adjustments: reversal.adjustments,
+			}
+		},
+		Err(e) => {
+			ReverseTransactionResponse {
+				success:     false,
+				errors:      failure_to_mutation_errors(e),
+				reversals:   vec![],
+				adjustments: vec![],
+			}
+		},
+	};
+
+	Ok(response)
+}
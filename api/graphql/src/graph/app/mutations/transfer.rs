@@ -0,0 +1,75 @@
+use juniper::{Executor, FieldError, FieldResult};
+
+pub use crate::actions::transactions::transfer::{self, TransferInput};
+use crate::{
+	actions::accounts::authorise,
+	graph::AppContext,
+	models::transaction::Transaction,
+	utils::mutations::{failure_to_mutation_errors, MutationError},
+};
+
+#[derive(Clone)]
+pub struct TransferResponse {
+	success:          bool,
+	errors:           Vec<MutationError>,
+	from_transaction: Option<Transaction>,
+	to_transaction:   Option<Transaction>,
+}
+
+graphql_object!(TransferResponse: AppContext |&self| {
+	field success() -> bool {
+		self.success
+	}
+
+	field errors() -> &Vec<MutationError> {
+		&self.errors
+	}
+
+	field from_transaction() -> &Option<Transaction> {
+		&self.from_transaction
+	}
+
+	field to_transaction() -> &Option<Transaction> {
+		&self.to_transaction
+	}
+});
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: TransferInput,
+) -> FieldResult<TransferResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	// Authorise both sides of the transfer
+	let can_access_from = authorise::can_access(&conn, input.from_account_id, &current_user)?;
+	let can_access_to = authorise::can_access(&conn, input.to_account_id, &current_user)?;
+
+	if can_access_from == false || can_access_to == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = transfer::call(&conn, input);
+
+	let response = match result {
+		Ok(transfer) => {
+			TransferResponse {
+				success:          true,
+				errors:           vec![],
+				from_transaction: Some(transfer.from),
+				to_transaction:   Some(transfer.to),
+			}
+		},
+		Err(e) => {
+			TransferResponse {
+				success:          false,
+				errors:           failure_to_mutation_errors(e),
+				from_transaction: None,
+				to_transaction:   None,
+			}
+		},
+	};
+
+	Ok(response)
+}
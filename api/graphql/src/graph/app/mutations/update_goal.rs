@@ -0,0 +1,23 @@
+use super::create_goal::GoalResponse;
+pub use crate::actions::goals::update::UpdateGoalInput;
+use crate::{actions, graph::AppContext, models::goal::Goal};
+use juniper::{Executor, FieldError, FieldResult};
+
+pub fn call(executor: &Executor<AppContext>, input: UpdateGoalInput) -> FieldResult<GoalResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	let goal = Goal::find(&conn, input.goal_id)?;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_access(&conn, goal.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = actions::goals::update::call(&conn, input);
+
+	Ok(GoalResponse::from_result(result))
+}
@@ -0,0 +1,26 @@
+use super::create_scheduled_transaction::ScheduledTransactionResponse;
+pub use crate::actions::scheduled_transactions::update::UpdateScheduledTransactionInput;
+use crate::{actions, graph::AppContext, models::scheduled_transaction::ScheduledTransaction};
+use juniper::{Executor, FieldError, FieldResult};
+
+pub fn call(
+	executor: &Executor<AppContext>,
+	input: UpdateScheduledTransactionInput,
+) -> FieldResult<ScheduledTransactionResponse> {
+	let ctx = executor.context();
+	let conn = &ctx.conn;
+	let current_user = &ctx.user;
+
+	let rule = ScheduledTransaction::find(&conn, input.scheduled_transaction_id)?;
+
+	// Authorise
+	let can = actions::accounts::authorise::can_admin(&conn, rule.account_id, &current_user)?;
+
+	if can == false {
+		return Err(FieldError::from("Unauthorised"));
+	}
+
+	let result = actions::scheduled_transactions::update::call(&conn, input);
+
+	Ok(ScheduledTransactionResponse::from_result(result))
+}
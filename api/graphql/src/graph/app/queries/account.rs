@@ -1,14 +1,21 @@
 use crate::{
-	actions,
+	actions::{self, accounts::project_balance::ProjectionPoint},
 	graph::AppContext,
 	models::{
-		account::{Account, Kind, State},
-		transaction::{Transaction, TransactionKind},
+		account::{Account, Compounding, Kind, State},
+		goal::Goal,
+		interest_rate::InterestRate,
+		interest_tier::InterestTier,
+		matching_rule::MatchingRule,
+		scheduled_transaction::ScheduledTransaction,
+		transaction::Transaction,
 		user::User,
+		withdrawal_rule::WithdrawalRule,
 	},
+	utils::dates,
 };
 use bigdecimal::{BigDecimal, ToPrimitive};
-use chrono::{prelude::*, NaiveDateTime};
+use chrono::{NaiveDateTime, Utc};
 use juniper::{FieldError, FieldResult};
 
 graphql_object!(Account: AppContext |&self| {
@@ -40,6 +47,10 @@ graphql_object!(Account: AppContext |&self| {
 		self.state
 	}
 
+	field matures_at() -> Option<NaiveDateTime> {
+		self.matures_at
+	}
+
 	field balance_in_cents(&executor) -> f64 {
 		let ctx = &executor.context();
 		let conn = &ctx.conn;
@@ -52,55 +63,115 @@ graphql_object!(Account: AppContext |&self| {
 		BigDecimal::to_f64(&self.yearly_interest).unwrap()
 	}
 
+	// Lowest first, yearly_interest applies below the first tier
+	field interest_tiers(&executor) -> FieldResult<Vec<InterestTier>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		InterestTier::find_by_account_id(&conn, self.id)
+			.map_err(|e| FieldError::from(e))
+	}
+
+	// The blended rate the current balance earns with the tiers
+	field effective_yearly_interest(&executor) -> FieldResult<f64> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		let rate = actions::accounts::get_effective_interest::call(&conn, &self)?;
+
+		BigDecimal::to_f64(&rate)
+			.ok_or(FieldError::from("Failed to convert yearly_interest"))
+	}
+
+	// Limits on withdrawing, oldest first
+	field withdrawal_rules(&executor) -> FieldResult<Vec<WithdrawalRule>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		WithdrawalRule::find_by_account_id(&conn, self.id)
+			.map_err(|e| FieldError::from(e))
+	}
+
+	// How deposits are matched, if they are
+	field matching(&executor) -> FieldResult<Option<MatchingRule>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		MatchingRule::find_by_account_id(&conn, self.id)
+			.map_err(|e| FieldError::from(e))
+	}
+
+	// What the account holder is saving for, oldest first
+	field goals(&executor) -> FieldResult<Vec<Goal>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		Goal::find_by_account_id(&conn, self.id)
+			.map_err(|e| FieldError::from(e))
+	}
+
+	// The balance at the end of each of the next `months`, saving `weekly_deposit` cents a week
+	// at the account's current rate, split into principal and interest
+	field projection(&executor, weekly_deposit: i32, months: i32) -> FieldResult<Vec<ProjectionPoint>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		let now = Utc::now().naive_utc();
+
+		actions::accounts::project_balance::call(&conn, &self, weekly_deposit, months, now)
+			.map_err(|e| FieldError::from(e))
+	}
+
+	field compounding() -> Compounding {
+		self.compounding
+	}
+
+	// Oldest first
+	field interest_rate_history(&executor) -> FieldResult<Vec<InterestRate>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		InterestRate::find_by_account_id(&conn, self.id)
+			.map_err(|e| FieldError::from(e))
+	}
+
+	// Allowances and other deposits that run by themselves
+	field scheduled_transactions(&executor) -> FieldResult<Vec<ScheduledTransaction>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		ScheduledTransaction::find_by_account_id(&conn, self.id)
+			.map_err(|e| FieldError::from(e))
+	}
+
 	// since POSIX is in milliseconds
 	// posix time should be kept in floats, because i32 will reset in the year 2038
-	field transactions(&executor, since: f64) -> Vec<Transaction> {
+	// category is a name, only transactions in it are returned
+	field transactions(&executor, since: f64, category: Option<String>) -> Vec<Transaction> {
 		let ctx = &executor.context();
 		let conn = &ctx.conn;
 
 		// let since_in_secs = since / 1000.0;
-		let since_dt = NaiveDateTime::from_timestamp(since as i64, 0);
+		// Nothing is recorded at a date that can't exist
+		let since_dt = match dates::from_seconds(since) {
+			Some(since_dt) => since_dt,
+			None => return vec![],
+		};
 
 		// We can access via account or accounts queries
 		// We assume that authorisation already happened in either of those
-		let mut transactions = Transaction::find_by_account_id(&conn, self.id, since_dt)
-			.unwrap_or(vec![]);
-
-		// Add a dummy transaction to reflect the latest interest
-		let maybe_last = transactions.last().cloned();
-
-		match maybe_last {
-			None => (),
-			Some(last) => {
-				let current_balance = last.balance;
-
-				let now = Utc::now().naive_utc();
-
-				let interest_result = actions::accounts::calculate_interest::call(
-					current_balance,
-					&self.yearly_interest,
-					last.created_at,
-					now,
-				);
-
-				match interest_result {
-					Ok(interest) => {
-						let interest_transaction = Transaction {
-							id: 0,
-							created_at: now,
-							account_id: self.id,
-							kind: TransactionKind::Interest,
-							amount: interest,
-							balance: current_balance + interest,
-						};
-
-						transactions.push(interest_transaction);
-					},
-					Err(_) => ()
-				};
-			}
+		// Interest is posted by the scheduler, so these are all real transactions
+		let transactions = match category {
+			Some(name) => match actions::transactions::describe::find_category(&conn, self.id, &name) {
+				Ok(Some(category)) => {
+					Transaction::find_by_account_id_and_category_id(&conn, self.id, since_dt, category.id)
+				},
+				// Nothing has been put in an unknown category
+				_ => Ok(vec![]),
+			},
+			None => Transaction::find_by_account_id(&conn, self.id, since_dt),
 		};
 
-		transactions
+		transactions.unwrap_or(vec![])
 	}
 });
@@ -0,0 +1,59 @@
+use crate::{
+	actions,
+	graph::AppContext,
+	models::{cents::Cents, goal::Goal},
+};
+use chrono::{NaiveDateTime, Utc};
+use juniper::{FieldError, FieldResult};
+
+graphql_object!(Goal: AppContext |&self| {
+	field id() -> i32 {
+		self.id
+	}
+
+	field account_id() -> i32 {
+		self.account_id
+	}
+
+	field name() -> &str {
+		self.name.as_str()
+	}
+
+	field target_in_cents() -> f64 {
+		let Cents(cents) = self.target;
+		cents as f64
+	}
+
+	field target_date() -> Option<NaiveDateTime> {
+		self.target_date
+	}
+
+	field image_url() -> &Option<String> {
+		&self.image_url
+	}
+
+	field reached_at() -> Option<NaiveDateTime> {
+		self.reached_at
+	}
+
+	// Percent of the target saved so far, at most 100
+	field progress(&executor) -> FieldResult<f64> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		actions::goals::project::progress(&conn, &self)
+			.map_err(|e| FieldError::from(e))
+	}
+
+	// When the goal is expected to be reached at the recent pace of deposits and the account's interest.
+	// Empty when it isn't expected within 10 years.
+	field projected_completion_at(&executor) -> FieldResult<Option<NaiveDateTime>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		let now = Utc::now().naive_utc();
+
+		actions::goals::project::completes_at(&conn, &self, now)
+			.map_err(|e| FieldError::from(e))
+	}
+});
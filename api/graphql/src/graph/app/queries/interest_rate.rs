@@ -0,0 +1,43 @@
+use crate::{
+	graph::AppContext,
+	models::{interest_rate::InterestRate, user::User},
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::NaiveDateTime;
+use juniper::{FieldError, FieldResult};
+
+graphql_object!(InterestRate: AppContext |&self| {
+	field id() -> i32 {
+		self.id
+	}
+
+	field created_at() -> NaiveDateTime {
+		self.created_at
+	}
+
+	field effective_from() -> NaiveDateTime {
+		self.effective_from
+	}
+
+	field changed_by(&executor) -> FieldResult<Option<User>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		match self.changed_by_user_id {
+			Some(user_id) => {
+				User::find(&conn, user_id)
+					.map(Some)
+					.map_err(|e| FieldError::from(e))
+			},
+			None => Ok(None),
+		}
+	}
+
+	field old_yearly_interest() -> Option<f64> {
+		self.old_yearly_interest.as_ref().and_then(BigDecimal::to_f64)
+	}
+
+	field new_yearly_interest() -> f64 {
+		BigDecimal::to_f64(&self.new_yearly_interest).unwrap()
+	}
+});
@@ -0,0 +1,20 @@
+use crate::{
+	graph::AppContext,
+	models::{cents::Cents, interest_tier::InterestTier},
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+
+graphql_object!(InterestTier: AppContext |&self| {
+	field id() -> i32 {
+		self.id
+	}
+
+	field from_cents() -> f64 {
+		let Cents(cents) = self.from_balance;
+		cents as f64
+	}
+
+	field yearly_interest() -> f64 {
+		BigDecimal::to_f64(&self.yearly_interest).unwrap()
+	}
+});
@@ -0,0 +1,30 @@
+use crate::{
+	graph::AppContext,
+	models::{
+		cents::Cents,
+		matching_rule::{DepositSource, MatchPeriod, MatchingRule},
+	},
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+
+graphql_object!(MatchingRule: AppContext |&self| {
+	field id() -> i32 {
+		self.id
+	}
+
+	field percentage() -> f64 {
+		BigDecimal::to_f64(&self.percentage).unwrap()
+	}
+
+	field cap_cents() -> Option<f64> {
+		self.cap.map(|Cents(cents)| cents as f64)
+	}
+
+	field period() -> MatchPeriod {
+		self.period
+	}
+
+	field sources() -> &Vec<DepositSource> {
+		&self.sources
+	}
+});
@@ -1,4 +1,11 @@
 pub mod account;
+pub mod goal;
+pub mod interest_rate;
+pub mod interest_tier;
+pub mod matching_rule;
+pub mod scheduled_transaction;
+pub mod split_rule;
 pub mod transaction;
 pub mod transaction_request;
 pub mod user;
+pub mod withdrawal_rule;
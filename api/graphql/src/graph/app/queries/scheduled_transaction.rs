@@ -0,0 +1,75 @@
+use crate::{
+	graph::AppContext,
+	models::{
+		cents::Cents, schedule_frequency::ScheduleFrequency,
+		scheduled_transaction::ScheduledTransaction,
+		scheduled_transaction_run::ScheduledTransactionRun,
+	},
+};
+use chrono::NaiveDateTime;
+use juniper::{FieldError, FieldResult};
+
+graphql_object!(ScheduledTransaction: AppContext |&self| {
+	field id() -> i32 {
+		self.id
+	}
+
+	field account_id() -> i32 {
+		self.account_id
+	}
+
+	field amount_in_cents() -> f64 {
+		let Cents(cents) = self.amount;
+		cents as f64
+	}
+
+	field frequency() -> ScheduleFrequency {
+		self.frequency
+	}
+
+	field day_of_month() -> Option<i32> {
+		self.day_of_month
+	}
+
+	field starts_at() -> NaiveDateTime {
+		self.starts_at
+	}
+
+	// Empty once a one-off has run
+	field next_run_at() -> Option<NaiveDateTime> {
+		self.next_run_at
+	}
+
+	// Latest first
+	field runs(&executor) -> FieldResult<Vec<ScheduledTransactionRun>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		ScheduledTransactionRun::find_by_scheduled_transaction_id(&conn, self.id)
+			.map_err(|e| FieldError::from(e))
+	}
+});
+
+graphql_object!(ScheduledTransactionRun: AppContext |&self| {
+	field id() -> i32 {
+		self.id
+	}
+
+	field created_at() -> NaiveDateTime {
+		self.created_at
+	}
+
+	field run_for() -> NaiveDateTime {
+		self.run_for
+	}
+
+	// The deposit made
+	field transaction_id() -> Option<i32> {
+		self.transaction_id
+	}
+
+	// Why the deposit failed
+	field error() -> &Option<String> {
+		&self.error
+	}
+});
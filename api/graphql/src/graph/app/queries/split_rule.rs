@@ -0,0 +1,28 @@
+use crate::{
+	graph::AppContext,
+	models::{account::Account, split_rule::SplitRule},
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use juniper::{FieldError, FieldResult};
+
+graphql_object!(SplitRule: AppContext |&self| {
+	field id() -> i32 {
+		self.id
+	}
+
+	field account_id() -> i32 {
+		self.account_id
+	}
+
+	field account(&executor) -> FieldResult<Account> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		Account::find(&conn, self.account_id)
+			.map_err(|e| FieldError::from(e))
+	}
+
+	field percentage() -> f64 {
+		BigDecimal::to_f64(&self.percentage).unwrap()
+	}
+});
@@ -1,11 +1,13 @@
 use crate::{
 	graph::AppContext,
 	models::{
+		category::Category,
 		cents::Cents,
 		transaction::{Transaction, TransactionKind},
 	},
 };
 use chrono::NaiveDateTime;
+use juniper::{FieldError, FieldResult};
 
 graphql_object!(Transaction: AppContext |&self| {
 	field id() -> i32 {
@@ -33,4 +35,43 @@ graphql_object!(Transaction: AppContext |&self| {
 		let Cents(cents) = self.balance;
 		cents as f64
 	}
+
+	// The other side of a transfer, or what a reversal undid
+	field linked_transaction_id() -> Option<i32> {
+		self.linked_transaction_id
+	}
+
+	field memo() -> Option<&str> {
+		self.memo.as_ref().map(String::as_str)
+	}
+
+	field category(&executor) -> FieldResult<Option<Category>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		match self.category_id {
+			Some(category_id) => Category::find(&conn, category_id)
+				.map(Some)
+				.map_err(|e| FieldError::from(e)),
+			None => Ok(None),
+		}
+	}
+
+	field reversed(&executor) -> FieldResult<bool> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		Transaction::find_reversal(&conn, self.id)
+			.map(|reversal| reversal.is_some())
+			.map_err(|e| FieldError::from(e))
+	}
+
+	// Recorded together with this one, e.g. the parts of a split deposit, itself included
+	field grouped(&executor) -> FieldResult<Vec<Transaction>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		Transaction::find_grouped(&conn, self.id)
+			.map_err(|e| FieldError::from(e))
+	}
 });
@@ -1,8 +1,8 @@
 use crate::{
 	graph::AppContext,
 	models::{
-		account::Account, cents::Cents, schema as db, transaction_kind::TransactionKind,
-		transaction_request::TransactionRequest,
+		account::Account, category::Category, cents::Cents, schema as db, transaction::Transaction,
+		transaction_kind::TransactionKind, transaction_request::TransactionRequest,
 		transaction_request_state::TransactionRequestState,
 	},
 };
@@ -44,4 +44,33 @@ graphql_object!(TransactionRequest: AppContext |&self| {
 	field state()-> TransactionRequestState {
 		self.state
 	}
+
+	// The deposit or withdrawal made on approval
+	field transaction(&executor) -> FieldResult<Option<Transaction>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		match self.transaction_id {
+			Some(transaction_id) => Transaction::find(&conn, transaction_id)
+				.map(Some)
+				.map_err(|e| FieldError::from(e)),
+			None => Ok(None),
+		}
+	}
+
+	field memo() -> Option<&str> {
+		self.memo.as_ref().map(String::as_str)
+	}
+
+	field category(&executor) -> FieldResult<Option<Category>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		match self.category_id {
+			Some(category_id) => Category::find(&conn, category_id)
+				.map(Some)
+				.map_err(|e| FieldError::from(e)),
+			None => Ok(None),
+		}
+	}
 });
@@ -1,6 +1,6 @@
 use crate::{
 	graph::AppContext,
-	models::{account::Account, schema as db, user::User},
+	models::{account::Account, schema as db, split_rule::SplitRule, user::User},
 };
 use diesel::prelude::*;
 use juniper::{FieldError, FieldResult};
@@ -40,4 +40,13 @@ graphql_object!(User: AppContext |&self| {
 			.load(&*conn)
 			.map_err(|e| FieldError::from(e))
 	}
+
+	// How deposits are shared across the accounts, empty when they aren't
+	field split_rules(&executor) -> FieldResult<Vec<SplitRule>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		SplitRule::find_by_user_id(&conn, self.id)
+			.map_err(|e| FieldError::from(e))
+	}
 });
@@ -0,0 +1,25 @@
+use crate::{
+	graph::AppContext,
+	models::{
+		cents::Cents,
+		withdrawal_rule::{WithdrawalRule, WithdrawalRuleKind},
+	},
+};
+
+graphql_object!(WithdrawalRule: AppContext |&self| {
+	field id() -> i32 {
+		self.id
+	}
+
+	field kind() -> WithdrawalRuleKind {
+		self.kind
+	}
+
+	field cents() -> Option<f64> {
+		self.amount.map(|Cents(cents)| cents as f64)
+	}
+
+	field days() -> Option<i32> {
+		self.days
+	}
+});
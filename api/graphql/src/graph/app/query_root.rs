@@ -1,8 +1,9 @@
 use crate::{
-	actions,
+	actions::{self, ledger::verify::LedgerBreak},
 	graph::AppContext,
 	models::{
-		account::Account, client::Client, role::Role, schema as db,
+		account::Account, category::Category, cents::Cents, client::Client, posting::Posting, role::Role,
+		schema as db, transaction_kind::TransactionKind,
 		transaction_request::TransactionRequest,
 		transaction_request_state::TransactionRequestState, user::User,
 	},
@@ -67,6 +68,14 @@ struct Admin {
 
 graphql_object!(Admin: AppContext |&self| {
 
+	field client(&executor) -> FieldResult<Client> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		Client::find(&conn, ctx.user.client_id)
+			.map_err(|e| FieldError::from(e))
+	}
+
 	field investors(&executor) -> FieldResult<Vec<User>> {
 		let ctx = &executor.context();
 		let client_id = ctx.user.client_id;
@@ -119,6 +128,48 @@ graphql_object!(Admin: AppContext |&self| {
 			.map_err(|e| FieldError::from(e))
 	}
 
+	// Everything the family bank has paid into the accounts, less withdrawals
+	field family_bank_paid_in_cents(&executor) -> FieldResult<f64> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		let Cents(cents) = Posting::family_bank_balance_by_client_id(&conn, ctx.user.client_id, None)?;
+
+		Ok(-cents as f64)
+	}
+
+	// Total interest the family bank has paid out
+	field interest_paid_in_cents(&executor) -> FieldResult<f64> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		let kind = Some(TransactionKind::Interest);
+
+		let Cents(cents) = Posting::family_bank_balance_by_client_id(&conn, ctx.user.client_id, kind)?;
+
+		Ok(-cents as f64)
+	}
+
+	// Transactions of the client whose running balance doesn't add up
+	field ledger_breaks(&executor) -> FieldResult<Vec<LedgerBreak>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		let client = Client::find(&conn, ctx.user.client_id)?;
+
+		actions::ledger::verify::for_client(&conn, &client)
+			.map_err(|e| FieldError::from(e))
+	}
+
+	// Categories the family has used for transactions, alphabetical
+	field categories(&executor) -> FieldResult<Vec<Category>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		Category::find_by_client_id(&conn, ctx.user.client_id)
+			.map_err(|e| FieldError::from(e))
+	}
+
 });
 
 struct Investor {
@@ -160,4 +211,12 @@ graphql_object!(Investor: AppContext |&self| {
 			.map_err(|e| FieldError::from(e))
 	}
 
+	field categories(&executor) -> FieldResult<Vec<Category>> {
+		let ctx = &executor.context();
+		let conn = &ctx.conn;
+
+		Category::find_by_client_id(&conn, ctx.user.client_id)
+			.map_err(|e| FieldError::from(e))
+	}
+
 });
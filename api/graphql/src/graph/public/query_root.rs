@@ -3,7 +3,20 @@
 // use chrono_tz::America;
 // use chrono_tz::Australia;
 
-use crate::graph::PublicContext;
+use crate::{
+	actions::{
+		self,
+		accounts::project_balance::{ProjectionPoint, Scenario},
+	},
+	graph::PublicContext,
+	models::{cents::Cents, compounding::Compounding},
+};
+use bigdecimal::{BigDecimal, FromPrimitive};
+use chrono::Utc;
+use juniper::{FieldError, FieldResult};
+
+// Highest yearly interest the public projection takes, in percent
+const MAX_YEARLY_INTEREST: f64 = 100.0;
 
 pub struct PublicQueryRoot;
 
@@ -11,4 +24,34 @@ graphql_object!(PublicQueryRoot: PublicContext |&self| {
 	field apiVersion() -> &str {
 		"1.0"
 	}
+
+	// What saving could grow to, for the marketing site.
+	// Amounts are in cents and yearly_interest is in percent.
+	field projection(
+		balance: i32,
+		weekly_deposit: i32,
+		yearly_interest: f64,
+		compounding: Compounding,
+		months: i32
+	) -> FieldResult<Vec<ProjectionPoint>> {
+		if !(yearly_interest >= 0.0 && yearly_interest <= MAX_YEARLY_INTEREST) {
+			return Err(FieldError::from("Invalid interest"));
+		}
+
+		let yearly_interest = BigDecimal::from_f64(yearly_interest)
+			.ok_or(FieldError::from("Invalid interest"))?;
+
+		let scenario = Scenario {
+			balance:         Cents(balance as i64),
+			weekly_deposit:  Cents(weekly_deposit as i64),
+			yearly_interest: yearly_interest,
+			tiers:           vec![],
+			compounding:     compounding,
+		};
+
+		let now = Utc::now().naive_utc();
+
+		actions::accounts::project_balance::simulate(&scenario, months, now)
+			.map_err(|e| FieldError::from(e))
+	}
 });
@@ -0,0 +1,51 @@
+use crate::{
+	actions,
+	utils::{config, db_conn},
+};
+use diesel::pg::PgConnection;
+use std::{thread, time::Duration};
+
+// Background worker running inside the API process.
+// Each tick runs every job once, a job failing doesn't stop the others.
+pub fn start() {
+	let config = config::get().expect("Failed to get config");
+
+	let interval = Duration::from_secs(config.jobs_interval_in_seconds);
+
+	thread::spawn(move || {
+		let pool = db_conn::init_pool();
+
+		loop {
+			// Sleep first, so the migrations run before the first tick
+			thread::sleep(interval);
+
+			match pool.get() {
+				Ok(conn) => run(&conn),
+				Err(e) => error!("Jobs failed to get a connection: {}", e),
+			}
+		}
+	});
+}
+
+fn run(conn: &PgConnection) {
+	match actions::accounts::post_scheduled_interest::call(&conn) {
+		Ok(transactions) => info!("Posted interest to {} accounts", transactions.len()),
+		Err(e) => error!("Failed to post interest: {}", e),
+	}
+
+	match actions::scheduled_transactions::run_due::call(&conn) {
+		Ok(runs) => info!("Ran {} scheduled transactions", runs.len()),
+		Err(e) => error!("Failed to run scheduled transactions: {}", e),
+	}
+
+	match actions::transactions::expire_transaction_requests::call(&conn) {
+		Ok(expired) => info!("Expired {} transaction requests", expired.len()),
+		Err(e) => error!("Failed to expire transaction requests: {}", e),
+	}
+
+	// Interest and transfers grow balances too
+	match actions::goals::check_reached::all(&conn) {
+		Ok(goals) => info!("Reached {} goals", goals.len()),
+		Err(e) => error!("Failed to check goals: {}", e),
+	}
+}
@@ -0,0 +1,24 @@
+// Shared by the API server in main.rs and the maintenance commands in bin/
+extern crate askama;
+#[macro_use]
+extern crate diesel;
+#[macro_use]
+extern crate failure;
+#[macro_use]
+extern crate juniper;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+#[macro_use]
+extern crate validator_derive;
+#[macro_use]
+extern crate lazy_static;
+#[macro_use]
+extern crate log;
+
+pub mod actions;
+pub mod graph;
+pub mod jobs;
+pub mod models;
+pub mod utils;
@@ -1,23 +1,10 @@
 #![feature(proc_macro_hygiene, decl_macro)]
 
-extern crate askama;
-#[macro_use]
-extern crate diesel;
 #[macro_use]
 extern crate diesel_migrations;
 #[macro_use]
 extern crate failure;
 #[macro_use]
-extern crate juniper;
-#[macro_use]
-extern crate serde_derive;
-#[macro_use]
-extern crate serde_json;
-#[macro_use]
-extern crate validator_derive;
-#[macro_use]
-extern crate lazy_static;
-#[macro_use]
 extern crate rocket;
 #[macro_use]
 extern crate rocket_contrib;
@@ -25,6 +12,7 @@ extern crate rocket_contrib;
 extern crate log;
 extern crate env_logger;
 
+use api::{actions, graph, jobs, utils};
 use rocket::{
 	config::{Config, Environment, Value},
 	fairing::AdHoc,
@@ -37,11 +25,6 @@ use std::collections::HashMap;
 
 use rocket_cors::{AllowedHeaders, AllowedOrigins};
 
-mod actions;
-mod graph;
-mod models;
-mod utils;
-
 embed_migrations!();
 
 struct JWT(String);
@@ -199,6 +182,8 @@ fn rocket() -> Result<Rocket, failure::Error> {
 fn main() {
 	env_logger::init();
 
+	jobs::start();
+
 	rocket()
 		.map(|ro| ro.launch());
 }
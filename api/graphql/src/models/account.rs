@@ -1,3 +1,4 @@
+pub use crate::models::compounding::Compounding;
 use crate::models::{schema::accounts, user::User};
 use bigdecimal::BigDecimal;
 use chrono::NaiveDateTime;
@@ -17,7 +18,10 @@ pub const DEFAULT_YEARLY_INTEREST: u8 = 20;
 pub const ACTIVE: &[u8] = b"ACTIVE";
 pub const ARCHIVED: &[u8] = b"ARCHIVED";
 pub const SAVINGS: &[u8] = b"SAVINGS";
+pub const TERM_DEPOSIT: &[u8] = b"TERM_DEPOSIT";
+pub const SPENDING: &[u8] = b"SPENDING";
 
+// `archived_at` is when the account was last archived, interest stops accruing then
 #[derive(Queryable, Associations, Identifiable, Clone)]
 #[belongs_to(User)]
 #[table_name = "accounts"]
@@ -29,6 +33,9 @@ pub struct Account {
 	pub yearly_interest: BigDecimal,
 	pub kind:            Kind,
 	pub state:           State,
+	pub compounding:     Compounding,
+	pub matures_at:      Option<NaiveDateTime>,
+	pub archived_at:     Option<NaiveDateTime>,
 }
 
 #[derive(Insertable, Validate)]
@@ -39,6 +46,8 @@ pub struct AccountAttrs {
 	pub yearly_interest: BigDecimal,
 	pub kind:            Kind,
 	pub state:           State,
+	pub compounding:     Compounding,
+	pub matures_at:      Option<NaiveDateTime>,
 }
 
 #[derive(
@@ -48,12 +57,24 @@ pub struct AccountAttrs {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Kind {
 	Savings,
+	// Funds are locked until matures_at
+	TermDeposit,
+	// Pocket money, doesn't earn interest
+	Spending,
+}
+
+impl Kind {
+	pub fn earns_interest(self) -> bool {
+		self != Kind::Spending
+	}
 }
 
 impl ToSql<Text, Pg> for Kind {
 	fn to_sql<W: io::Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
 		let _v = match *self {
 			Kind::Savings => out.write_all(SAVINGS)?,
+			Kind::TermDeposit => out.write_all(TERM_DEPOSIT)?,
+			Kind::Spending => out.write_all(SPENDING)?,
 		};
 		Ok(IsNull::No)
 	}
@@ -63,6 +84,8 @@ impl FromSql<Text, Pg> for Kind {
 	fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
 		match not_none!(bytes) {
 			SAVINGS => Ok(Kind::Savings),
+			TERM_DEPOSIT => Ok(Kind::TermDeposit),
+			SPENDING => Ok(Kind::Spending),
 			_ => Err("Unrecognized Kind variant".into()),
 		}
 	}
@@ -111,6 +134,12 @@ impl Account {
 		accounts::table.filter(accounts::id.eq(id)).get_result(conn)
 	}
 
+	// Locks the account row until the end of the transaction,
+	// so balance changes to the account happen one at a time
+	pub fn lock(conn: &PgConnection, id: i32) -> Result<Account, Error> {
+		accounts::table.find(id).for_update().get_result(conn)
+	}
+
 	#[allow(dead_code)]
 	pub fn find_by_user_id(conn: &PgConnection, id: i32) -> Result<Account, Error> {
 		accounts::table
@@ -135,6 +164,8 @@ pub mod factories {
 			yearly_interest: yearly_interest,
 			kind:            Kind::Savings,
 			state:           State::Active,
+			compounding:     Compounding::Daily,
+			matures_at:      None,
 		}
 	}
 
@@ -142,11 +173,53 @@ pub mod factories {
 		pub fn save(self, conn: &PgConnection) -> Account {
 			Account::create(conn, self).unwrap()
 		}
+
+		pub fn kind(self, kind: Kind) -> Self {
+			AccountAttrs { kind, ..self }
+		}
+
+		pub fn matures_at(self, matures_at: NaiveDateTime) -> Self {
+			AccountAttrs {
+				matures_at: Some(matures_at),
+				..self
+			}
+		}
 	}
 
 	impl Account {
 		pub fn delete_all(conn: &PgConnection) -> Result<usize, Error> {
 			diesel::delete(accounts::table).execute(conn)
 		}
+
+		pub fn compounding(&self, conn: &PgConnection, compounding: Compounding) -> Account {
+			diesel::update(accounts::table.find(self.id))
+				.set(accounts::compounding.eq(compounding))
+				.get_result(conn)
+				.unwrap()
+		}
+
+		pub fn state(&self, conn: &PgConnection, state: State) -> Account {
+			diesel::update(accounts::table.find(self.id))
+				.set(accounts::state.eq(state))
+				.get_result(conn)
+				.unwrap()
+		}
+
+		pub fn created_at(&self, conn: &PgConnection, at: NaiveDateTime) -> Account {
+			diesel::update(accounts::table.find(self.id))
+				.set(accounts::created_at.eq(at))
+				.get_result(conn)
+				.unwrap()
+		}
+
+		pub fn archived_at(&self, conn: &PgConnection, at: NaiveDateTime) -> Account {
+			diesel::update(accounts::table.find(self.id))
+				.set((
+					accounts::state.eq(State::Archived),
+					accounts::archived_at.eq(at),
+				))
+				.get_result(conn)
+				.unwrap()
+		}
 	}
 }
@@ -0,0 +1,67 @@
+use crate::models::{client::Client, schema::categories};
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// A label for transactions, names are unique within a client
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq, GraphQLObject)]
+#[belongs_to(Client)]
+#[table_name = "categories"]
+pub struct Category {
+	pub id:         i32,
+	pub created_at: NaiveDateTime,
+	pub client_id:  i32,
+	pub name:       String,
+}
+
+#[derive(Insertable)]
+#[table_name = "categories"]
+pub struct CategoryAttrs {
+	pub client_id: i32,
+	pub name:      String,
+}
+
+impl Category {
+	pub fn find(conn: &PgConnection, id: i32) -> Result<Category, Error> {
+		categories::table.find(id).first(conn)
+	}
+
+	pub fn find_by_name(
+		conn: &PgConnection,
+		client_id: i32,
+		name: &str,
+	) -> Result<Option<Category>, Error> {
+		let filter = categories::client_id
+			.eq(client_id)
+			.and(categories::name.eq(name));
+
+		categories::table.filter(filter).first(conn).optional()
+	}
+
+	// Alphabetical
+	pub fn find_by_client_id(conn: &PgConnection, client_id: i32) -> Result<Vec<Category>, Error> {
+		categories::table
+			.filter(categories::client_id.eq(client_id))
+			.order_by(categories::name.asc())
+			.load(conn)
+	}
+
+	// The client's category with this name, made the first time it is used
+	pub fn find_or_create(
+		conn: &PgConnection,
+		client_id: i32,
+		name: &str,
+	) -> Result<Category, Error> {
+		let attrs = CategoryAttrs {
+			client_id: client_id,
+			name:      name.to_string(),
+		};
+
+		// Another request may have just made it
+		diesel::insert_into(categories::table)
+			.values(&attrs)
+			.on_conflict_do_nothing()
+			.execute(conn)?;
+
+		Category::find_by_name(conn, client_id, name)?.ok_or(Error::NotFound)
+	}
+}
@@ -1,4 +1,4 @@
-use super::schema::clients;
+use super::{posting_frequency::PostingFrequency, schema::clients};
 use chrono::NaiveDateTime;
 use diesel;
 use diesel::prelude::*;
@@ -15,6 +15,8 @@ pub struct Client {
 	pub id: i32,
 	pub created_at: NaiveDateTime,
 	pub name: String,
+	pub interest_posting: PostingFrequency,
+	pub request_expiry_days: i32,
 }
 
 #[derive(Insertable)]
@@ -45,6 +47,11 @@ impl Client {
 		clients::table.find(client_id).first::<Client>(conn)
 	}
 
+	#[allow(dead_code)]
+	pub fn all(conn: &PgConnection) -> Result<Vec<Client>, Error> {
+		clients::table.load::<Client>(conn)
+	}
+
 	#[allow(dead_code)]
 	pub fn first(conn: &PgConnection) -> Result<Client, Error> {
 		clients::table.first::<Client>(conn)
@@ -72,5 +79,19 @@ pub mod factories {
 		pub fn delete_all(conn: &PgConnection) -> Result<usize, Error> {
 			diesel::delete(clients::table).execute(conn)
 		}
+
+		pub fn request_expiry_days(self, conn: &PgConnection, days: i32) -> Client {
+			diesel::update(clients::table.find(self.id))
+				.set(clients::request_expiry_days.eq(days))
+				.get_result(conn)
+				.unwrap()
+		}
+
+		pub fn interest_posting(self, conn: &PgConnection, frequency: PostingFrequency) -> Client {
+			diesel::update(clients::table.find(self.id))
+				.set(clients::interest_posting.eq(frequency))
+				.get_result(conn)
+				.unwrap()
+		}
 	}
 }
@@ -0,0 +1,105 @@
+use chrono::{Duration, NaiveDateTime};
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::Pg;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::*;
+use std::io;
+
+pub const SIMPLE: &[u8] = b"SIMPLE";
+pub const DAILY: &[u8] = b"DAILY";
+pub const WEEKLY: &[u8] = b"WEEKLY";
+pub const MONTHLY: &[u8] = b"MONTHLY";
+
+// How often interest earns interest.
+// Simple interest is only paid on the money put in, it never compounds.
+#[derive(Debug, Copy, Clone, FromSqlRow, AsExpression, GraphQLEnum, PartialEq)]
+#[sql_type = "Varchar"]
+pub enum Compounding {
+	Simple,
+	Daily,
+	Weekly,
+	Monthly,
+}
+
+impl Compounding {
+	// Length of a compounding period in days, as (numerator, denominator)
+	// e.g. a month is 365 / 12 days
+	pub fn period_in_days(self) -> Option<(u64, u64)> {
+		match self {
+			Compounding::Simple => None,
+			Compounding::Daily => Some((1, 1)),
+			Compounding::Weekly => Some((7, 1)),
+			Compounding::Monthly => Some((365, 12)),
+		}
+	}
+
+	// The end of the period `at` falls in, periods being counted in whole days from `opened_at`.
+	// None for simple interest, which has no periods.
+	pub fn period_end(self, opened_at: NaiveDateTime, at: NaiveDateTime) -> Option<NaiveDateTime> {
+		let (numerator, denominator) = self.period_in_days()?;
+
+		let days = at.signed_duration_since(opened_at).num_days().max(0) as u64;
+
+		let mut periods = days * denominator / numerator;
+
+		loop {
+			let end = opened_at + Duration::days(((periods + 1) * numerator / denominator) as i64);
+
+			if end > at {
+				return Some(end);
+			}
+
+			periods += 1;
+		}
+	}
+}
+
+impl ToSql<Text, Pg> for Compounding {
+	fn to_sql<W: io::Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+		let _v = match *self {
+			Compounding::Simple => out.write_all(SIMPLE)?,
+			Compounding::Daily => out.write_all(DAILY)?,
+			Compounding::Weekly => out.write_all(WEEKLY)?,
+			Compounding::Monthly => out.write_all(MONTHLY)?,
+		};
+		Ok(IsNull::No)
+	}
+}
+
+impl FromSql<Text, Pg> for Compounding {
+	fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+		match not_none!(bytes) {
+			SIMPLE => Ok(Compounding::Simple),
+			DAILY => Ok(Compounding::Daily),
+			WEEKLY => Ok(Compounding::Weekly),
+			MONTHLY => Ok(Compounding::Monthly),
+			_ => Err("Unrecognized enum variant".into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn at(days: i64) -> NaiveDateTime {
+		NaiveDateTime::from_timestamp(1_560_000_000, 0) + Duration::days(days)
+	}
+
+	#[test]
+	fn it_counts_periods_from_the_open_date() {
+		assert_eq!(Compounding::Daily.period_end(at(0), at(0)), Some(at(1)));
+		assert_eq!(Compounding::Weekly.period_end(at(0), at(3)), Some(at(7)));
+		assert_eq!(Compounding::Weekly.period_end(at(0), at(7)), Some(at(14)));
+		assert_eq!(Compounding::Simple.period_end(at(0), at(3)), None);
+	}
+
+	#[test]
+	fn months_are_a_twelfth_of_a_year() {
+		let monthly = Compounding::Monthly;
+
+		assert_eq!(monthly.period_end(at(0), at(0)), Some(at(30)));
+		assert_eq!(monthly.period_end(at(0), at(60)), Some(at(91)));
+		assert_eq!(monthly.period_end(at(0), at(364)), Some(at(365)));
+	}
+}
@@ -0,0 +1,43 @@
+use diesel::{
+	deserialize::{self, FromSql},
+	pg::Pg,
+	serialize::{self, IsNull, Output, ToSql},
+	sql_types::*,
+};
+use std::io;
+
+pub const DIRECT: &[u8] = b"DIRECT";
+pub const REQUEST: &[u8] = b"REQUEST";
+pub const SCHEDULED: &[u8] = b"SCHEDULED";
+
+// How a deposit was made: with the deposit mutation,
+// by approving a deposit request, or by a scheduled transaction such as an allowance
+#[derive(Debug, Copy, Clone, FromSqlRow, AsExpression, GraphQLEnum, PartialEq)]
+#[sql_type = "Varchar"]
+pub enum DepositSource {
+	Direct,
+	Request,
+	Scheduled,
+}
+
+impl ToSql<Text, Pg> for DepositSource {
+	fn to_sql<W: io::Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+		let _v = match *self {
+			DepositSource::Direct => out.write_all(DIRECT)?,
+			DepositSource::Request => out.write_all(REQUEST)?,
+			DepositSource::Scheduled => out.write_all(SCHEDULED)?,
+		};
+		Ok(IsNull::No)
+	}
+}
+
+impl FromSql<Text, Pg> for DepositSource {
+	fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+		match not_none!(bytes) {
+			DIRECT => Ok(DepositSource::Direct),
+			REQUEST => Ok(DepositSource::Request),
+			SCHEDULED => Ok(DepositSource::Scheduled),
+			_ => Err("Unrecognized enum variant".into()),
+		}
+	}
+}
@@ -0,0 +1,403 @@
+use crate::models::cents::Cents;
+use bigdecimal::BigDecimal;
+use failure::Error;
+use std::{fmt, str::FromStr};
+
+// Fixed-point decimal used for money calculations that need more precision than Cents.
+//
+// Values are an i128 scaled by 10^18, so arithmetic is exact integer arithmetic
+// and gives the same result on every machine.
+//
+// Rounding:
+// - Multiplication and division round half to even at the 18th decimal place.
+// - Parsing truncates digits beyond the 18th decimal place.
+// - `to_cents` truncates towards zero, so we never pay a fraction of a cent
+//   that has not been earned.
+pub const DECIMALS: u32 = 18;
+
+const SCALE: i128 = 1_000_000_000_000_000_000;
+
+// Newton's method converges in a handful of steps, this is only a safety net
+const MAX_ROOT_ITERATIONS: u32 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FixedPoint(i128);
+
+impl FixedPoint {
+	pub fn zero() -> FixedPoint {
+		FixedPoint(0)
+	}
+
+	pub fn one() -> FixedPoint {
+		FixedPoint(SCALE)
+	}
+
+	pub fn from_int(n: i64) -> FixedPoint {
+		FixedPoint(n as i128 * SCALE)
+	}
+
+	pub fn from_cents(cents: Cents) -> FixedPoint {
+		let Cents(n) = cents;
+		FixedPoint::from_int(n)
+	}
+
+	// e.g. from_ratio(1, 3) is 0.333333333333333333
+	pub fn from_ratio(numerator: i64, denominator: i64) -> Result<FixedPoint, Error> {
+		FixedPoint::from_int(numerator).checked_div(FixedPoint::from_int(denominator))
+	}
+
+	pub fn from_big_decimal(value: &BigDecimal) -> Result<FixedPoint, Error> {
+		value.to_string().parse()
+	}
+
+	// Rates are stored as percentages e.g. 20 means 20%, this returns 0.2
+	pub fn from_percentage(value: &BigDecimal) -> Result<FixedPoint, Error> {
+		FixedPoint::from_big_decimal(value)?.checked_div(FixedPoint::from_int(100))
+	}
+
+	pub fn to_big_decimal(self) -> Result<BigDecimal, Error> {
+		BigDecimal::from_str(&self.to_string()).map_err(|e| format_err!("{}", e))
+	}
+
+	// Truncates towards zero
+	pub fn to_cents(self) -> Cents {
+		Cents((self.0 / SCALE) as i64)
+	}
+
+	pub fn is_negative(self) -> bool {
+		self.0 < 0
+	}
+
+	pub fn checked_add(self, other: FixedPoint) -> Result<FixedPoint, Error> {
+		self.0
+			.checked_add(other.0)
+			.map(FixedPoint)
+			.ok_or(format_err!("Decimal overflow"))
+	}
+
+	pub fn checked_sub(self, other: FixedPoint) -> Result<FixedPoint, Error> {
+		self.0
+			.checked_sub(other.0)
+			.map(FixedPoint)
+			.ok_or(format_err!("Decimal overflow"))
+	}
+
+	pub fn checked_mul(self, other: FixedPoint) -> Result<FixedPoint, Error> {
+		let negative = (self.0 < 0) != (other.0 < 0);
+		let (a, b) = (self.0.abs(), other.0.abs());
+
+		// Multiplying the scaled values would overflow above about 170,
+		// so each value is split into integer and fraction parts.
+		// Only the product of the fractions is below a unit, it is the only part rounded.
+		let (a_int, a_frac) = (a / SCALE, a % SCALE);
+		let (b_int, b_frac) = (b / SCALE, b % SCALE);
+
+		let product = a_int
+			.checked_mul(b_int)
+			.and_then(|n| n.checked_mul(SCALE))
+			.and_then(|n| n.checked_add(a_int.checked_mul(b_frac)?))
+			.and_then(|n| n.checked_add(a_frac.checked_mul(b_int)?))
+			.and_then(|n| n.checked_add(div_round_half_even(a_frac * b_frac, SCALE)))
+			.ok_or(format_err!("Decimal overflow"))?;
+
+		Ok(FixedPoint(if negative { -product } else { product }))
+	}
+
+	pub fn checked_div(self, other: FixedPoint) -> Result<FixedPoint, Error> {
+		if other.0 == 0 {
+			return Err(format_err!("Division by zero"));
+		}
+
+		let negative = (self.0 < 0) != (other.0 < 0);
+		let (a, b) = (self.0.abs(), other.0.abs());
+
+		// Long division, one decimal digit of the fraction at a time,
+		// so the numerator is never scaled up by 10^18
+		let integer = a / b;
+		let mut remainder = a % b;
+		let mut fraction: i128 = 0;
+
+		for _ in 0..DECIMALS {
+			remainder = remainder
+				.checked_mul(10)
+				.ok_or(format_err!("Decimal overflow"))?;
+
+			fraction = fraction * 10 + remainder / b;
+			remainder %= b;
+		}
+
+		let rest = b - remainder;
+
+		if remainder > rest || (remainder == rest && fraction % 2 == 1) {
+			fraction += 1;
+		}
+
+		let quotient = integer
+			.checked_mul(SCALE)
+			.and_then(|n| n.checked_add(fraction))
+			.ok_or(format_err!("Decimal overflow"))?;
+
+		Ok(FixedPoint(if negative { -quotient } else { quotient }))
+	}
+
+	// Exponentiation by squaring, each step rounds half to even
+	pub fn checked_pow(self, exponent: u64) -> Result<FixedPoint, Error> {
+		let mut result = FixedPoint::one();
+		let mut base = self;
+		let mut remaining = exponent;
+
+		while remaining > 0 {
+			if remaining & 1 == 1 {
+				result = result.checked_mul(base)?;
+			}
+
+			remaining >>= 1;
+
+			if remaining > 0 {
+				base = base.checked_mul(base)?;
+			}
+		}
+
+		Ok(result)
+	}
+
+	// Nth root using Newton's method.
+	// We start from a value that is known to be above the root,
+	// so the iteration decreases until it converges.
+	pub fn checked_root(self, n: u64) -> Result<FixedPoint, Error> {
+		if self.0 <= 0 {
+			return Err(format_err!("Root of a non positive number"));
+		}
+
+		if n == 0 {
+			return Err(format_err!("Zeroth root"));
+		}
+
+		if n == 1 {
+			return Ok(self);
+		}
+
+		let one = FixedPoint::one();
+		let degree = FixedPoint::from_int(n as i64);
+		let degree_minus_one = FixedPoint::from_int(n as i64 - 1);
+
+		// By Bernoulli's inequality 1 + (a - 1) / n is above the root when a >= 1
+		let mut x = if self >= one {
+			one.checked_add(self.checked_sub(one)?.checked_div(degree)?)?
+		} else {
+			one
+		};
+
+		for _ in 0..MAX_ROOT_ITERATIONS {
+			let next = degree_minus_one
+				.checked_mul(x)?
+				.checked_add(self.checked_div(x.checked_pow(n - 1)?)?)?
+				.checked_div(degree)?;
+
+			if next >= x {
+				break;
+			}
+
+			x = next;
+		}
+
+		Ok(x)
+	}
+
+	// Round half to even to the given number of decimal places
+	pub fn round(self, decimals: u32) -> FixedPoint {
+		if decimals >= DECIMALS {
+			return self;
+		}
+
+		let unit = 10i128.pow(DECIMALS - decimals);
+
+		FixedPoint(div_round_half_even(self.0, unit) * unit)
+	}
+}
+
+fn div_round_half_even(numerator: i128, denominator: i128) -> i128 {
+	let negative = (numerator < 0) != (denominator < 0);
+	let n = numerator.abs();
+	let d = denominator.abs();
+
+	let quotient = n / d;
+	let remainder = n % d;
+	let rest = d - remainder;
+
+	let rounded = if remainder > rest || (remainder == rest && quotient % 2 == 1) {
+		quotient + 1
+	} else {
+		quotient
+	};
+
+	if negative {
+		-rounded
+	} else {
+		rounded
+	}
+}
+
+impl fmt::Display for FixedPoint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let sign = if self.0 < 0 { "-" } else { "" };
+		let abs = self.0.abs();
+		let integer = abs / SCALE;
+		let fraction = abs % SCALE;
+
+		if fraction == 0 {
+			return write!(f, "{}{}", sign, integer);
+		}
+
+		let digits = format!("{:018}", fraction);
+
+		write!(f, "{}{}.{}", sign, integer, digits.trim_end_matches('0'))
+	}
+}
+
+impl FromStr for FixedPoint {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<FixedPoint, Error> {
+		let invalid = || format_err!("Invalid decimal {}", s);
+
+		let trimmed = s.trim();
+
+		let (negative, unsigned) = if trimmed.starts_with('-') {
+			(true, &trimmed[1..])
+		} else if trimmed.starts_with('+') {
+			(false, &trimmed[1..])
+		} else {
+			(false, trimmed)
+		};
+
+		let mut parts = unsigned.splitn(2, '.');
+		let integer_part = parts.next().unwrap_or("");
+		let fraction_part = parts.next().unwrap_or("");
+
+		if integer_part.is_empty() && fraction_part.is_empty() {
+			return Err(invalid());
+		}
+
+		let all_digits = integer_part
+			.chars()
+			.chain(fraction_part.chars())
+			.all(|c| c.is_ascii_digit());
+
+		if !all_digits {
+			return Err(invalid());
+		}
+
+		let integer: i128 = if integer_part.is_empty() {
+			0
+		} else {
+			integer_part.parse().map_err(|_| invalid())?
+		};
+
+		// Digits beyond our precision are truncated
+		let kept: String = fraction_part.chars().take(DECIMALS as usize).collect();
+		let padded = format!("{:0<width$}", kept, width = DECIMALS as usize);
+		let fraction: i128 = padded.parse().map_err(|_| invalid())?;
+
+		let value = integer
+			.checked_mul(SCALE)
+			.and_then(|n| n.checked_add(fraction))
+			.ok_or(format_err!("Decimal overflow"))?;
+
+		if negative {
+			Ok(FixedPoint(-value))
+		} else {
+			Ok(FixedPoint(value))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fp(s: &str) -> FixedPoint {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn it_parses_and_prints() {
+		assert_eq!(fp("10.5").to_string(), "10.5");
+		assert_eq!(fp("-0.25").to_string(), "-0.25");
+		assert_eq!(fp("20").to_string(), "20");
+		assert_eq!(fp(".5").to_string(), "0.5");
+	}
+
+	#[test]
+	fn it_rejects_invalid_input() {
+		assert!("abc".parse::<FixedPoint>().is_err());
+		assert!("1.2.3".parse::<FixedPoint>().is_err());
+		assert!("".parse::<FixedPoint>().is_err());
+	}
+
+	#[test]
+	fn it_rounds_half_to_even() {
+		assert_eq!(fp("0.25").round(1), fp("0.2"));
+		assert_eq!(fp("0.35").round(1), fp("0.4"));
+		assert_eq!(fp("-0.25").round(1), fp("-0.2"));
+		assert_eq!(fp("0.26").round(1), fp("0.3"));
+	}
+
+	#[test]
+	fn it_truncates_to_cents() {
+		assert_eq!(fp("12.99").to_cents(), Cents(12));
+		assert_eq!(fp("-12.99").to_cents(), Cents(-12));
+	}
+
+	#[test]
+	fn it_divides() {
+		let third = FixedPoint::from_ratio(1, 3).unwrap();
+		assert_eq!(third.to_string(), "0.333333333333333333");
+
+		let two_thirds = FixedPoint::from_ratio(2, 3).unwrap();
+		assert_eq!(two_thirds.to_string(), "0.666666666666666667");
+	}
+
+	#[test]
+	fn it_calculates_powers() {
+		assert_eq!(fp("1.1").checked_pow(2).unwrap(), fp("1.21"));
+		assert_eq!(fp("2").checked_pow(10).unwrap(), fp("1024"));
+		assert_eq!(fp("2").checked_pow(0).unwrap(), FixedPoint::one());
+	}
+
+	#[test]
+	fn it_calculates_roots() {
+		assert_eq!(fp("1024").checked_root(10).unwrap(), fp("2"));
+
+		let root = fp("1.2").checked_root(365).unwrap();
+		let back = root.checked_pow(365).unwrap().round(12);
+
+		assert_eq!(back, fp("1.2"));
+	}
+
+	#[test]
+	fn it_multiplies_and_divides_large_values() {
+		assert_eq!(
+			fp("5000").checked_mul(fp("0.2")).unwrap(),
+			FixedPoint::from_int(1_000)
+		);
+		assert_eq!(
+			fp("123456.5").checked_mul(fp("-2.25")).unwrap(),
+			fp("-277777.125")
+		);
+		assert_eq!(
+			fp("1000000").checked_div(fp("0.5")).unwrap(),
+			FixedPoint::from_int(2_000_000)
+		);
+		assert_eq!(
+			fp("-10000").checked_div(fp("3")).unwrap().to_string(),
+			"-3333.333333333333333333"
+		);
+	}
+
+	#[test]
+	fn it_detects_overflow() {
+		let big = FixedPoint::from_int(i64::max_value());
+		assert!(big.checked_mul(big).is_err());
+	}
+}
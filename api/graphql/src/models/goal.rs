@@ -0,0 +1,118 @@
+use crate::models::{account::Account, cents::Cents, schema::goals};
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// Something the account holder is saving towards, e.g. a bike.
+// `reached_at` is set the first time the balance gets to the target.
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(Account)]
+#[table_name = "goals"]
+pub struct Goal {
+	pub id:          i32,
+	pub created_at:  NaiveDateTime,
+	pub account_id:  i32,
+	pub name:        String,
+	pub target:      Cents,
+	pub target_date: Option<NaiveDateTime>,
+	pub image_url:   Option<String>,
+	pub reached_at:  Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "goals"]
+pub struct GoalAttrs {
+	pub account_id:  i32,
+	pub name:        String,
+	pub target:      Cents,
+	pub target_date: Option<NaiveDateTime>,
+	pub image_url:   Option<String>,
+}
+
+impl Goal {
+	pub fn create(conn: &PgConnection, attrs: GoalAttrs) -> Result<Goal, Error> {
+		diesel::insert_into(goals::dsl::goals)
+			.values(&attrs)
+			.get_result(conn)
+	}
+
+	pub fn find(conn: &PgConnection, id: i32) -> Result<Goal, Error> {
+		goals::table.find(id).first(conn)
+	}
+
+	// Oldest first
+	pub fn find_by_account_id(conn: &PgConnection, account_id: i32) -> Result<Vec<Goal>, Error> {
+		goals::table
+			.filter(goals::account_id.eq(account_id))
+			.order_by(goals::id.asc())
+			.get_results(conn)
+	}
+
+	// Goals of every account that haven't been reached yet
+	pub fn find_unreached(conn: &PgConnection) -> Result<Vec<Goal>, Error> {
+		goals::table
+			.filter(goals::reached_at.is_null())
+			.order_by((goals::account_id.asc(), goals::id.asc()))
+			.get_results(conn)
+	}
+
+	// Replaces the goal, the account stays the same
+	pub fn update(
+		conn: &PgConnection,
+		id: i32,
+		attrs: GoalAttrs,
+		reached_at: Option<NaiveDateTime>,
+	) -> Result<Goal, Error> {
+		diesel::update(goals::table.find(id))
+			.set((
+				goals::name.eq(attrs.name),
+				goals::target.eq(attrs.target),
+				goals::target_date.eq(attrs.target_date),
+				goals::image_url.eq(attrs.image_url),
+				goals::reached_at.eq(reached_at),
+			))
+			.get_result(conn)
+	}
+
+	// None if it was already reached, so only one caller tells the account holder
+	pub fn reach(conn: &PgConnection, id: i32, at: NaiveDateTime) -> Result<Option<Goal>, Error> {
+		let filter = goals::id.eq(id).and(goals::reached_at.is_null());
+
+		diesel::update(goals::table.filter(filter))
+			.set(goals::reached_at.eq(at))
+			.get_result(conn)
+			.optional()
+	}
+
+	pub fn delete(conn: &PgConnection, id: i32) -> Result<usize, Error> {
+		diesel::delete(goals::table.find(id)).execute(conn)
+	}
+}
+
+#[cfg(test)]
+pub mod factories {
+	use super::*;
+
+	#[allow(dead_code)]
+	pub fn goal_attrs(account: &Account) -> GoalAttrs {
+		GoalAttrs {
+			account_id:  account.id,
+			name:        "Bike".to_string(),
+			target:      Cents(10_000),
+			target_date: None,
+			image_url:   None,
+		}
+	}
+
+	impl GoalAttrs {
+		pub fn save(self, conn: &PgConnection) -> Goal {
+			Goal::create(conn, self).unwrap()
+		}
+
+		pub fn target(self, cents: i64) -> Self {
+			GoalAttrs {
+				target: Cents(cents),
+				..self
+			}
+		}
+	}
+}
@@ -0,0 +1,91 @@
+use crate::models::{account::Account, schema::interest_rates};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// A change of the yearly interest of an account.
+// `changed_by_user_id` is None when set by the system e.g. when creating the account
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(Account)]
+#[table_name = "interest_rates"]
+pub struct InterestRate {
+	pub id:                  i32,
+	pub created_at:          NaiveDateTime,
+	pub account_id:          i32,
+	pub effective_from:      NaiveDateTime,
+	pub changed_by_user_id:  Option<i32>,
+	pub old_yearly_interest: Option<BigDecimal>,
+	pub new_yearly_interest: BigDecimal,
+}
+
+#[derive(Insertable)]
+#[table_name = "interest_rates"]
+pub struct InterestRateAttrs {
+	pub account_id:          i32,
+	pub effective_from:      NaiveDateTime,
+	pub changed_by_user_id:  Option<i32>,
+	pub old_yearly_interest: Option<BigDecimal>,
+	pub new_yearly_interest: BigDecimal,
+}
+
+impl InterestRate {
+	#[allow(dead_code)]
+	pub fn create(conn: &PgConnection, attrs: InterestRateAttrs) -> Result<InterestRate, Error> {
+		diesel::insert_into(interest_rates::dsl::interest_rates)
+			.values(&attrs)
+			.get_result(conn)
+	}
+
+	// Oldest first
+	#[allow(dead_code)]
+	pub fn find_by_account_id(
+		conn: &PgConnection,
+		account_id: i32,
+	) -> Result<Vec<InterestRate>, Error> {
+		interest_rates::table
+			.filter(interest_rates::account_id.eq(account_id))
+			.order_by((
+				interest_rates::effective_from.asc(),
+				interest_rates::id.asc(),
+			))
+			.get_results(conn)
+	}
+}
+
+#[cfg(test)]
+pub mod factories {
+	use super::*;
+	use bigdecimal::FromPrimitive;
+
+	#[allow(dead_code)]
+	pub fn interest_rate_attrs(account: &Account) -> InterestRateAttrs {
+		InterestRateAttrs {
+			account_id:          account.id,
+			effective_from:      account.created_at,
+			changed_by_user_id:  None,
+			old_yearly_interest: None,
+			new_yearly_interest: BigDecimal::from_f32(10.5).unwrap(),
+		}
+	}
+
+	impl InterestRateAttrs {
+		pub fn save(self, conn: &PgConnection) -> InterestRate {
+			InterestRate::create(conn, self).unwrap()
+		}
+
+		pub fn effective_from(self, effective_from: NaiveDateTime) -> Self {
+			InterestRateAttrs {
+				effective_from,
+				..self
+			}
+		}
+
+		pub fn change(self, old: f32, new: f32) -> Self {
+			InterestRateAttrs {
+				old_yearly_interest: BigDecimal::from_f32(old),
+				new_yearly_interest: BigDecimal::from_f32(new).unwrap(),
+				..self
+			}
+		}
+	}
+}
@@ -0,0 +1,85 @@
+use crate::models::{account::Account, cents::Cents, schema::interest_tiers};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// The part of the balance from `from_balance` up to the next tier earns `yearly_interest`.
+// Below the first tier the account's yearly_interest applies.
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(Account)]
+#[table_name = "interest_tiers"]
+pub struct InterestTier {
+	pub id:              i32,
+	pub created_at:      NaiveDateTime,
+	pub account_id:      i32,
+	pub from_balance:    Cents,
+	pub yearly_interest: BigDecimal,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "interest_tiers"]
+pub struct InterestTierAttrs {
+	pub account_id:      i32,
+	pub from_balance:    Cents,
+	pub yearly_interest: BigDecimal,
+}
+
+impl InterestTier {
+	#[allow(dead_code)]
+	pub fn create(conn: &PgConnection, attrs: InterestTierAttrs) -> Result<InterestTier, Error> {
+		diesel::insert_into(interest_tiers::dsl::interest_tiers)
+			.values(&attrs)
+			.get_result(conn)
+	}
+
+	// Lowest first
+	pub fn find_by_account_id(
+		conn: &PgConnection,
+		account_id: i32,
+	) -> Result<Vec<InterestTier>, Error> {
+		interest_tiers::table
+			.filter(interest_tiers::account_id.eq(account_id))
+			.order_by(interest_tiers::from_balance.asc())
+			.get_results(conn)
+	}
+
+	pub fn delete_by_account_id(conn: &PgConnection, account_id: i32) -> Result<usize, Error> {
+		diesel::delete(interest_tiers::table.filter(interest_tiers::account_id.eq(account_id)))
+			.execute(conn)
+	}
+}
+
+#[cfg(test)]
+pub mod factories {
+	use super::*;
+	use bigdecimal::FromPrimitive;
+
+	#[allow(dead_code)]
+	pub fn interest_tier_attrs(account: &Account) -> InterestTierAttrs {
+		InterestTierAttrs {
+			account_id:      account.id,
+			from_balance:    Cents(5_000),
+			yearly_interest: BigDecimal::from_f32(5.0).unwrap(),
+		}
+	}
+
+	impl InterestTierAttrs {
+		pub fn save(self, conn: &PgConnection) -> InterestTier {
+			InterestTier::create(conn, self).unwrap()
+		}
+
+		pub fn from_balance(self, cents: i64) -> Self {
+			InterestTierAttrs {
+				from_balance: Cents(cents),
+				..self
+			}
+		}
+
+		pub fn yearly_interest(self, yearly_interest: f32) -> Self {
+			InterestTierAttrs {
+				yearly_interest: BigDecimal::from_f32(yearly_interest).unwrap(),
+				..self
+			}
+		}
+	}
+}
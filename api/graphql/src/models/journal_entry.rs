@@ -0,0 +1,29 @@
+use crate::models::{client::Client, schema::journal_entries, transaction_kind::TransactionKind};
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// One money movement, its postings add up to zero
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(Client)]
+#[table_name = "journal_entries"]
+pub struct JournalEntry {
+	pub id:         i32,
+	pub created_at: NaiveDateTime,
+	pub client_id:  i32,
+	pub kind:       TransactionKind,
+}
+
+#[derive(Insertable)]
+#[table_name = "journal_entries"]
+pub struct JournalEntryAttrs {
+	pub client_id: i32,
+	pub kind:      TransactionKind,
+}
+
+impl JournalEntry {
+	pub fn create(conn: &PgConnection, attrs: JournalEntryAttrs) -> Result<JournalEntry, Error> {
+		diesel::insert_into(journal_entries::dsl::journal_entries)
+			.values(&attrs)
+			.get_result(conn)
+	}
+}
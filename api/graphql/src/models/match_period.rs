@@ -0,0 +1,52 @@
+use diesel::{
+	deserialize::{self, FromSql},
+	pg::Pg,
+	serialize::{self, IsNull, Output, ToSql},
+	sql_types::*,
+};
+use std::io;
+
+pub const WEEKLY: &[u8] = b"WEEKLY";
+pub const MONTHLY: &[u8] = b"MONTHLY";
+pub const YEARLY: &[u8] = b"YEARLY";
+
+// The window a matching cap applies to, counted back from the deposit
+#[derive(Debug, Copy, Clone, FromSqlRow, AsExpression, GraphQLEnum, PartialEq)]
+#[sql_type = "Varchar"]
+pub enum MatchPeriod {
+	Weekly,
+	Monthly,
+	Yearly,
+}
+
+impl MatchPeriod {
+	pub fn days(self) -> i64 {
+		match self {
+			MatchPeriod::Weekly => 7,
+			MatchPeriod::Monthly => 30,
+			MatchPeriod::Yearly => 365,
+		}
+	}
+}
+
+impl ToSql<Text, Pg> for MatchPeriod {
+	fn to_sql<W: io::Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+		let _v = match *self {
+			MatchPeriod::Weekly => out.write_all(WEEKLY)?,
+			MatchPeriod::Monthly => out.write_all(MONTHLY)?,
+			MatchPeriod::Yearly => out.write_all(YEARLY)?,
+		};
+		Ok(IsNull::No)
+	}
+}
+
+impl FromSql<Text, Pg> for MatchPeriod {
+	fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+		match not_none!(bytes) {
+			WEEKLY => Ok(MatchPeriod::Weekly),
+			MONTHLY => Ok(MatchPeriod::Monthly),
+			YEARLY => Ok(MatchPeriod::Yearly),
+			_ => Err("Unrecognized enum variant".into()),
+		}
+	}
+}
@@ -0,0 +1,91 @@
+use crate::models::{account::Account, cents::Cents, schema::matching_rules};
+pub use crate::models::{deposit_source::DepositSource, match_period::MatchPeriod};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// Deposits from `sources` are matched with `percentage` of their amount,
+// up to `cap` over the last `period`, or without a limit when there is no cap.
+// An account has at most one.
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(Account)]
+#[table_name = "matching_rules"]
+pub struct MatchingRule {
+	pub id:         i32,
+	pub created_at: NaiveDateTime,
+	pub account_id: i32,
+	pub percentage: BigDecimal,
+	pub cap:        Option<Cents>,
+	pub period:     MatchPeriod,
+	pub sources:    Vec<DepositSource>,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "matching_rules"]
+pub struct MatchingRuleAttrs {
+	pub account_id: i32,
+	pub percentage: BigDecimal,
+	pub cap:        Option<Cents>,
+	pub period:     MatchPeriod,
+	pub sources:    Vec<DepositSource>,
+}
+
+impl MatchingRule {
+	pub fn create(conn: &PgConnection, attrs: MatchingRuleAttrs) -> Result<MatchingRule, Error> {
+		diesel::insert_into(matching_rules::dsl::matching_rules)
+			.values(&attrs)
+			.get_result(conn)
+	}
+
+	pub fn find_by_account_id(
+		conn: &PgConnection,
+		account_id: i32,
+	) -> Result<Option<MatchingRule>, Error> {
+		matching_rules::table
+			.filter(matching_rules::account_id.eq(account_id))
+			.first(conn)
+			.optional()
+	}
+
+	pub fn delete_by_account_id(conn: &PgConnection, account_id: i32) -> Result<usize, Error> {
+		diesel::delete(matching_rules::table.filter(matching_rules::account_id.eq(account_id)))
+			.execute(conn)
+	}
+}
+
+#[cfg(test)]
+pub mod factories {
+	use super::*;
+	use bigdecimal::FromPrimitive;
+
+	#[allow(dead_code)]
+	pub fn matching_rule_attrs(account: &Account) -> MatchingRuleAttrs {
+		MatchingRuleAttrs {
+			account_id: account.id,
+			percentage: BigDecimal::from_f32(50.0).unwrap(),
+			cap:        None,
+			period:     MatchPeriod::Monthly,
+			sources:    vec![DepositSource::Direct],
+		}
+	}
+
+	impl MatchingRuleAttrs {
+		pub fn save(self, conn: &PgConnection) -> MatchingRule {
+			MatchingRule::create(conn, self).unwrap()
+		}
+
+		pub fn cap(self, cents: i64) -> Self {
+			MatchingRuleAttrs {
+				cap: Some(Cents(cents)),
+				..self
+			}
+		}
+
+		pub fn sources(self, sources: Vec<DepositSource>) -> Self {
+			MatchingRuleAttrs {
+				sources: sources,
+				..self
+			}
+		}
+	}
+}
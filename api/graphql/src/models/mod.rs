@@ -1,14 +1,32 @@
 pub mod account;
+pub mod category;
 pub mod cents;
 pub mod client;
+pub mod compounding;
+pub mod deposit_source;
 pub mod error;
+pub mod fixed_point;
+pub mod goal;
+pub mod interest_rate;
+pub mod interest_tier;
 pub mod invitation;
+pub mod journal_entry;
+pub mod match_period;
+pub mod matching_rule;
+pub mod posting;
+pub mod posting_frequency;
 pub mod role;
+pub mod schedule_frequency;
+pub mod scheduled_transaction;
+pub mod scheduled_transaction_run;
 pub mod schema;
 pub mod sign_in;
 pub mod sign_up;
+pub mod split_rule;
 pub mod transaction;
 pub mod transaction_kind;
 pub mod transaction_request;
 pub mod transaction_request_state;
 pub mod user;
+pub mod withdrawal_rule;
+pub mod withdrawal_rule_kind;
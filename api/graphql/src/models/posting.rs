@@ -0,0 +1,79 @@
+use crate::models::{
+	cents::Cents,
+	journal_entry::JournalEntry,
+	schema::{journal_entries, postings},
+	transaction_kind::TransactionKind,
+};
+use chrono::NaiveDateTime;
+use diesel::{self, dsl::sum, pg::PgConnection, prelude::*, result::Error};
+
+// One side of a journal entry.
+// Postings without an account are against the client's family bank,
+// which funds deposits and interest.
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(JournalEntry)]
+#[table_name = "postings"]
+pub struct Posting {
+	pub id:               i32,
+	pub created_at:       NaiveDateTime,
+	pub journal_entry_id: i32,
+	pub account_id:       Option<i32>,
+	pub transaction_id:   Option<i32>,
+	pub amount:           Cents,
+}
+
+#[derive(Insertable)]
+#[table_name = "postings"]
+pub struct PostingAttrs {
+	pub journal_entry_id: i32,
+	pub account_id:       Option<i32>,
+	pub transaction_id:   Option<i32>,
+	pub amount:           Cents,
+}
+
+impl Posting {
+	pub fn create(conn: &PgConnection, attrs: PostingAttrs) -> Result<Posting, Error> {
+		diesel::insert_into(postings::dsl::postings)
+			.values(&attrs)
+			.get_result(conn)
+	}
+
+	// Oldest first
+	pub fn find_by_account_id(conn: &PgConnection, account_id: i32) -> Result<Vec<Posting>, Error> {
+		postings::table
+			.filter(postings::account_id.eq(account_id))
+			.order_by(postings::id.asc())
+			.get_results(conn)
+	}
+
+	pub fn balance_by_account_id(conn: &PgConnection, account_id: i32) -> Result<Cents, Error> {
+		let total = postings::table
+			.select(sum(postings::amount))
+			.filter(postings::account_id.eq(account_id))
+			.get_result::<Option<Cents>>(conn)?;
+
+		Ok(total.unwrap_or(Cents(0)))
+	}
+
+	// What the family bank has posted for the client, negative when it paid out
+	pub fn family_bank_balance_by_client_id(
+		conn: &PgConnection,
+		client_id: i32,
+		kind: Option<TransactionKind>,
+	) -> Result<Cents, Error> {
+		let mut query = postings::table
+			.inner_join(journal_entries::table)
+			.select(sum(postings::amount))
+			.filter(postings::account_id.is_null())
+			.filter(journal_entries::client_id.eq(client_id))
+			.into_boxed();
+
+		if let Some(kind) = kind {
+			query = query.filter(journal_entries::kind.eq(kind));
+		}
+
+		let total = query.get_result::<Option<Cents>>(conn)?;
+
+		Ok(total.unwrap_or(Cents(0)))
+	}
+}
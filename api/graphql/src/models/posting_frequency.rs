@@ -0,0 +1,52 @@
+use crate::utils::dates;
+use chrono::{Duration, NaiveDateTime};
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::Pg;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::*;
+use std::io;
+
+pub const DAILY: &[u8] = b"DAILY";
+pub const WEEKLY: &[u8] = b"WEEKLY";
+pub const MONTHLY: &[u8] = b"MONTHLY";
+
+// How often the scheduler posts interest for the accounts of a client
+#[derive(Debug, Copy, Clone, FromSqlRow, AsExpression, GraphQLEnum, PartialEq)]
+#[sql_type = "Varchar"]
+pub enum PostingFrequency {
+	Daily,
+	Weekly,
+	Monthly,
+}
+
+impl PostingFrequency {
+	pub fn next_after(self, at: NaiveDateTime) -> NaiveDateTime {
+		match self {
+			PostingFrequency::Daily => at + Duration::days(1),
+			PostingFrequency::Weekly => at + Duration::days(7),
+			PostingFrequency::Monthly => dates::add_months(at, 1),
+		}
+	}
+}
+
+impl ToSql<Text, Pg> for PostingFrequency {
+	fn to_sql<W: io::Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+		let _v = match *self {
+			PostingFrequency::Daily => out.write_all(DAILY)?,
+			PostingFrequency::Weekly => out.write_all(WEEKLY)?,
+			PostingFrequency::Monthly => out.write_all(MONTHLY)?,
+		};
+		Ok(IsNull::No)
+	}
+}
+
+impl FromSql<Text, Pg> for PostingFrequency {
+	fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+		match not_none!(bytes) {
+			DAILY => Ok(PostingFrequency::Daily),
+			WEEKLY => Ok(PostingFrequency::Weekly),
+			MONTHLY => Ok(PostingFrequency::Monthly),
+			_ => Err("Unrecognized enum variant".into()),
+		}
+	}
+}
@@ -0,0 +1,150 @@
+use crate::utils::dates;
+use chrono::{Datelike, Duration, NaiveDateTime};
+use diesel::{
+	deserialize::{self, FromSql},
+	pg::Pg,
+	serialize::{self, IsNull, Output, ToSql},
+	sql_types::*,
+};
+use std::io;
+
+pub const ONCE: &[u8] = b"ONCE";
+pub const WEEKLY: &[u8] = b"WEEKLY";
+pub const FORTNIGHTLY: &[u8] = b"FORTNIGHTLY";
+pub const MONTHLY: &[u8] = b"MONTHLY";
+pub const DAY_OF_MONTH: &[u8] = b"DAY_OF_MONTH";
+
+// How often a scheduled transaction runs.
+// Monthly keeps the day it started on, DayOfMonth runs on a chosen day.
+#[derive(Debug, Copy, Clone, FromSqlRow, AsExpression, GraphQLEnum, PartialEq)]
+#[sql_type = "Varchar"]
+pub enum ScheduleFrequency {
+	Once,
+	Weekly,
+	Fortnightly,
+	Monthly,
+	DayOfMonth,
+}
+
+impl ScheduleFrequency {
+	// The first run, on or after `starts_at`
+	pub fn first_at(self, starts_at: NaiveDateTime, day_of_month: Option<u32>) -> NaiveDateTime {
+		match (self, day_of_month) {
+			(ScheduleFrequency::DayOfMonth, Some(day)) if day < starts_at.day() => {
+				next_month_on(day, starts_at)
+			},
+			(ScheduleFrequency::DayOfMonth, Some(day)) => {
+				let last_day = dates::days_in_month(starts_at.year(), starts_at.month());
+
+				starts_at.with_day(day.min(last_day)).unwrap()
+			},
+			_ => starts_at,
+		}
+	}
+
+	// The run after the one at `at`, None when there are no more.
+	// Months are counted from `starts_at` so short months don't move the day for good.
+	pub fn next_after(
+		self,
+		starts_at: NaiveDateTime,
+		day_of_month: Option<u32>,
+		at: NaiveDateTime,
+	) -> Option<NaiveDateTime> {
+		match self {
+			ScheduleFrequency::Once => None,
+			ScheduleFrequency::Weekly => Some(at + Duration::days(7)),
+			ScheduleFrequency::Fortnightly => Some(at + Duration::days(14)),
+			ScheduleFrequency::Monthly => Some(next_month_on(starts_at.day(), at)),
+			ScheduleFrequency::DayOfMonth => {
+				let day = day_of_month.unwrap_or(starts_at.day());
+
+				Some(next_month_on(day, at))
+			},
+		}
+	}
+}
+
+// `day` in the month after `at`, or the last day of that month
+fn next_month_on(day: u32, at: NaiveDateTime) -> NaiveDateTime {
+	let next = dates::add_months(at.date().with_day(1).unwrap().and_time(at.time()), 1);
+
+	let day = day.min(dates::days_in_month(next.year(), next.month()));
+
+	next.with_day(day).unwrap()
+}
+
+impl ToSql<Text, Pg> for ScheduleFrequency {
+	fn to_sql<W: io::Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+		let _v = match *self {
+			ScheduleFrequency::Once => out.write_all(ONCE)?,
+			ScheduleFrequency::Weekly => out.write_all(WEEKLY)?,
+			ScheduleFrequency::Fortnightly => out.write_all(FORTNIGHTLY)?,
+			ScheduleFrequency::Monthly => out.write_all(MONTHLY)?,
+			ScheduleFrequency::DayOfMonth => out.write_all(DAY_OF_MONTH)?,
+		};
+		Ok(IsNull::No)
+	}
+}
+
+impl FromSql<Text, Pg> for ScheduleFrequency {
+	fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+		match not_none!(bytes) {
+			ONCE => Ok(ScheduleFrequency::Once),
+			WEEKLY => Ok(ScheduleFrequency::Weekly),
+			FORTNIGHTLY => Ok(ScheduleFrequency::Fortnightly),
+			MONTHLY => Ok(ScheduleFrequency::Monthly),
+			DAY_OF_MONTH => Ok(ScheduleFrequency::DayOfMonth),
+			_ => Err("Unrecognized enum variant".into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::NaiveDate;
+
+	#[test]
+	fn monthly_keeps_the_starting_day() {
+		let starts_at = NaiveDate::from_ymd(2019, 1, 31).and_hms(9, 0, 0);
+		let february = NaiveDate::from_ymd(2019, 2, 28).and_hms(9, 0, 0);
+
+		let next = ScheduleFrequency::Monthly.next_after(starts_at, None, february);
+
+		assert_eq!(
+			next,
+			Some(NaiveDate::from_ymd(2019, 3, 31).and_hms(9, 0, 0))
+		);
+	}
+
+	#[test]
+	fn day_of_month_runs_on_the_chosen_day() {
+		let starts_at = NaiveDate::from_ymd(2019, 1, 3).and_hms(9, 0, 0);
+
+		let next = ScheduleFrequency::DayOfMonth.next_after(starts_at, Some(15), starts_at);
+
+		assert_eq!(
+			next,
+			Some(NaiveDate::from_ymd(2019, 2, 15).and_hms(9, 0, 0))
+		);
+	}
+
+	#[test]
+	fn day_of_month_starts_on_the_next_chosen_day() {
+		let starts_at = NaiveDate::from_ymd(2019, 1, 20).and_hms(9, 0, 0);
+
+		let first = ScheduleFrequency::DayOfMonth.first_at(starts_at, Some(15));
+
+		assert_eq!(first, NaiveDate::from_ymd(2019, 2, 15).and_hms(9, 0, 0));
+	}
+
+	#[test]
+	fn once_doesnt_repeat() {
+		let starts_at = NaiveDate::from_ymd(2019, 1, 3).and_hms(9, 0, 0);
+
+		assert_eq!(
+			ScheduleFrequency::Once.next_after(starts_at, None, starts_at),
+			None
+		);
+	}
+}
@@ -0,0 +1,164 @@
+use crate::models::{
+	account::Account, cents::Cents, schedule_frequency::ScheduleFrequency,
+	schema::scheduled_transactions,
+};
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// A deposit that runs by itself, e.g. a weekly allowance.
+// `day_of_month` is only used with ScheduleFrequency::DayOfMonth.
+// `next_run_at` is None once a one-off rule has run.
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(Account)]
+#[table_name = "scheduled_transactions"]
+pub struct ScheduledTransaction {
+	pub id:           i32,
+	pub created_at:   NaiveDateTime,
+	pub account_id:   i32,
+	pub amount:       Cents,
+	pub frequency:    ScheduleFrequency,
+	pub day_of_month: Option<i32>,
+	pub starts_at:    NaiveDateTime,
+	pub next_run_at:  Option<NaiveDateTime>,
+	pub archived_at:  Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "scheduled_transactions"]
+pub struct ScheduledTransactionAttrs {
+	pub account_id:   i32,
+	pub amount:       Cents,
+	pub frequency:    ScheduleFrequency,
+	pub day_of_month: Option<i32>,
+	pub starts_at:    NaiveDateTime,
+	pub next_run_at:  Option<NaiveDateTime>,
+}
+
+impl ScheduledTransaction {
+	pub fn create(
+		conn: &PgConnection,
+		attrs: ScheduledTransactionAttrs,
+	) -> Result<ScheduledTransaction, Error> {
+		diesel::insert_into(scheduled_transactions::dsl::scheduled_transactions)
+			.values(&attrs)
+			.get_result(conn)
+	}
+
+	pub fn find(conn: &PgConnection, id: i32) -> Result<ScheduledTransaction, Error> {
+		scheduled_transactions::table.find(id).first(conn)
+	}
+
+	// Locks the rule, so two runners can't run it at once
+	pub fn lock(conn: &PgConnection, id: i32) -> Result<ScheduledTransaction, Error> {
+		scheduled_transactions::table
+			.find(id)
+			.for_update()
+			.first(conn)
+	}
+
+	// Rules that haven't been removed, oldest first
+	pub fn find_by_account_id(
+		conn: &PgConnection,
+		account_id: i32,
+	) -> Result<Vec<ScheduledTransaction>, Error> {
+		let filter = scheduled_transactions::account_id
+			.eq(account_id)
+			.and(scheduled_transactions::archived_at.is_null());
+
+		scheduled_transactions::table
+			.filter(filter)
+			.order_by(scheduled_transactions::id.asc())
+			.get_results(conn)
+	}
+
+	pub fn find_due(
+		conn: &PgConnection,
+		now: NaiveDateTime,
+	) -> Result<Vec<ScheduledTransaction>, Error> {
+		let filter = scheduled_transactions::next_run_at
+			.le(now)
+			.and(scheduled_transactions::archived_at.is_null());
+
+		scheduled_transactions::table
+			.filter(filter)
+			.order_by(scheduled_transactions::next_run_at.asc())
+			.get_results(conn)
+	}
+
+	// Replaces the schedule, the account stays the same
+	pub fn update(
+		conn: &PgConnection,
+		id: i32,
+		attrs: ScheduledTransactionAttrs,
+	) -> Result<ScheduledTransaction, Error> {
+		diesel::update(scheduled_transactions::table.find(id))
+			.set((
+				scheduled_transactions::amount.eq(attrs.amount),
+				scheduled_transactions::frequency.eq(attrs.frequency),
+				scheduled_transactions::day_of_month.eq(attrs.day_of_month),
+				scheduled_transactions::starts_at.eq(attrs.starts_at),
+				scheduled_transactions::next_run_at.eq(attrs.next_run_at),
+			))
+			.get_result(conn)
+	}
+
+	pub fn set_next_run_at(
+		conn: &PgConnection,
+		id: i32,
+		next_run_at: Option<NaiveDateTime>,
+	) -> Result<ScheduledTransaction, Error> {
+		diesel::update(scheduled_transactions::table.find(id))
+			.set(scheduled_transactions::next_run_at.eq(next_run_at))
+			.get_result(conn)
+	}
+
+	pub fn archive(
+		conn: &PgConnection,
+		id: i32,
+		at: NaiveDateTime,
+	) -> Result<ScheduledTransaction, Error> {
+		diesel::update(scheduled_transactions::table.find(id))
+			.set(scheduled_transactions::archived_at.eq(at))
+			.get_result(conn)
+	}
+}
+
+#[cfg(test)]
+pub mod factories {
+	use super::*;
+	use chrono::prelude::*;
+
+	#[allow(dead_code)]
+	pub fn scheduled_transaction_attrs(account: &Account) -> ScheduledTransactionAttrs {
+		let now = Utc::now().naive_utc();
+
+		ScheduledTransactionAttrs {
+			account_id:   account.id,
+			amount:       Cents(500),
+			frequency:    ScheduleFrequency::Weekly,
+			day_of_month: None,
+			starts_at:    now,
+			next_run_at:  Some(now),
+		}
+	}
+
+	impl ScheduledTransactionAttrs {
+		pub fn save(self, conn: &PgConnection) -> ScheduledTransaction {
+			ScheduledTransaction::create(conn, self).unwrap()
+		}
+
+		pub fn frequency(self, frequency: ScheduleFrequency) -> Self {
+			ScheduledTransactionAttrs {
+				frequency: frequency,
+				..self
+			}
+		}
+
+		pub fn next_run_at(self, next_run_at: NaiveDateTime) -> Self {
+			ScheduledTransactionAttrs {
+				next_run_at: Some(next_run_at),
+				..self
+			}
+		}
+	}
+}
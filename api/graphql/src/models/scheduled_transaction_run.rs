@@ -0,0 +1,67 @@
+use crate::models::{
+	scheduled_transaction::ScheduledTransaction, schema::scheduled_transaction_runs,
+};
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// One execution of a scheduled transaction.
+// `transaction_id` is the deposit made, `error` why it wasn't.
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(ScheduledTransaction)]
+#[table_name = "scheduled_transaction_runs"]
+pub struct ScheduledTransactionRun {
+	pub id: i32,
+	pub created_at: NaiveDateTime,
+	pub scheduled_transaction_id: i32,
+	pub run_for: NaiveDateTime,
+	pub transaction_id: Option<i32>,
+	pub error: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "scheduled_transaction_runs"]
+pub struct ScheduledTransactionRunAttrs {
+	pub scheduled_transaction_id: i32,
+	pub run_for:                  NaiveDateTime,
+}
+
+impl ScheduledTransactionRun {
+	// None when the rule has already run for that date
+	pub fn start(
+		conn: &PgConnection,
+		attrs: ScheduledTransactionRunAttrs,
+	) -> Result<Option<ScheduledTransactionRun>, Error> {
+		diesel::insert_into(scheduled_transaction_runs::dsl::scheduled_transaction_runs)
+			.values(&attrs)
+			.on_conflict_do_nothing()
+			.get_result(conn)
+			.optional()
+	}
+
+	pub fn finish(
+		conn: &PgConnection,
+		id: i32,
+		transaction_id: Option<i32>,
+		error: Option<String>,
+	) -> Result<ScheduledTransactionRun, Error> {
+		diesel::update(scheduled_transaction_runs::table.find(id))
+			.set((
+				scheduled_transaction_runs::transaction_id.eq(transaction_id),
+				scheduled_transaction_runs::error.eq(error),
+			))
+			.get_result(conn)
+	}
+
+	// Latest first
+	pub fn find_by_scheduled_transaction_id(
+		conn: &PgConnection,
+		scheduled_transaction_id: i32,
+	) -> Result<Vec<ScheduledTransactionRun>, Error> {
+		scheduled_transaction_runs::table
+			.filter(
+				scheduled_transaction_runs::scheduled_transaction_id.eq(scheduled_transaction_id),
+			)
+			.order_by(scheduled_transaction_runs::run_for.desc())
+			.get_results(conn)
+	}
+}
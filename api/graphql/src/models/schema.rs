@@ -7,6 +7,18 @@ table! {
         yearly_interest -> Numeric,
         kind -> Varchar,
         state -> Varchar,
+        compounding -> Varchar,
+        matures_at -> Nullable<Timestamp>,
+        archived_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    categories (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        client_id -> Int4,
+        name -> Varchar,
     }
 }
 
@@ -15,6 +27,43 @@ table! {
         id -> Int4,
         created_at -> Timestamp,
         name -> Varchar,
+        interest_posting -> Varchar,
+        request_expiry_days -> Int4,
+    }
+}
+
+table! {
+    goals (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        account_id -> Int4,
+        name -> Varchar,
+        target -> Money,
+        target_date -> Nullable<Timestamp>,
+        image_url -> Nullable<Varchar>,
+        reached_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    interest_rates (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        account_id -> Int4,
+        effective_from -> Timestamp,
+        changed_by_user_id -> Nullable<Int4>,
+        old_yearly_interest -> Nullable<Numeric>,
+        new_yearly_interest -> Numeric,
+    }
+}
+
+table! {
+    interest_tiers (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        account_id -> Int4,
+        from_balance -> Money,
+        yearly_interest -> Numeric,
     }
 }
 
@@ -30,6 +79,73 @@ table! {
     }
 }
 
+table! {
+    journal_entries (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        client_id -> Int4,
+        kind -> Varchar,
+    }
+}
+
+table! {
+    matching_rules (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        account_id -> Int4,
+        percentage -> Numeric,
+        cap -> Nullable<Money>,
+        period -> Varchar,
+        sources -> Array<Varchar>,
+    }
+}
+
+table! {
+    postings (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        journal_entry_id -> Int4,
+        account_id -> Nullable<Int4>,
+        transaction_id -> Nullable<Int4>,
+        amount -> Money,
+    }
+}
+
+table! {
+    scheduled_transaction_runs (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        scheduled_transaction_id -> Int4,
+        run_for -> Timestamp,
+        transaction_id -> Nullable<Int4>,
+        error -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    scheduled_transactions (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        account_id -> Int4,
+        amount -> Money,
+        frequency -> Varchar,
+        day_of_month -> Nullable<Int4>,
+        starts_at -> Timestamp,
+        next_run_at -> Nullable<Timestamp>,
+        archived_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    split_rules (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        user_id -> Int4,
+        account_id -> Int4,
+        percentage -> Numeric,
+    }
+}
+
 table! {
     transaction_requests (id) {
         id -> Int4,
@@ -38,6 +154,10 @@ table! {
         kind -> Varchar,
         amount -> Money,
         state -> Varchar,
+        idempotency_key -> Nullable<Varchar>,
+        memo -> Nullable<Varchar>,
+        category_id -> Nullable<Int4>,
+        transaction_id -> Nullable<Int4>,
     }
 }
 
@@ -49,6 +169,11 @@ table! {
         kind -> Varchar,
         amount -> Money,
         balance -> Money,
+        accrued_interest -> Numeric,
+        linked_transaction_id -> Nullable<Int4>,
+        idempotency_key -> Nullable<Varchar>,
+        memo -> Nullable<Varchar>,
+        category_id -> Nullable<Int4>,
     }
 }
 
@@ -69,8 +194,58 @@ table! {
     }
 }
 
+table! {
+    withdrawal_rules (id) {
+        id -> Int4,
+        created_at -> Timestamp,
+        account_id -> Int4,
+        kind -> Varchar,
+        amount -> Nullable<Money>,
+        days -> Nullable<Int4>,
+    }
+}
+
 joinable!(users -> clients (client_id));
 joinable!(invitations -> users (user_id));
 joinable!(accounts -> users (user_id));
+joinable!(interest_rates -> accounts (account_id));
+joinable!(interest_rates -> users (changed_by_user_id));
+joinable!(interest_tiers -> accounts (account_id));
 joinable!(transactions -> accounts (account_id));
 joinable!(transaction_requests -> accounts (account_id));
+joinable!(journal_entries -> clients (client_id));
+joinable!(matching_rules -> accounts (account_id));
+joinable!(postings -> journal_entries (journal_entry_id));
+joinable!(postings -> accounts (account_id));
+joinable!(postings -> transactions (transaction_id));
+joinable!(scheduled_transactions -> accounts (account_id));
+joinable!(scheduled_transaction_runs -> scheduled_transactions (scheduled_transaction_id));
+joinable!(scheduled_transaction_runs -> transactions (transaction_id));
+joinable!(categories -> clients (client_id));
+joinable!(transactions -> categories (category_id));
+joinable!(transaction_requests -> categories (category_id));
+joinable!(transaction_requests -> transactions (transaction_id));
+joinable!(withdrawal_rules -> accounts (account_id));
+joinable!(split_rules -> users (user_id));
+joinable!(split_rules -> accounts (account_id));
+joinable!(goals -> accounts (account_id));
+
+allow_tables_to_appear_in_same_query!(
+    accounts,
+    categories,
+    clients,
+    goals,
+    interest_rates,
+    interest_tiers,
+    invitations,
+    journal_entries,
+    matching_rules,
+    postings,
+    scheduled_transaction_runs,
+    scheduled_transactions,
+    split_rules,
+    transaction_requests,
+    transactions,
+    users,
+    withdrawal_rules,
+);
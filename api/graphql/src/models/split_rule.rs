@@ -0,0 +1,67 @@
+use crate::models::{account::Account, schema::split_rules, user::User};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// The investor's deposits are shared across their accounts by `percentage`,
+// e.g. 50 to savings, 40 to spending and 10 to giving. The percentages add up to 100.
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(User)]
+#[belongs_to(Account)]
+#[table_name = "split_rules"]
+pub struct SplitRule {
+	pub id:         i32,
+	pub created_at: NaiveDateTime,
+	pub user_id:    i32,
+	pub account_id: i32,
+	pub percentage: BigDecimal,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "split_rules"]
+pub struct SplitRuleAttrs {
+	pub user_id:    i32,
+	pub account_id: i32,
+	pub percentage: BigDecimal,
+}
+
+impl SplitRule {
+	pub fn create(conn: &PgConnection, attrs: SplitRuleAttrs) -> Result<SplitRule, Error> {
+		diesel::insert_into(split_rules::dsl::split_rules)
+			.values(&attrs)
+			.get_result(conn)
+	}
+
+	// In account order
+	pub fn find_by_user_id(conn: &PgConnection, user_id: i32) -> Result<Vec<SplitRule>, Error> {
+		split_rules::table
+			.filter(split_rules::user_id.eq(user_id))
+			.order_by(split_rules::account_id.asc())
+			.get_results(conn)
+	}
+
+	pub fn delete_by_user_id(conn: &PgConnection, user_id: i32) -> Result<usize, Error> {
+		diesel::delete(split_rules::table.filter(split_rules::user_id.eq(user_id))).execute(conn)
+	}
+}
+
+#[cfg(test)]
+pub mod factories {
+	use super::*;
+	use bigdecimal::FromPrimitive;
+
+	#[allow(dead_code)]
+	pub fn split_rule_attrs(account: &Account, percentage: f32) -> SplitRuleAttrs {
+		SplitRuleAttrs {
+			user_id:    account.user_id,
+			account_id: account.id,
+			percentage: BigDecimal::from_f32(percentage).unwrap(),
+		}
+	}
+
+	impl SplitRuleAttrs {
+		pub fn save(self, conn: &PgConnection) -> SplitRule {
+			SplitRule::create(conn, self).unwrap()
+		}
+	}
+}
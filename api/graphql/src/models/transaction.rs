@@ -1,26 +1,49 @@
 pub use crate::models::transaction_kind::TransactionKind;
-use crate::models::{cents::Cents, schema::transactions};
+use crate::models::{
+	cents::Cents,
+	schema::{postings, transactions},
+};
+use bigdecimal::BigDecimal;
 use chrono::NaiveDateTime;
 use diesel::{self, pg::PgConnection, prelude::*, result::Error};
 use validator::Validate;
 
+// `amount` of a withdrawal is positive, transfers out and reversals are negative,
+// `balance_change` says how any of them moved the balance.
+// `accrued_interest` is interest earned but not credited yet, in cents.
+// `linked_transaction_id` is the other side of a transfer,
+// or the transaction undone by a reversal.
+// `idempotency_key` is sent by the client so a retried call isn't recorded twice.
+// `memo` and `category_id` say what the money was for.
 #[derive(Debug, Queryable, Clone, PartialEq)]
 pub struct Transaction {
-	pub id:         i32,
-	pub created_at: NaiveDateTime,
-	pub account_id: i32,
-	pub kind:       TransactionKind,
-	pub amount:     Cents,
-	pub balance:    Cents,
+	pub id:                    i32,
+	pub created_at:            NaiveDateTime,
+	pub account_id:            i32,
+	pub kind:                  TransactionKind,
+	pub amount:                Cents,
+	pub balance:               Cents,
+	pub accrued_interest:      BigDecimal,
+	pub linked_transaction_id: Option<i32>,
+	pub idempotency_key:       Option<String>,
+	pub memo:                  Option<String>,
+	pub category_id:           Option<i32>,
 }
 
+// `created_at` defaults to now, it is only set to back-date a transaction
 #[derive(Insertable, Validate, AsExpression)]
 #[table_name = "transactions"]
 pub struct TransactionAttrs {
-	pub account_id: i32,
-	pub kind:       TransactionKind,
-	pub amount:     Cents,
-	pub balance:    Cents,
+	pub account_id:            i32,
+	pub kind:                  TransactionKind,
+	pub amount:                Cents,
+	pub balance:               Cents,
+	pub accrued_interest:      BigDecimal,
+	pub linked_transaction_id: Option<i32>,
+	pub created_at:            Option<NaiveDateTime>,
+	pub idempotency_key:       Option<String>,
+	pub memo:                  Option<String>,
+	pub category_id:           Option<i32>,
 }
 
 impl Transaction {
@@ -31,6 +54,91 @@ impl Transaction {
 			.get_result(conn)
 	}
 
+	pub fn find(conn: &PgConnection, id: i32) -> Result<Transaction, Error> {
+		transactions::table.find(id).first(conn)
+	}
+
+	// The transaction made by an earlier call with the same key
+	pub fn find_by_idempotency_key(
+		conn: &PgConnection,
+		account_id: i32,
+		key: &str,
+	) -> Result<Option<Transaction>, Error> {
+		let filter = transactions::account_id
+			.eq(account_id)
+			.and(transactions::idempotency_key.eq(key));
+
+		transactions::table.filter(filter).first(conn).optional()
+	}
+
+	// The reversal undoing the transaction, if any
+	pub fn find_reversal(conn: &PgConnection, id: i32) -> Result<Option<Transaction>, Error> {
+		let filter = transactions::linked_transaction_id
+			.eq(id)
+			.and(transactions::kind.eq(TransactionKind::Reversal));
+
+		transactions::table.filter(filter).first(conn).optional()
+	}
+
+	// The parents' match for a deposit, if any
+	pub fn find_match(conn: &PgConnection, id: i32) -> Result<Option<Transaction>, Error> {
+		let filter = transactions::linked_transaction_id
+			.eq(id)
+			.and(transactions::kind.eq(TransactionKind::Match));
+
+		transactions::table.filter(filter).first(conn).optional()
+	}
+
+	// The transactions recorded together with this one in a journal entry, itself included,
+	// e.g. the accounts a deposit was split across or both legs of a transfer
+	pub fn find_grouped(conn: &PgConnection, id: i32) -> Result<Vec<Transaction>, Error> {
+		let entry_ids = postings::table
+			.select(postings::journal_entry_id)
+			.filter(postings::transaction_id.eq(id));
+
+		let transaction_ids = postings::table
+			.select(postings::transaction_id)
+			.filter(postings::journal_entry_id.eq_any(entry_ids));
+
+		transactions::table
+			.filter(transactions::id.nullable().eq_any(transaction_ids))
+			.order_by(transactions::id.asc())
+			.get_results(conn)
+	}
+
+	// How much the transaction moved the balance
+	pub fn balance_change(&self) -> Cents {
+		let Cents(amount) = self.amount;
+
+		match self.kind {
+			TransactionKind::Withdrawal => Cents(-amount),
+			// Transfers out and reversals are already signed
+			_ => Cents(amount),
+		}
+	}
+
+	pub fn set_balance(conn: &PgConnection, id: i32, balance: Cents) -> Result<Transaction, Error> {
+		diesel::update(transactions::table.find(id))
+			.set(transactions::balance.eq(balance))
+			.get_result(conn)
+	}
+
+	pub fn set_accrued_interest(
+		conn: &PgConnection,
+		id: i32,
+		accrued_interest: BigDecimal,
+	) -> Result<Transaction, Error> {
+		diesel::update(transactions::table.find(id))
+			.set(transactions::accrued_interest.eq(accrued_interest))
+			.get_result(conn)
+	}
+
+	pub fn link(conn: &PgConnection, id: i32, linked_id: i32) -> Result<Transaction, Error> {
+		diesel::update(transactions::table.find(id))
+			.set(transactions::linked_transaction_id.eq(linked_id))
+			.get_result(conn)
+	}
+
 	#[allow(dead_code)]
 	pub fn find_last_by_account_id(
 		conn: &PgConnection,
@@ -40,10 +148,73 @@ impl Transaction {
 
 		transactions::table
 			.filter(filter)
-			.order_by(transactions::created_at.desc())
+			.order_by((transactions::created_at.desc(), transactions::id.desc()))
 			.get_result(conn)
 	}
 
+	// The last transaction at or before `at`
+	pub fn find_last_by_account_id_at(
+		conn: &PgConnection,
+		account_id: i32,
+		at: NaiveDateTime,
+	) -> Result<Option<Transaction>, Error> {
+		let filter = transactions::account_id
+			.eq(account_id)
+			.and(transactions::created_at.le(at));
+
+		transactions::table
+			.filter(filter)
+			.order_by((transactions::created_at.desc(), transactions::id.desc()))
+			.first(conn)
+			.optional()
+	}
+
+	#[allow(dead_code)]
+	pub fn find_last_by_account_id_and_kind(
+		conn: &PgConnection,
+		account_id: i32,
+		kind: TransactionKind,
+	) -> Result<Transaction, Error> {
+		let filter = transactions::account_id
+			.eq(account_id)
+			.and(transactions::kind.eq(kind));
+
+		transactions::table
+			.filter(filter)
+			.order_by((transactions::created_at.desc(), transactions::id.desc()))
+			.first(conn)
+	}
+
+	#[allow(dead_code)]
+	pub fn total_interest_by_account_id(
+		conn: &PgConnection,
+		account_id: i32,
+	) -> Result<Cents, Error> {
+		let filter = transactions::account_id
+			.eq(account_id)
+			.and(transactions::kind.eq(TransactionKind::Interest));
+
+		let amounts = transactions::table
+			.select(transactions::amount)
+			.filter(filter)
+			.load::<Cents>(conn)?;
+
+		Ok(amounts
+			.into_iter()
+			.fold(Cents(0), |total, amount| total + amount))
+	}
+
+	// Every transaction of the account, oldest first
+	pub fn find_all_by_account_id(
+		conn: &PgConnection,
+		account_id: i32,
+	) -> Result<Vec<Transaction>, Error> {
+		transactions::table
+			.filter(transactions::account_id.eq(account_id))
+			.order_by((transactions::created_at.asc(), transactions::id.asc()))
+			.get_results(conn)
+	}
+
 	#[allow(dead_code)]
 	pub fn find_by_account_id(
 		conn: &PgConnection,
@@ -54,6 +225,23 @@ impl Transaction {
 			.eq(account_id)
 			.and(transactions::created_at.ge(since));
 
+		transactions::table
+			.filter(filter)
+			.order_by((transactions::created_at.asc(), transactions::id.asc()))
+			.get_results(conn)
+	}
+
+	pub fn find_by_account_id_and_category_id(
+		conn: &PgConnection,
+		account_id: i32,
+		since: NaiveDateTime,
+		category_id: i32,
+	) -> Result<Vec<Transaction>, Error> {
+		let filter = transactions::account_id
+			.eq(account_id)
+			.and(transactions::created_at.ge(since))
+			.and(transactions::category_id.eq(category_id));
+
 		transactions::table
 			.filter(filter)
 			.order_by(transactions::created_at.asc())
@@ -64,30 +252,99 @@ impl Transaction {
 #[cfg(test)]
 pub mod factories {
 	use super::*;
-	// use bigdecimal::FromPrimitive;
-	use crate::models::account::Account;
+	use crate::models::{
+		account::Account,
+		journal_entry::{JournalEntry, JournalEntryAttrs},
+		posting::{Posting, PostingAttrs},
+		user::User,
+	};
+	use bigdecimal::FromPrimitive;
+	use std::str::FromStr;
 
 	#[allow(dead_code)]
 	pub fn transaction_attrs(account: &Account) -> TransactionAttrs {
 		let balance = Cents(0);
 
 		TransactionAttrs {
-			account_id: account.id,
-			kind:       TransactionKind::Deposit,
-			amount:     Cents(0),
-			balance:    balance,
+			account_id:            account.id,
+			kind:                  TransactionKind::Deposit,
+			amount:                Cents(0),
+			balance:               balance,
+			accrued_interest:      BigDecimal::from_u8(0).unwrap(),
+			linked_transaction_id: None,
+			created_at:            None,
+			idempotency_key:       None,
+			memo:                  None,
+			category_id:           None,
 		}
 	}
 
 	impl TransactionAttrs {
+		// Posts the amount against the family bank, like a recorded transaction.
+		// The balance is stored as given, so it has to agree with the amounts.
 		pub fn save(self, conn: &PgConnection) -> Transaction {
-			Transaction::create(conn, self).unwrap()
+			let transaction = Transaction::create(conn, self).unwrap();
+
+			let change = transaction.balance_change();
+
+			let account = Account::find(conn, transaction.account_id).unwrap();
+			let user = User::find(conn, account.user_id).unwrap();
+
+			let entry_attrs = JournalEntryAttrs {
+				client_id: user.client_id,
+				kind:      transaction.kind,
+			};
+
+			let entry = JournalEntry::create(conn, entry_attrs).unwrap();
+
+			let postings = vec![
+				(Some(transaction.account_id), Some(transaction.id), change),
+				(None, None, Cents(0) - change),
+			];
+
+			for (account_id, transaction_id, amount) in postings {
+				let posting_attrs = PostingAttrs {
+					journal_entry_id: entry.id,
+					account_id:       account_id,
+					transaction_id:   transaction_id,
+					amount:           amount,
+				};
+
+				Posting::create(conn, posting_attrs).unwrap();
+			}
+
+			transaction
+		}
+
+		pub fn kind(mut self, kind: TransactionKind) -> TransactionAttrs {
+			self.kind = kind;
+			self
+		}
+
+		pub fn amount(mut self, amount: i64) -> TransactionAttrs {
+			self.amount = Cents(amount);
+			self
 		}
 
 		pub fn balance(mut self, balance: i64) -> TransactionAttrs {
 			self.balance = Cents(balance);
 			self
 		}
+
+		pub fn accrued_interest(mut self, accrued_interest: &str) -> TransactionAttrs {
+			self.accrued_interest = BigDecimal::from_str(accrued_interest).unwrap();
+			self
+		}
+
+		pub fn idempotency_key(mut self, key: &str) -> TransactionAttrs {
+			self.idempotency_key = Some(key.to_string());
+			self
+		}
+
+		pub fn created_at(mut self, created_at: NaiveDateTime) -> TransactionAttrs {
+			self.created_at = Some(created_at);
+			self
+		}
 	}
 
 	impl Transaction {
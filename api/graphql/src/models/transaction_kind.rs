@@ -7,6 +7,9 @@ use std::io;
 pub const DEPOSIT: &[u8] = b"DEPOSIT";
 pub const WITHDRAWAL: &[u8] = b"WITHDRAWAL";
 pub const INTEREST: &[u8] = b"INTEREST";
+pub const TRANSFER: &[u8] = b"TRANSFER";
+pub const REVERSAL: &[u8] = b"REVERSAL";
+pub const MATCH: &[u8] = b"MATCH";
 
 #[derive(Debug, Copy, Clone, FromSqlRow, AsExpression, GraphQLEnum, PartialEq)]
 #[sql_type = "Varchar"]
@@ -14,6 +17,12 @@ pub enum TransactionKind {
 	Deposit,
 	Withdrawal,
 	Interest,
+	// Money moved between two accounts, the amount is negative on the sending side
+	Transfer,
+	// Undoes a mistaken transaction, the amount is the signed change to the balance
+	Reversal,
+	// The parents' contribution towards a deposit, linked to it
+	Match,
 }
 
 impl ToSql<Text, Pg> for TransactionKind {
@@ -22,6 +31,9 @@ impl ToSql<Text, Pg> for TransactionKind {
 			TransactionKind::Deposit => out.write_all(DEPOSIT)?,
 			TransactionKind::Withdrawal => out.write_all(WITHDRAWAL)?,
 			TransactionKind::Interest => out.write_all(INTEREST)?,
+			TransactionKind::Transfer => out.write_all(TRANSFER)?,
+			TransactionKind::Reversal => out.write_all(REVERSAL)?,
+			TransactionKind::Match => out.write_all(MATCH)?,
 		};
 		Ok(IsNull::No)
 	}
@@ -33,6 +45,9 @@ impl FromSql<Text, Pg> for TransactionKind {
 			DEPOSIT => Ok(TransactionKind::Deposit),
 			WITHDRAWAL => Ok(TransactionKind::Withdrawal),
 			INTEREST => Ok(TransactionKind::Interest),
+			TRANSFER => Ok(TransactionKind::Transfer),
+			REVERSAL => Ok(TransactionKind::Reversal),
+			MATCH => Ok(TransactionKind::Match),
 			_ => Err("Unrecognized enum variant".into()),
 		}
 	}
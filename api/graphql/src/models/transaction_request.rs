@@ -1,4 +1,6 @@
-use crate::models::{account::Account, cents::Cents, schema::transaction_requests};
+use crate::models::{
+	account::Account, cents::Cents, client::Client, schema::transaction_requests, user::User,
+};
 pub use crate::models::{
 	transaction_kind::TransactionKind, transaction_request_state::TransactionRequestState,
 };
@@ -6,25 +8,33 @@ use chrono::NaiveDateTime;
 use diesel::{self, pg::PgConnection, prelude::*, result::Error};
 use validator::Validate;
 
+// `transaction_id` is the deposit or withdrawal made on approval
 #[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
 #[belongs_to(Account)]
 #[table_name = "transaction_requests"]
 pub struct TransactionRequest {
-	pub id:         i32,
-	pub created_at: NaiveDateTime,
-	pub account_id: i32,
-	pub kind:       TransactionKind,
-	pub amount:     Cents,
-	pub state:      TransactionRequestState,
+	pub id:              i32,
+	pub created_at:      NaiveDateTime,
+	pub account_id:      i32,
+	pub kind:            TransactionKind,
+	pub amount:          Cents,
+	pub state:           TransactionRequestState,
+	pub idempotency_key: Option<String>,
+	pub memo:            Option<String>,
+	pub category_id:     Option<i32>,
+	pub transaction_id:  Option<i32>,
 }
 
 #[derive(Insertable, Validate, AsExpression)]
 #[table_name = "transaction_requests"]
 pub struct TransactionRequestAttrs {
-	pub account_id: i32,
-	pub kind:       TransactionKind,
-	pub amount:     Cents,
-	pub state:      TransactionRequestState,
+	pub account_id:      i32,
+	pub kind:            TransactionKind,
+	pub amount:          Cents,
+	pub state:           TransactionRequestState,
+	pub idempotency_key: Option<String>,
+	pub memo:            Option<String>,
+	pub category_id:     Option<i32>,
 }
 
 impl TransactionRequest {
@@ -42,4 +52,60 @@ impl TransactionRequest {
 	pub fn find(conn: &PgConnection, id: i32) -> Result<TransactionRequest, Error> {
 		transaction_requests::table.find(id).get_result(conn)
 	}
+
+	pub fn lock(conn: &PgConnection, id: i32) -> Result<TransactionRequest, Error> {
+		transaction_requests::table
+			.find(id)
+			.for_update()
+			.get_result(conn)
+	}
+
+	pub fn resolve(
+		conn: &PgConnection,
+		id: i32,
+		state: TransactionRequestState,
+		transaction_id: Option<i32>,
+	) -> Result<TransactionRequest, Error> {
+		diesel::update(transaction_requests::table.find(id))
+			.set((
+				transaction_requests::state.eq(state),
+				transaction_requests::transaction_id.eq(transaction_id),
+			))
+			.get_result(conn)
+	}
+
+	// Requests of the client's accounts still pending since before `before`
+	pub fn find_pending_by_client(
+		conn: &PgConnection,
+		client: &Client,
+		before: NaiveDateTime,
+	) -> Result<Vec<TransactionRequest>, Error> {
+		let users = User::belonging_to(client).load::<User>(conn)?;
+
+		let accounts = Account::belonging_to(&users).load::<Account>(conn)?;
+
+		let filter = transaction_requests::state
+			.eq(TransactionRequestState::Pending)
+			.and(transaction_requests::created_at.lt(before));
+
+		TransactionRequest::belonging_to(&accounts)
+			.filter(filter)
+			.get_results(conn)
+	}
+
+	// The request made by an earlier call with the same key
+	pub fn find_by_idempotency_key(
+		conn: &PgConnection,
+		account_id: i32,
+		key: &str,
+	) -> Result<Option<TransactionRequest>, Error> {
+		let filter = transaction_requests::account_id
+			.eq(account_id)
+			.and(transaction_requests::idempotency_key.eq(key));
+
+		transaction_requests::table
+			.filter(filter)
+			.first(conn)
+			.optional()
+	}
 }
@@ -5,6 +5,8 @@ use diesel::sql_types::*;
 use std::io;
 
 pub const APPROVED: &[u8] = b"APPROVED";
+pub const CANCELLED: &[u8] = b"CANCELLED";
+pub const EXPIRED: &[u8] = b"EXPIRED";
 pub const PENDING: &[u8] = b"PENDING";
 pub const REJECTED: &[u8] = b"REJECTED";
 
@@ -14,12 +16,29 @@ pub enum TransactionRequestState {
 	Pending,
 	Approved,
 	Rejected,
+	// Withdrawn by the account holder
+	Cancelled,
+	// Left pending for longer than the client allows
+	Expired,
+}
+
+impl TransactionRequestState {
+	// A request only leaves Pending, and only once
+	pub fn can_change_to(self, to: TransactionRequestState) -> bool {
+		match (self, to) {
+			(TransactionRequestState::Pending, TransactionRequestState::Pending) => false,
+			(TransactionRequestState::Pending, _) => true,
+			_ => false,
+		}
+	}
 }
 
 impl ToSql<Text, Pg> for TransactionRequestState {
 	fn to_sql<W: io::Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
 		let _v = match *self {
 			TransactionRequestState::Approved => out.write_all(APPROVED)?,
+			TransactionRequestState::Cancelled => out.write_all(CANCELLED)?,
+			TransactionRequestState::Expired => out.write_all(EXPIRED)?,
 			TransactionRequestState::Pending => out.write_all(PENDING)?,
 			TransactionRequestState::Rejected => out.write_all(REJECTED)?,
 		};
@@ -31,9 +50,36 @@ impl FromSql<Text, Pg> for TransactionRequestState {
 	fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
 		match not_none!(bytes) {
 			APPROVED => Ok(TransactionRequestState::Approved),
+			CANCELLED => Ok(TransactionRequestState::Cancelled),
+			EXPIRED => Ok(TransactionRequestState::Expired),
 			PENDING => Ok(TransactionRequestState::Pending),
 			REJECTED => Ok(TransactionRequestState::Rejected),
 			_ => Err("Unrecognized enum variant".into()),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn only_pending_can_be_resolved() {
+		let pending = TransactionRequestState::Pending;
+		let approved = TransactionRequestState::Approved;
+		let rejected = TransactionRequestState::Rejected;
+		let cancelled = TransactionRequestState::Cancelled;
+		let expired = TransactionRequestState::Expired;
+
+		assert!(pending.can_change_to(approved));
+		assert!(pending.can_change_to(rejected));
+		assert!(!pending.can_change_to(pending));
+		assert!(!approved.can_change_to(rejected));
+		assert!(!rejected.can_change_to(approved));
+		assert!(!approved.can_change_to(pending));
+		assert!(pending.can_change_to(cancelled));
+		assert!(pending.can_change_to(expired));
+		assert!(!cancelled.can_change_to(approved));
+		assert!(!expired.can_change_to(cancelled));
+	}
+}
@@ -130,6 +130,17 @@ impl User {
 			.filter(users::password_reset_token.eq(token))
 			.first::<User>(conn)
 	}
+
+	pub fn find_admins_by_client_id(
+		conn: &PgConnection,
+		client_id: i32,
+	) -> Result<Vec<User>, Error> {
+		let filter = users::client_id
+			.eq(client_id)
+			.and(users::role.eq(Role::Admin));
+
+		users::table.filter(filter).load::<User>(conn)
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,95 @@
+pub use crate::models::withdrawal_rule_kind::WithdrawalRuleKind;
+use crate::models::{account::Account, cents::Cents, schema::withdrawal_rules};
+use chrono::NaiveDateTime;
+use diesel::{self, pg::PgConnection, prelude::*, result::Error};
+
+// A limit on withdrawals from the account, at most one of each kind.
+// `amount` is set for every kind but CoolingOff, which sets `days`.
+#[derive(Identifiable, Debug, Queryable, Associations, Clone, PartialEq)]
+#[belongs_to(Account)]
+#[table_name = "withdrawal_rules"]
+pub struct WithdrawalRule {
+	pub id:         i32,
+	pub created_at: NaiveDateTime,
+	pub account_id: i32,
+	pub kind:       WithdrawalRuleKind,
+	pub amount:     Option<Cents>,
+	pub days:       Option<i32>,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "withdrawal_rules"]
+pub struct WithdrawalRuleAttrs {
+	pub account_id: i32,
+	pub kind:       WithdrawalRuleKind,
+	pub amount:     Option<Cents>,
+	pub days:       Option<i32>,
+}
+
+impl WithdrawalRule {
+	pub fn create(
+		conn: &PgConnection,
+		attrs: WithdrawalRuleAttrs,
+	) -> Result<WithdrawalRule, Error> {
+		diesel::insert_into(withdrawal_rules::dsl::withdrawal_rules)
+			.values(&attrs)
+			.get_result(conn)
+	}
+
+	// Oldest first
+	pub fn find_by_account_id(
+		conn: &PgConnection,
+		account_id: i32,
+	) -> Result<Vec<WithdrawalRule>, Error> {
+		withdrawal_rules::table
+			.filter(withdrawal_rules::account_id.eq(account_id))
+			.order_by(withdrawal_rules::id.asc())
+			.get_results(conn)
+	}
+
+	pub fn delete_by_account_id(conn: &PgConnection, account_id: i32) -> Result<usize, Error> {
+		diesel::delete(withdrawal_rules::table.filter(withdrawal_rules::account_id.eq(account_id)))
+			.execute(conn)
+	}
+}
+
+#[cfg(test)]
+pub mod factories {
+	use super::*;
+
+	#[allow(dead_code)]
+	pub fn withdrawal_rule_attrs(account: &Account) -> WithdrawalRuleAttrs {
+		WithdrawalRuleAttrs {
+			account_id: account.id,
+			kind:       WithdrawalRuleKind::WeeklyLimit,
+			amount:     Some(Cents(1_000)),
+			days:       None,
+		}
+	}
+
+	impl WithdrawalRuleAttrs {
+		pub fn save(self, conn: &PgConnection) -> WithdrawalRule {
+			WithdrawalRule::create(conn, self).unwrap()
+		}
+
+		pub fn kind(self, kind: WithdrawalRuleKind) -> Self {
+			WithdrawalRuleAttrs { kind: kind, ..self }
+		}
+
+		pub fn amount(self, cents: i64) -> Self {
+			WithdrawalRuleAttrs {
+				amount: Some(Cents(cents)),
+				..self
+			}
+		}
+
+		// CoolingOff rules have days instead of an amount
+		pub fn days(self, days: i32) -> Self {
+			WithdrawalRuleAttrs {
+				amount: None,
+				days: Some(days),
+				..self
+			}
+		}
+	}
+}
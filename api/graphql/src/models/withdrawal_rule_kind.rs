@@ -0,0 +1,65 @@
+use diesel::{
+	deserialize::{self, FromSql},
+	pg::Pg,
+	serialize::{self, IsNull, Output, ToSql},
+	sql_types::*,
+};
+use std::io;
+
+pub const WEEKLY_LIMIT: &[u8] = b"WEEKLY_LIMIT";
+pub const MONTHLY_LIMIT: &[u8] = b"MONTHLY_LIMIT";
+pub const MINIMUM_BALANCE: &[u8] = b"MINIMUM_BALANCE";
+pub const COOLING_OFF: &[u8] = b"COOLING_OFF";
+pub const APPROVAL_ABOVE: &[u8] = b"APPROVAL_ABOVE";
+
+// The limits take an amount withdrawn over the last 7 or 30 days.
+// CoolingOff takes a number of days after a deposit.
+// ApprovalAbove sends larger withdrawals through requestWithdraw.
+#[derive(Debug, Copy, Clone, FromSqlRow, AsExpression, GraphQLEnum, PartialEq)]
+#[sql_type = "Varchar"]
+pub enum WithdrawalRuleKind {
+	WeeklyLimit,
+	MonthlyLimit,
+	MinimumBalance,
+	CoolingOff,
+	ApprovalAbove,
+}
+
+impl WithdrawalRuleKind {
+	// As stored and as named in GraphQL
+	pub fn name(self) -> &'static str {
+		match self {
+			WithdrawalRuleKind::WeeklyLimit => "WEEKLY_LIMIT",
+			WithdrawalRuleKind::MonthlyLimit => "MONTHLY_LIMIT",
+			WithdrawalRuleKind::MinimumBalance => "MINIMUM_BALANCE",
+			WithdrawalRuleKind::CoolingOff => "COOLING_OFF",
+			WithdrawalRuleKind::ApprovalAbove => "APPROVAL_ABOVE",
+		}
+	}
+}
+
+impl ToSql<Text, Pg> for WithdrawalRuleKind {
+	fn to_sql<W: io::Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+		let _v = match *self {
+			WithdrawalRuleKind::WeeklyLimit => out.write_all(WEEKLY_LIMIT)?,
+			WithdrawalRuleKind::MonthlyLimit => out.write_all(MONTHLY_LIMIT)?,
+			WithdrawalRuleKind::MinimumBalance => out.write_all(MINIMUM_BALANCE)?,
+			WithdrawalRuleKind::CoolingOff => out.write_all(COOLING_OFF)?,
+			WithdrawalRuleKind::ApprovalAbove => out.write_all(APPROVAL_ABOVE)?,
+		};
+		Ok(IsNull::No)
+	}
+}
+
+impl FromSql<Text, Pg> for WithdrawalRuleKind {
+	fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+		match not_none!(bytes) {
+			WEEKLY_LIMIT => Ok(WithdrawalRuleKind::WeeklyLimit),
+			MONTHLY_LIMIT => Ok(WithdrawalRuleKind::MonthlyLimit),
+			MINIMUM_BALANCE => Ok(WithdrawalRuleKind::MinimumBalance),
+			COOLING_OFF => Ok(WithdrawalRuleKind::CoolingOff),
+			APPROVAL_ABOVE => Ok(WithdrawalRuleKind::ApprovalAbove),
+			_ => Err("Unrecognized enum variant".into()),
+		}
+	}
+}
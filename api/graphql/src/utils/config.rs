@@ -15,6 +15,7 @@ pub struct Config {
 	pub aws_sns_email_topic_arn:   String,
 	pub client_host:    String,
 	pub database_url:   String,
+	pub jobs_interval_in_seconds: u64,
 	pub observer_email: String,
 	pub system_jwt:     String,
 }
@@ -69,6 +70,12 @@ pub fn get() -> Result<Config, Error> {
 	let database_url =
 		env::var(names.database_url).map_err(|_| format_err!("database_url not found"))?;
 
+	// How often the background jobs run, defaults to every hour
+	let jobs_interval_in_seconds = env::var("JOBS_INTERVAL_SECONDS")
+		.ok()
+		.and_then(|s| s.parse().ok())
+		.unwrap_or(3600);
+
 	let observer_email =
 		env::var("OBSERVER_EMAIL").map_err(|_| format_err!("OBSERVER_EMAIL not found"))?;
 
@@ -81,6 +88,7 @@ pub fn get() -> Result<Config, Error> {
 		aws_sns_email_topic_arn,
 		client_host,
 		database_url,
+		jobs_interval_in_seconds,
 		observer_email,
 		system_jwt,
 	};
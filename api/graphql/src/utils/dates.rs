@@ -0,0 +1,76 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+
+// Adds calendar months, keeping the time of day.
+// The day is clamped to the end of the month e.g. 31 Jan + 1 month = 28 Feb
+pub fn add_months(at: NaiveDateTime, months: u32) -> NaiveDateTime {
+	let total = at.month0() + months;
+	let year = at.year() + (total / 12) as i32;
+	let month = total % 12 + 1;
+	let day = at.day().min(days_in_month(year, month));
+
+	NaiveDate::from_ymd(year, month, day).and_time(at.time())
+}
+
+// POSIX time in seconds sent by a client.
+// None when it is out of range, rather than panicking.
+pub fn from_seconds(seconds: f64) -> Option<NaiveDateTime> {
+	// Also rules out NaN, casting it or a huge float to i64 isn't defined
+	if !(seconds >= i64::min_value() as f64 && seconds < i64::max_value() as f64) {
+		return None;
+	}
+
+	NaiveDateTime::from_timestamp_opt(seconds as i64, 0)
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+	let (next_year, next_month) = if month == 12 {
+		(year + 1, 1)
+	} else {
+		(year, month + 1)
+	};
+
+	NaiveDate::from_ymd(next_year, next_month, 1).pred().day()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn it_adds_months() {
+		let at = NaiveDate::from_ymd(2019, 1, 15).and_hms(10, 0, 0);
+		let expected = NaiveDate::from_ymd(2019, 2, 15).and_hms(10, 0, 0);
+
+		assert_eq!(add_months(at, 1), expected);
+	}
+
+	#[test]
+	fn it_rolls_over_the_year() {
+		let at = NaiveDate::from_ymd(2019, 12, 1).and_hms(0, 0, 0);
+		let expected = NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0);
+
+		assert_eq!(add_months(at, 1), expected);
+	}
+
+	#[test]
+	fn it_converts_seconds() {
+		let expected = NaiveDate::from_ymd(2019, 6, 8).and_hms(13, 20, 0);
+
+		assert_eq!(from_seconds(1_560_000_000.0), Some(expected));
+	}
+
+	#[test]
+	fn it_rejects_seconds_out_of_range() {
+		assert_eq!(from_seconds(1e20), None);
+		assert_eq!(from_seconds(std::f64::NAN), None);
+		assert_eq!(from_seconds(i64::max_value() as f64 / 2.0), None);
+	}
+
+	#[test]
+	fn it_clamps_to_the_end_of_the_month() {
+		let at = NaiveDate::from_ymd(2020, 1, 31).and_hms(0, 0, 0);
+		let expected = NaiveDate::from_ymd(2020, 2, 29).and_hms(0, 0, 0);
+
+		assert_eq!(add_months(at, 1), expected);
+	}
+}
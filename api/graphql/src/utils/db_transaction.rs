@@ -0,0 +1,48 @@
+use diesel::{
+	connection::TransactionManager,
+	pg::PgConnection,
+	result::{DatabaseErrorKind, Error as DieselError},
+	Connection,
+};
+use failure::Error;
+
+// Attempts before giving up on a transaction that keeps conflicting
+pub const MAX_ATTEMPTS: u32 = 5;
+
+// Runs `f` in a serializable Postgres transaction.
+//
+// Postgres aborts one of two conflicting serializable transactions,
+// in that case the whole transaction is run again, up to MAX_ATTEMPTS times.
+// When already inside a transaction `f` runs in a savepoint instead,
+// and the outermost transaction is the one that retries.
+//
+// `f` must only have effects on the database, e.g. send emails after this returns.
+pub fn serializable<T, F>(conn: &PgConnection, f: F) -> Result<T, Error>
+where
+	F: Fn() -> Result<T, Error>,
+{
+	if conn.transaction_manager().get_transaction_depth() > 0 {
+		return conn.transaction(|| f());
+	}
+
+	let mut attempt = 1;
+
+	loop {
+		let result = conn.build_transaction().serializable().run(|| f());
+
+		match result {
+			Err(ref e) if is_serialization_failure(e) && attempt < MAX_ATTEMPTS => {
+				info!("Retrying serialization failure, attempt {}", attempt);
+				attempt += 1;
+			},
+			_ => return result,
+		}
+	}
+}
+
+fn is_serialization_failure(error: &Error) -> bool {
+	match error.downcast_ref::<DieselError>() {
+		Some(DieselError::DatabaseError(DatabaseErrorKind::SerializationFailure, _)) => true,
+		_ => false,
+	}
+}
@@ -1,5 +1,7 @@
 pub mod config;
+pub mod dates;
 pub mod db_conn;
+pub mod db_transaction;
 pub mod links;
 pub mod mutations;
 pub mod seed_db;
@@ -1,3 +1,4 @@
+use crate::actions::accounts::check_withdrawal_rules::Violations;
 use failure::Error;
 use validator::{ValidationErrors, ValidationErrorsKind};
 
@@ -25,8 +26,21 @@ fn kind_to_string(kind: &ValidationErrorsKind) -> String {
 	format!("{:?}", kind)
 }
 
+// Broken withdrawal rules are keyed by the rule's kind, anything else is "other"
 #[allow(dead_code)]
 pub fn failure_to_mutation_errors(error: Error) -> Vec<MutationError> {
+	if let Some(Violations(violations)) = error.downcast_ref::<Violations>() {
+		return violations
+			.iter()
+			.map(|violation| {
+				MutationError {
+					key:      violation.kind.name().to_owned(),
+					messages: vec![violation.message.clone()],
+				}
+			})
+			.collect();
+	}
+
 	let mutation_error = MutationError {
 		key:      "other".to_owned(),
 		messages: vec![error.to_string()],
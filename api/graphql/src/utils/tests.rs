@@ -3,11 +3,18 @@ use crate::{
 		self,
 		account::Account,
 		client::Client,
+		schema::{
+			accounts, categories, clients, goals, interest_rates, interest_tiers, invitations,
+			journal_entries, matching_rules, postings, scheduled_transaction_runs,
+			scheduled_transactions, split_rules, transaction_requests, transactions, users,
+			withdrawal_rules,
+		},
 		user::{Role, User},
 	},
 	utils::db_conn,
 };
-use diesel::{pg::PgConnection, result::Error, Connection};
+use diesel::{self, dsl::max, pg::PgConnection, prelude::*, result::Error};
+use std::sync::Mutex;
 
 #[allow(dead_code)]
 pub fn with_db<F>(f: F) -> ()
@@ -22,21 +29,138 @@ where
 	});
 }
 
+lazy_static! {
+	// Tests that commit run one at a time, so each one only removes its own rows
+	static ref CLEANER_LOCK: Mutex<()> = Mutex::new(());
+}
+
+// For tests that need to commit, e.g. to use several connections.
+// Everything belonging to the clients created by the test is deleted afterwards,
+// even when it panics.
 #[allow(dead_code)]
 pub fn with_db_cleaner<F>(f: F) -> ()
 where
 	F: Fn(&PgConnection) -> (),
 {
+	// A failed test poisons the lock, the next one can still run
+	let _lock = CLEANER_LOCK
+		.lock()
+		.unwrap_or_else(|poisoned| poisoned.into_inner());
+
 	let conn = db_conn::get_test_connection();
 
+	let last_client_id = clients::table
+		.select(max(clients::id))
+		.first::<Option<i32>>(&conn)
+		.unwrap()
+		.unwrap_or(0);
+
+	let _cleaner = Cleaner {
+		conn:           &conn,
+		last_client_id: last_client_id,
+	};
+
 	f(&conn);
+}
 
-	models::client::Client::delete_all(&conn).unwrap();
-	models::user::User::delete_all(&conn).unwrap();
-	models::account::Account::delete_all(&conn).unwrap();
-	models::transaction::Transaction::delete_all(&conn).unwrap();
+struct Cleaner<'a> {
+	conn:           &'a PgConnection,
+	last_client_id: i32,
+}
+
+impl<'a> Cleaner<'a> {
+	fn clean(&self) -> Result<(), Error> {
+		let conn = self.conn;
+
+		let client_ids = clients::table
+			.select(clients::id)
+			.filter(clients::id.gt(self.last_client_id))
+			.load::<i32>(conn)?;
+
+		let user_ids = users::table
+			.select(users::id)
+			.filter(users::client_id.eq_any(&client_ids))
+			.load::<i32>(conn)?;
+
+		let account_ids = accounts::table
+			.select(accounts::id)
+			.filter(accounts::user_id.eq_any(&user_ids))
+			.load::<i32>(conn)?;
+
+		let scheduled_transaction_ids = scheduled_transactions::table
+			.select(scheduled_transactions::id)
+			.filter(scheduled_transactions::account_id.eq_any(&account_ids))
+			.load::<i32>(conn)?;
+
+		let journal_entry_ids = journal_entries::table
+			.select(journal_entries::id)
+			.filter(journal_entries::client_id.eq_any(&client_ids))
+			.load::<i32>(conn)?;
+
+		// Children first, because of the foreign keys
+		diesel::delete(scheduled_transaction_runs::table.filter(
+			scheduled_transaction_runs::scheduled_transaction_id.eq_any(&scheduled_transaction_ids),
+		))
+		.execute(conn)?;
+		diesel::delete(
+			scheduled_transactions::table
+				.filter(scheduled_transactions::account_id.eq_any(&account_ids)),
+		)
+		.execute(conn)?;
+		diesel::delete(
+			postings::table.filter(postings::journal_entry_id.eq_any(&journal_entry_ids)),
+		)
+		.execute(conn)?;
+		diesel::delete(
+			journal_entries::table.filter(journal_entries::client_id.eq_any(&client_ids)),
+		)
+		.execute(conn)?;
+		diesel::delete(
+			transaction_requests::table
+				.filter(transaction_requests::account_id.eq_any(&account_ids)),
+		)
+		.execute(conn)?;
+		diesel::delete(
+			withdrawal_rules::table.filter(withdrawal_rules::account_id.eq_any(&account_ids)),
+		)
+		.execute(conn)?;
+		diesel::delete(
+			matching_rules::table.filter(matching_rules::account_id.eq_any(&account_ids)),
+		)
+		.execute(conn)?;
+		diesel::delete(split_rules::table.filter(split_rules::account_id.eq_any(&account_ids)))
+			.execute(conn)?;
+		diesel::delete(goals::table.filter(goals::account_id.eq_any(&account_ids)))
+			.execute(conn)?;
+		diesel::delete(
+			interest_rates::table.filter(interest_rates::account_id.eq_any(&account_ids)),
+		)
+		.execute(conn)?;
+		diesel::delete(
+			interest_tiers::table.filter(interest_tiers::account_id.eq_any(&account_ids)),
+		)
+		.execute(conn)?;
+		diesel::delete(transactions::table.filter(transactions::account_id.eq_any(&account_ids)))
+			.execute(conn)?;
+		diesel::delete(categories::table.filter(categories::client_id.eq_any(&client_ids)))
+			.execute(conn)?;
+		diesel::delete(accounts::table.filter(accounts::id.eq_any(&account_ids))).execute(conn)?;
+		diesel::delete(invitations::table.filter(invitations::user_id.eq_any(&user_ids)))
+			.execute(conn)?;
+		diesel::delete(users::table.filter(users::id.eq_any(&user_ids))).execute(conn)?;
+		diesel::delete(clients::table.filter(clients::id.eq_any(&client_ids))).execute(conn)?;
+
+		Ok(())
+	}
+}
 
-	()
+impl<'a> Drop for Cleaner<'a> {
+	fn drop(&mut self) {
+		// Panicking again while unwinding would abort the test run
+		if let Err(error) = self.clean() {
+			eprintln!("Failed to clean up after the test: {}", error);
+		}
+	}
 }
 
 // Create models bottom up
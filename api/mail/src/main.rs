@@ -23,23 +23,42 @@ struct ConfirmEmailTemplate<'a> {
 	confirmation_url: &'a str,
 }
 
+#[derive(Template)]
+#[template(path = "request_deposit.html")]
+struct RequestDepositTemplate<'a> {
+	amount: &'a i64,
+	name:   &'a str,
+	memo:   &'a str,
+}
+
 #[derive(Template)]
 #[template(path = "request_withdrawal.html")]
 struct RequestWithdrawalTemplate<'a> {
 	amount: &'a i64,
 	name:   &'a str,
+	memo:   &'a str,
 }
 
 #[derive(Template)]
 #[template(path = "approve_transaction.html")]
 struct ApproveTransactionRequestTemplate<'a> {
 	amount: &'a i64,
+	kind:   &'a str,
 }
 
 #[derive(Template)]
 #[template(path = "reject_transaction.html")]
 struct RejectTransactionRequestTemplate<'a> {
 	amount: &'a i64,
+	kind:   &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "expire_transaction.html")]
+struct ExpireTransactionRequestTemplate<'a> {
+	amount: &'a i64,
+	kind:   &'a str,
+	name:   &'a str,
 }
 
 #[derive(Template)]
@@ -47,6 +66,9 @@ struct RejectTransactionRequestTemplate<'a> {
 struct AcknowledgeDepositTemplate<'a> {
 	amount:  &'a i64,
 	balance: &'a i64,
+	memo:    &'a str,
+	// Whole dollars, 0 without a match
+	matched: i64,
 }
 
 #[derive(Template)]
@@ -54,6 +76,31 @@ struct AcknowledgeDepositTemplate<'a> {
 struct AcknowledgeWithdrawalTemplate<'a> {
 	amount:  &'a i64,
 	balance: &'a i64,
+	memo:    &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "acknowledge_reversal.html")]
+struct AcknowledgeReversalTemplate<'a> {
+	amount:  &'a i64,
+	balance: &'a i64,
+	account: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "acknowledge_transfer.html")]
+struct AcknowledgeTransferTemplate<'a> {
+	amount: &'a i64,
+	from:   &'a str,
+	to:     &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "reach_goal.html")]
+struct ReachGoalTemplate<'a> {
+	target: &'a i64,
+	goal:   &'a str,
+	name:   &'a str,
 }
 
 #[derive(Template)]
@@ -108,11 +155,15 @@ fn generate_intermediate(email_kind: &EmailKind) -> Result<String, Error> {
 		EmailKind::AcknowledgeDeposit {
 			amount_in_cents,
 			balance_in_cents,
+			memo,
+			match_in_cents,
 			..
 		} => {
 			AcknowledgeDepositTemplate {
 				amount:  &(amount_in_cents / 100),
 				balance: &(balance_in_cents / 100),
+				memo:    memo_or_blank(memo),
+				matched: match_in_cents.unwrap_or(0) / 100,
 			}
 			.render()
 		},
@@ -120,20 +171,53 @@ fn generate_intermediate(email_kind: &EmailKind) -> Result<String, Error> {
 		EmailKind::AcknowledgeWithdrawal {
 			amount_in_cents,
 			balance_in_cents,
+			memo,
 			..
 		} => {
 			AcknowledgeWithdrawalTemplate {
 				amount:  &(amount_in_cents / 100),
 				balance: &(balance_in_cents / 100),
+				memo:    memo_or_blank(memo),
+			}
+			.render()
+		},
+
+		EmailKind::AcknowledgeReversal {
+			amount_in_cents,
+			balance_in_cents,
+			account_name,
+			..
+		} => {
+			AcknowledgeReversalTemplate {
+				amount:  &(amount_in_cents / 100),
+				balance: &(balance_in_cents / 100),
+				account: account_name,
+			}
+			.render()
+		},
+
+		EmailKind::AcknowledgeTransfer {
+			amount_in_cents,
+			from_account_name,
+			to_account_name,
+			..
+		} => {
+			AcknowledgeTransferTemplate {
+				amount: &(amount_in_cents / 100),
+				from:   from_account_name,
+				to:     to_account_name,
 			}
 			.render()
 		},
 
 		EmailKind::ApproveTransactionRequest {
-			amount_in_cents, ..
+			amount_in_cents,
+			request_kind,
+			..
 		} => {
 			ApproveTransactionRequestTemplate {
 				amount: &(amount_in_cents / 100),
+				kind:   request_kind,
 			}
 			.render()
 		},
@@ -142,6 +226,20 @@ fn generate_intermediate(email_kind: &EmailKind) -> Result<String, Error> {
 			confirmation_url, ..
 		} => ConfirmEmailTemplate { confirmation_url }.render(),
 
+		EmailKind::ExpireTransactionRequest {
+			amount_in_cents,
+			request_kind,
+			name,
+			..
+		} => {
+			ExpireTransactionRequestTemplate {
+				amount: &(amount_in_cents / 100),
+				kind: request_kind,
+				name,
+			}
+			.render()
+		},
+
 		EmailKind::Invite {
 			inviter_name,
 			invitation_url,
@@ -154,23 +252,56 @@ fn generate_intermediate(email_kind: &EmailKind) -> Result<String, Error> {
 			.render()
 		},
 
+		EmailKind::ReachGoal {
+			target_in_cents,
+			goal_name,
+			name,
+			..
+		} => {
+			ReachGoalTemplate {
+				target: &(target_in_cents / 100),
+				goal: goal_name,
+				name,
+			}
+			.render()
+		},
+
+		EmailKind::RequestDeposit {
+			amount_in_cents,
+			name,
+			memo,
+			..
+		} => {
+			RequestDepositTemplate {
+				amount: &(amount_in_cents / 100),
+				name,
+				memo: memo_or_blank(memo),
+			}
+			.render()
+		},
+
 		EmailKind::RequestWithdrawal {
 			amount_in_cents,
 			name,
+			memo,
 			..
 		} => {
 			RequestWithdrawalTemplate {
 				amount: &(amount_in_cents / 100),
 				name,
+				memo: memo_or_blank(memo),
 			}
 			.render()
 		},
 
 		EmailKind::RejectTransactionRequest {
-			amount_in_cents, ..
+			amount_in_cents,
+			request_kind,
+			..
 		} => {
 			RejectTransactionRequestTemplate {
 				amount: &(amount_in_cents / 100),
+				kind:   request_kind,
 			}
 			.render()
 		},
@@ -183,6 +314,11 @@ fn generate_intermediate(email_kind: &EmailKind) -> Result<String, Error> {
 	result.map_err(|e| format_err!("{}", e))
 }
 
+// Templates leave out a blank memo
+fn memo_or_blank(memo: &Option<String>) -> &str {
+	memo.as_ref().map(String::as_str).unwrap_or("")
+}
+
 fn generate_html(intermediate: &str) -> Result<String, Error> {
 	Ok(intermediate.to_owned())
 }
@@ -237,8 +373,13 @@ fn subject_for_email(email_kind: &EmailKind) -> String {
 	match email_kind {
 		EmailKind::AcknowledgeDeposit { .. } => "Successful deposit".to_owned(),
 		EmailKind::AcknowledgeWithdrawal { .. } => "Successful withdrawal".to_owned(),
+		EmailKind::AcknowledgeReversal { .. } => "A transaction was reversed".to_owned(),
+		EmailKind::AcknowledgeTransfer { .. } => "Successful transfer".to_owned(),
 		EmailKind::ConfirmEmail { .. } => "Confirm your email".to_owned(),
+		EmailKind::ExpireTransactionRequest { .. } => "A request has expired".to_owned(),
 		EmailKind::Invite { .. } => "You have been invited to SaveUp".to_owned(),
+		EmailKind::ReachGoal { .. } => "A savings goal has been reached".to_owned(),
+		EmailKind::RequestDeposit { .. } => "Deposit request".to_owned(),
 		EmailKind::RequestWithdrawal { .. } => "Withdrawal request".to_owned(),
 		EmailKind::ApproveTransactionRequest { .. } => "Your request has been approved".to_owned(),
 		EmailKind::RejectTransactionRequest { .. } => "Your request".to_owned(),
@@ -9,27 +9,58 @@ pub enum EmailKind {
 	AcknowledgeDeposit {
 		amount_in_cents: i64,
 		balance_in_cents: i64,
+		memo: Option<String>,
+		match_in_cents: Option<i64>,
 	},
 	AcknowledgeWithdrawal {
 		amount_in_cents: i64,
 		balance_in_cents: i64,
+		memo: Option<String>,
+	},
+	AcknowledgeReversal {
+		amount_in_cents: i64,
+		balance_in_cents: i64,
+		account_name: String,
+	},
+	AcknowledgeTransfer {
+		amount_in_cents: i64,
+		from_account_name: String,
+		to_account_name: String,
 	},
 	ApproveTransactionRequest {
 		amount_in_cents: i64,
+		request_kind: String,
 	},
 	ConfirmEmail {
 		confirmation_url: String,
 	},
+	ExpireTransactionRequest {
+		name: String,
+		amount_in_cents: i64,
+		request_kind: String,
+	},
 	Invite {
 		inviter_name: String,
 		invitation_url: String,
 	},
+	ReachGoal {
+		name: String,
+		goal_name: String,
+		target_in_cents: i64,
+	},
+	RequestDeposit {
+		name: String,
+		amount_in_cents: i64,
+		memo: Option<String>,
+	},
 	RequestWithdrawal {
 		name: String,
 		amount_in_cents: i64,
+		memo: Option<String>,
 	},
 	RejectTransactionRequest {
 		amount_in_cents: i64,
+		request_kind: String,
 	},
 	ResetPassword {
 		reset_url: String,